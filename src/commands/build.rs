@@ -1,13 +1,12 @@
-use crate::config::get_workflow_config;
+use crate::config::get_workflow;
 use crate::core::trust::ensure_trusted;
-use crate::core::Result;
+use crate::core::{output, Result};
 use crate::integrations::taskfile;
-use colored::*;
 use std::env;
 
 /// Execute build workflow
 pub async fn execute() -> Result<()> {
-    println!("{}", "🔨 Building project...".cyan().bold());
+    output::banner("🔨 Building project...");
 
     // Check trust before executing
     let current_dir = env::current_dir()?;
@@ -20,15 +19,15 @@ pub async fn execute() -> Result<()> {
     }
 
     // Get workflow config with fallback chain
-    if let Some(workflow_content) = get_workflow_config("build")? {
+    if let Some(workflow) = get_workflow("build")? {
         // Execute via taskfile with the workflow content in interactive mode
-        taskfile::execute_workflow_task_interactive("build", &workflow_content).await?;
+        taskfile::execute_workflow_task_interactive("build", &workflow).await?;
     } else {
         return Err(crate::core::RazdError::command(
             "No build workflow found. Try running 'razd init --config' to create a Razdfile.yml",
         ));
     }
 
-    println!("{}", "✅ Build completed successfully".green().bold());
+    output::done("✅ Build completed successfully");
     Ok(())
 }
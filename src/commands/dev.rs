@@ -1,13 +1,12 @@
-use crate::config::get_workflow_config;
+use crate::config::get_workflow;
 use crate::core::trust::ensure_trusted;
-use crate::core::Result;
+use crate::core::{output, Result};
 use crate::integrations::taskfile;
-use colored::*;
 use std::env;
 
 /// Execute development workflow
 pub async fn execute() -> Result<()> {
-    println!("{}", "🚀 Starting development workflow...".cyan().bold());
+    output::banner("🚀 Starting development workflow...");
 
     // Check trust before executing
     let current_dir = env::current_dir()?;
@@ -20,15 +19,15 @@ pub async fn execute() -> Result<()> {
     }
 
     // Get workflow config with fallback chain
-    if let Some(workflow_content) = get_workflow_config("dev")? {
+    if let Some(workflow) = get_workflow("dev")? {
         // Execute via taskfile with the workflow content in interactive mode
-        taskfile::execute_workflow_task_interactive("dev", &workflow_content).await?;
+        taskfile::execute_workflow_task_interactive("dev", &workflow).await?;
     } else {
         return Err(crate::core::RazdError::command(
             "No development workflow found. Try running 'razd init --config' to create a Razdfile.yml"
         ));
     }
 
-    println!("{}", "✅ Development workflow completed".green().bold());
+    output::done("✅ Development workflow completed");
     Ok(())
 }
@@ -0,0 +1,90 @@
+use crate::core::{output, RazdError, Result};
+use crate::integrations::{mise, tools};
+use std::env;
+
+/// Tools razd runs itself: name, whether razd needs it, and where to get it
+const RAZD_TOOLS: [(&str, bool, &str); 3] = [
+    ("git", true, "https://git-scm.com/downloads"),
+    ("mise", true, "https://mise.jdx.dev/getting-started.html"),
+    ("task", false, "https://taskfile.dev/installation/"),
+];
+
+/// Execute the `razd doctor` command: show where the tools razd and the project use are found
+/// Fails when a tool razd needs is missing; missing project tools are only warnings.
+pub async fn execute() -> Result<()> {
+    output::banner("🩺 Checking tools...");
+
+    let current_dir = env::current_dir()?;
+    let project_tools: Vec<(String, String)> = mise::planned_tools(&current_dir)
+        .into_iter()
+        .filter_map(|spec| {
+            let (name, version) = spec.split_once('@')?;
+            // Tools of other backends (npm:, cargo:, ...) install binaries under other names
+            (!name.contains(':')).then(|| (name.to_string(), version.to_string()))
+        })
+        .collect();
+    let width = RAZD_TOOLS
+        .iter()
+        .map(|(name, ..)| name.len())
+        .chain(project_tools.iter().map(|(name, _)| name.len()))
+        .max()
+        .unwrap_or(0);
+
+    let mut missing = None;
+    for (name, required, help) in RAZD_TOOLS {
+        match describe(name) {
+            Some(found) => output::success(&format!("{:<width$}  {}", name, found)),
+            None if required => {
+                output::warning(&format!("{:<width$}  not found, see {}", name, help));
+                missing.get_or_insert((name, help));
+            }
+            None => output::info(&format!(
+                "{:<width$}  not found; only needed with --external-task, razd installs it with mise",
+                name
+            )),
+        }
+    }
+
+    if !project_tools.is_empty() {
+        output::step("Project tools");
+        for (name, version) in &project_tools {
+            match describe(name) {
+                Some(found) => {
+                    output::success(&format!("{:<width$}  {} (wants {})", name, found, version))
+                }
+                None => output::warning(&format!(
+                    "{:<width$}  not found (wants {}), run `razd install`",
+                    name, version
+                )),
+            }
+        }
+    }
+
+    match (tools::disk_cache_ttl(), tools::disk_cache_path()) {
+        (Some(ttl), Some(path)) => output::info(&format!(
+            "Tool cache: {} (entries kept for {})",
+            path.display(),
+            crate::core::duration::HumanDuration(ttl)
+        )),
+        _ => output::info(
+            "Tool cache: off (set RAZD_TOOL_CACHE_TTL, e.g. 1h, to keep lookups between runs)",
+        ),
+    }
+
+    match missing {
+        Some((tool, help)) => Err(RazdError::missing_tool(tool, help)),
+        None => Ok(()),
+    }
+}
+
+/// `<path> (<version>)` of a tool, or None when it is not found
+fn describe(name: &str) -> Option<String> {
+    let tool = tools::resolve(name)?;
+    let version = tools::version(name).unwrap_or_else(|| "unknown version".to_string());
+    Some(format!(
+        "{} ({}, from {})",
+        tool.path.display(),
+        version,
+        tool.source.describe()
+    ))
+}
@@ -1,4 +1,4 @@
-use crate::config::get_workflow_config;
+use crate::config::get_workflow;
 use crate::core::trust::ensure_trusted;
 use crate::core::{output, Result};
 use crate::integrations::{mise, taskfile};
@@ -19,8 +19,8 @@ pub async fn execute() -> Result<()> {
     }
 
     // Execute install workflow (with fallback chain)
-    if let Some(workflow_content) = get_workflow_config("install")? {
-        taskfile::execute_workflow_task("install", &workflow_content).await?;
+    if let Some(workflow) = get_workflow("install")? {
+        taskfile::execute_workflow_task("install", &workflow).await?;
     } else {
         // Fallback to legacy behavior
         output::warning("No install workflow found, falling back to mise install");
@@ -1,8 +1,15 @@
-use crate::config::razdfile::RazdfileConfig;
+use crate::config::razdfile::{resolve_razdfile_path, RazdfileConfig, TaskConfig};
+use crate::config::Workflow;
+use crate::core::events::{self, Event};
+use crate::core::trust::TrustStore;
 use crate::core::{output, Result};
+use crate::executor::checks::TaskChecks;
+use crate::executor::vars::{self, VarLayers};
+use crate::executor::{self, fingerprint, template, Executor, ExecutorOptions};
 use colored::*;
 use serde::Serialize;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 /// Helper function to skip serializing false boolean values for cleaner JSON
 fn is_false(b: &bool) -> bool {
@@ -33,6 +40,31 @@ struct TaskInfo {
     location: TaskLocation,
     #[serde(skip_serializing_if = "is_false")]
     internal: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    up_to_date: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<StatusInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    preconditions: Vec<PreconditionInfo>,
+    /// A precondition currently fails, so running the task would abort
+    #[serde(skip_serializing_if = "is_false")]
+    blocked: bool,
+}
+
+/// `status:` commands of a task; `satisfied` is null when checks were not run
+#[derive(Serialize)]
+struct StatusInfo {
+    commands: Vec<String>,
+    satisfied: Option<bool>,
+}
+
+/// A precondition of a task; `satisfied` is null when checks were not run
+#[derive(Serialize)]
+struct PreconditionInfo {
+    sh: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    msg: Option<String>,
+    satisfied: Option<bool>,
 }
 
 /// Find the absolute path to Razdfile.yml or custom config file
@@ -100,12 +132,48 @@ fn estimate_task_line(task_name: &str, razdfile_path: &std::path::Path) -> Resul
     Ok(1)
 }
 
-pub async fn execute(list_all: bool, json: bool, custom_path: Option<PathBuf>) -> Result<()> {
+/// Check whether the project in `dir` is trusted
+fn is_trusted(dir: &Path) -> bool {
+    TrustStore::load()
+        .map(|store| store.is_trusted(dir))
+        .unwrap_or(false)
+}
+
+/// Print the task list as JSON, or as a `task_list` event with `--format json`
+fn print_task_list(output: &TaskListOutput) {
+    if events::enabled() {
+        events::emit(Event::TaskList {
+            list: serde_json::to_value(output).unwrap_or_default(),
+        });
+        return;
+    }
+    println!(
+        "{}",
+        serde_json::to_string_pretty(output).unwrap_or_else(|_| r#"{"tasks":[]}"#.to_string())
+    );
+}
+
+/// Execute the `razd list` command
+/// `check` runs the `status` and `preconditions` commands of every task (trusted projects only);
+/// otherwise only `sources` fingerprints decide whether a task is shown as up to date.
+pub async fn execute(
+    list_all: bool,
+    json: bool,
+    check: bool,
+    custom_path: Option<PathBuf>,
+) -> Result<()> {
+    let json = json || events::enabled();
     // Load Razdfile.yml
-    let razdfile = match RazdfileConfig::load_with_path(custom_path.clone())? {
+    let loaded = match resolve_razdfile_path(custom_path.clone())? {
+        Some(path) => RazdfileConfig::load_with_includes(path)?,
+        None => None,
+    };
+    let razdfile = match loaded {
         Some(config) => config,
         None => {
-            if json {
+            if events::enabled() {
+                // Reported by the error event razd exits with
+            } else if json {
                 // Output error as JSON
                 let error_json = serde_json::json!({
                     "error": "Razdfile.yml not found in current directory"
@@ -124,14 +192,32 @@ pub async fn execute(list_all: bool, json: bool, custom_path: Option<PathBuf>) -
         }
     };
 
+    // Tasks run in the current directory, so their fingerprints are keyed by it
+    let working_dir = std::env::current_dir().ok();
+
     // Extract tasks based on list_all flag
-    let tasks: Vec<(String, String, bool)> = razdfile
+    let tasks: Vec<(String, String, bool, bool)> = razdfile
         .tasks
         .iter()
         .filter(|(_, config)| list_all || !config.internal)
         .map(|(name, config)| {
-            let desc = config.desc.clone().unwrap_or_default();
-            (name.clone(), desc, config.internal)
+            // Listing never runs `sh:` vars; they show up as `$(command)`
+            let layers = VarLayers {
+                global: razdfile.vars.as_ref(),
+                task: config.vars.as_ref(),
+                call: None,
+                cli: &[],
+            };
+            let vars = vars::describe(&layers);
+            let desc = template::render(config.desc.as_deref().unwrap_or_default(), &vars);
+            let up_to_date = working_dir
+                .as_ref()
+                .map(|dir| {
+                    let task_dir = executor::task_dir(dir, config, &vars);
+                    fingerprint::is_up_to_date(&task_dir, name, config).unwrap_or(false)
+                })
+                .unwrap_or(false);
+            (name.clone(), desc, config.internal, up_to_date)
         })
         .collect();
 
@@ -141,15 +227,10 @@ pub async fn execute(list_all: bool, json: bool, custom_path: Option<PathBuf>) -
             let razdfile_path = find_razdfile_path(custom_path.as_ref()).ok();
             let location = razdfile_path.map(|p| p.to_string_lossy().to_string());
 
-            let output = TaskListOutput {
+            print_task_list(&TaskListOutput {
                 tasks: vec![],
                 location,
-            };
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&output)
-                    .unwrap_or_else(|_| r#"{"tasks":[]}"#.to_string())
-            );
+            });
         } else {
             println!("No tasks found in Razdfile.yml");
         }
@@ -158,6 +239,37 @@ pub async fn execute(list_all: bool, json: bool, custom_path: Option<PathBuf>) -
 
     // Tasks are already in the order they appear in Razdfile.yml (IndexMap preserves order)
 
+    // Checks run commands from the Razdfile, so only trusted projects are evaluated on request
+    let mut checks = HashMap::new();
+    match working_dir {
+        Some(ref dir) if check && is_trusted(dir) => {
+            let workflow = Workflow {
+                config: razdfile.clone(),
+                source: Some(find_razdfile_path(custom_path.as_ref())?),
+            };
+            let executor = Executor::new(workflow, dir.clone(), ExecutorOptions::default());
+            for (name, _, _, _) in &tasks {
+                if let Ok(result) = executor.check_task(name).await {
+                    checks.insert(name.clone(), result);
+                }
+            }
+        }
+        Some(_) if check => output::warning(
+            "Not running status and precondition commands of an untrusted project; run `razd trust` first",
+        ),
+        _ => {}
+    }
+    let tasks: Vec<(String, String, bool, bool)> = tasks
+        .into_iter()
+        .map(|(name, desc, internal, fingerprint)| {
+            let up_to_date = razdfile
+                .tasks
+                .get(&name)
+                .is_some_and(|task| is_up_to_date(fingerprint, task, checks.get(&name)));
+            (name, desc, internal, up_to_date)
+        })
+        .collect();
+
     if json {
         // Get absolute path to Razdfile.yml for location metadata
         let razdfile_path = find_razdfile_path(custom_path.as_ref())?;
@@ -166,8 +278,19 @@ pub async fn execute(list_all: bool, json: bool, custom_path: Option<PathBuf>) -
         // Output as JSON with enhanced taskfile-compatible format
         let task_infos: Vec<TaskInfo> = tasks
             .iter()
-            .map(|(name, desc, internal)| {
-                let line = estimate_task_line(name, &razdfile_path).unwrap_or(1);
+            .map(|(name, desc, internal, up_to_date)| {
+                let config = razdfile.tasks.get(name);
+                let checked = checks.get(name);
+                // Included tasks point at the file they were defined in, under their own name
+                let source = razdfile.tasks.get(name).and_then(|t| t.source.as_ref());
+                let (taskfile, local_name) = match source {
+                    Some(path) => (
+                        path.to_string_lossy().to_string(),
+                        name.rsplit(':').next().unwrap_or(name),
+                    ),
+                    None => (razdfile_path_str.clone(), name.as_str()),
+                };
+                let line = estimate_task_line(local_name, Path::new(&taskfile)).unwrap_or(1);
 
                 TaskInfo {
                     name: name.clone(),
@@ -176,29 +299,44 @@ pub async fn execute(list_all: bool, json: bool, custom_path: Option<PathBuf>) -
                     summary: String::new(), // Placeholder for future feature
                     aliases: Vec::new(),    // Placeholder for future feature
                     location: TaskLocation {
-                        taskfile: razdfile_path_str.clone(),
+                        taskfile,
                         line,
                         column: 3, // Tasks are typically indented 2 spaces (column 3)
                     },
                     internal: *internal,
+                    up_to_date: *up_to_date,
+                    status: config.and_then(|task| task.status.clone()).map(|commands| {
+                        StatusInfo {
+                            commands,
+                            satisfied: checked.and_then(|c| c.status),
+                        }
+                    }),
+                    preconditions: config
+                        .and_then(|task| task.preconditions.as_ref())
+                        .into_iter()
+                        .flatten()
+                        .enumerate()
+                        .map(|(index, precondition)| PreconditionInfo {
+                            sh: precondition.command().to_string(),
+                            msg: precondition.message().map(String::from),
+                            satisfied: checked.and_then(|c| c.preconditions.get(index)).copied(),
+                        })
+                        .collect(),
+                    blocked: checked.is_some_and(|c| c.blocked()),
                 }
             })
             .collect();
 
-        let output = TaskListOutput {
+        print_task_list(&TaskListOutput {
             tasks: task_infos,
             location: Some(razdfile_path_str),
-        };
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&output).unwrap_or_else(|_| r#"{"tasks":[]}"#.to_string())
-        );
+        });
     } else {
         // Output as text
         // Calculate maximum task name length for proper alignment
         let max_name_len = tasks
             .iter()
-            .map(|(name, _, _)| name.len())
+            .map(|(name, _, _, _)| name.len())
             .max()
             .unwrap_or(0);
         let column_width = max_name_len + 1; // +1 for the colon
@@ -207,15 +345,23 @@ pub async fn execute(list_all: bool, json: bool, custom_path: Option<PathBuf>) -
         println!("{}", "task: Available tasks for this project:".bold());
 
         // Display each task with proper formatting
-        for (name, desc, _) in tasks {
+        for (name, desc, _, up_to_date) in tasks {
             let formatted_name = format!("{}:", name);
+            let status = if checks.get(&name).is_some_and(|c| c.blocked()) {
+                format!(" {}", "(blocked)".red())
+            } else if up_to_date {
+                format!(" {}", "(up to date)".green())
+            } else {
+                String::new()
+            };
             if desc.is_empty() {
-                println!("* {}", formatted_name.cyan());
+                println!("* {}{}", formatted_name.cyan(), status);
             } else {
                 println!(
-                    "* {:<width$} {}",
+                    "* {:<width$} {}{}",
                     formatted_name.cyan(),
                     desc,
+                    status,
                     width = column_width
                 );
             }
@@ -225,6 +371,16 @@ pub async fn execute(list_all: bool, json: bool, custom_path: Option<PathBuf>) -
     Ok(())
 }
 
+/// Whether running the task would skip it, as the executor decides: its `sources` are
+/// unchanged (`fingerprint`) and its `status` commands succeed. Without `checks` the
+/// `status` commands are unknown and only the fingerprint counts.
+fn is_up_to_date(fingerprint: bool, task: &TaskConfig, checks: Option<&TaskChecks>) -> bool {
+    match checks.and_then(|c| c.status) {
+        Some(satisfied) => satisfied && (fingerprint || task.sources.is_none()),
+        None => fingerprint,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::config::razdfile::{RazdfileConfig, TaskConfig};
@@ -244,6 +400,7 @@ mod tests {
                 vars: None,
                 silent: None,
                 platforms: None,
+                ..Default::default()
             },
         );
         tasks.insert(
@@ -257,6 +414,7 @@ mod tests {
                 vars: None,
                 silent: None,
                 platforms: None,
+                ..Default::default()
             },
         );
 
@@ -265,6 +423,10 @@ mod tests {
             mise: None,
             env: None,
             vars: None,
+            dotenv: None,
+            includes: None,
+            output: None,
+            version_files: None,
             tasks,
         };
 
@@ -302,6 +464,7 @@ mod tests {
                 vars: None,
                 silent: None,
                 platforms: None,
+                ..Default::default()
             },
         );
         tasks.insert(
@@ -315,6 +478,7 @@ mod tests {
                 vars: None,
                 silent: None,
                 platforms: None,
+                ..Default::default()
             },
         );
 
@@ -355,6 +519,10 @@ mod tests {
                     column: 3,
                 },
                 internal: false,
+                up_to_date: false,
+                status: None,
+                preconditions: Vec::new(),
+                blocked: false,
             },
             TaskInfo {
                 name: "test".to_string(),
@@ -368,6 +536,10 @@ mod tests {
                     column: 3,
                 },
                 internal: false,
+                up_to_date: false,
+                status: None,
+                preconditions: Vec::new(),
+                blocked: false,
             },
         ];
 
@@ -402,6 +574,10 @@ mod tests {
                     column: 3,
                 },
                 internal: false,
+                up_to_date: false,
+                status: None,
+                preconditions: Vec::new(),
+                blocked: false,
             },
             TaskInfo {
                 name: "internal".to_string(),
@@ -415,6 +591,10 @@ mod tests {
                     column: 3,
                 },
                 internal: true,
+                up_to_date: false,
+                status: None,
+                preconditions: Vec::new(),
+                blocked: false,
             },
         ];
 
@@ -448,6 +628,10 @@ mod tests {
                 column: 3,
             },
             internal: false,
+            up_to_date: false,
+            status: None,
+            preconditions: Vec::new(),
+            blocked: false,
         }];
 
         let output = TaskListOutput {
@@ -493,6 +677,10 @@ mod tests {
                 column: 3,
             },
             internal: false,
+            up_to_date: false,
+            status: None,
+            preconditions: Vec::new(),
+            blocked: false,
         };
 
         assert_eq!(task.name, task.task);
@@ -529,6 +717,10 @@ mod tests {
                 column: 3,
             },
             internal: false, // Should be omitted from JSON
+            up_to_date: false,
+            status: None,
+            preconditions: Vec::new(),
+            blocked: false,
         }];
 
         let output = TaskListOutput {
@@ -541,4 +733,30 @@ mod tests {
         // When internal is false, it should not appear in JSON
         assert!(!json.contains("\"internal\""));
     }
+
+    #[test]
+    fn test_up_to_date_field_serialized_when_true() {
+        use super::{TaskInfo, TaskLocation};
+
+        let task = TaskInfo {
+            name: "build".to_string(),
+            task: "build".to_string(),
+            desc: "".to_string(),
+            summary: String::new(),
+            aliases: Vec::new(),
+            location: TaskLocation {
+                taskfile: "Razdfile.yml".to_string(),
+                line: 1,
+                column: 3,
+            },
+            internal: false,
+            up_to_date: true,
+            status: None,
+            preconditions: Vec::new(),
+            blocked: false,
+        };
+
+        let parsed = serde_json::to_value(&task).unwrap();
+        assert_eq!(parsed["up_to_date"], true);
+    }
 }
@@ -0,0 +1,99 @@
+use crate::core::{output, RazdError, Result};
+use crate::executor::logs;
+use colored::*;
+use std::env;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How often `--follow` checks the log for new output
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Execute the `razd logs` command: print the logs of the last runs of a task
+/// With `follow` the newest log keeps being printed as it grows, switching to
+/// new runs as they start, until interrupted.
+pub async fn execute(task_name: &str, last: usize, follow: bool) -> Result<()> {
+    let working_dir = env::current_dir()?;
+    let logs_dir = logs::project_logs_dir(&working_dir)?;
+    let runs = logs::list_runs(&logs_dir, task_name)?;
+
+    if runs.is_empty() && !follow {
+        return Err(RazdError::config(format!(
+            "No logs for task '{}' in this project (stored in {})",
+            task_name,
+            logs::task_logs_dir(&logs_dir, task_name).display()
+        )));
+    }
+
+    let shown = &runs[runs.len().saturating_sub(last.max(1))..];
+    let mut offset = 0;
+    for path in shown {
+        if shown.len() > 1 || follow {
+            print_header(path);
+        }
+        offset = print_from(path, 0)?;
+    }
+
+    if follow {
+        follow_runs(&logs_dir, task_name, shown.last().cloned(), offset).await?;
+    }
+
+    Ok(())
+}
+
+/// Keep printing new output of the newest run, switching to newer runs as they start
+async fn follow_runs(
+    logs_dir: &Path,
+    task_name: &str,
+    mut current: Option<PathBuf>,
+    mut offset: u64,
+) -> Result<()> {
+    if current.is_none() {
+        output::info(&format!("Waiting for task '{}' to run...", task_name));
+    }
+
+    loop {
+        if let Some(ref path) = current {
+            offset = print_from(path, offset)?;
+        }
+
+        let newest = logs::list_runs(logs_dir, task_name)?.pop();
+        if newest.is_some() && newest != current {
+            // Print what the previous run wrote since the last poll before switching
+            if let Some(ref path) = current {
+                print_from(path, offset)?;
+            }
+            current = newest;
+            offset = 0;
+            if let Some(ref path) = current {
+                print_header(path);
+            }
+            continue;
+        }
+
+        tokio::time::sleep(FOLLOW_POLL_INTERVAL).await;
+    }
+}
+
+/// Separate runs like `tail` does with several files
+fn print_header(path: &Path) {
+    println!("{}", format!("==> {} <==", path.display()).dimmed());
+}
+
+/// Print a log file from `offset` on and return the offset of its end
+/// A log removed in the meantime (old runs are pruned) has nothing more to print
+fn print_from(path: &Path, offset: u64) -> Result<u64> {
+    let Ok(mut file) = File::open(path) else {
+        return Ok(offset);
+    };
+    file.seek(SeekFrom::Start(offset))?;
+    let mut content = Vec::new();
+    let read = file.read_to_end(&mut content)?;
+
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(&content)?;
+    stdout.flush()?;
+
+    Ok(offset + read as u64)
+}
@@ -1,8 +1,11 @@
 pub mod build;
 pub mod dev;
+pub mod doctor;
 pub mod install;
 pub mod list;
+pub mod logs;
 pub mod run;
 pub mod setup;
+pub mod sync;
 pub mod trust;
 pub mod up;
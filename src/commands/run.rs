@@ -1,16 +1,45 @@
 use crate::core::trust::ensure_trusted;
-use crate::core::Result;
-use crate::integrations::taskfile;
-use colored::*;
+use crate::core::{output, RazdError, Result};
+use crate::executor::vars;
+use crate::integrations::{process, taskfile};
 use std::env;
 use std::path::PathBuf;
 
-/// Execute a custom task defined in Razdfile.yml
-pub async fn execute(task_name: &str, args: &[String], custom_path: Option<PathBuf>) -> Result<()> {
-    println!(
-        "{}",
-        format!("🚀 Running task '{}'...", task_name).cyan().bold()
-    );
+/// Options for running several tasks in one invocation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunOptions {
+    /// Run the tasks concurrently instead of one after another
+    pub parallel: bool,
+    /// Keep going after a task fails and report every failure at the end
+    pub continue_on_error: bool,
+    /// Rerun the tasks whenever their sources change
+    pub watch: bool,
+}
+
+/// Execute one or more custom tasks defined in Razdfile.yml
+/// `KEY=value` entries among the task names set variables for every task;
+/// arguments after `--` are passed to the last task
+pub async fn execute(
+    task_names: &[String],
+    args: &[String],
+    options: RunOptions,
+    custom_path: Option<PathBuf>,
+) -> Result<()> {
+    let (task_names, cli_vars) = split_cli_vars(task_names);
+    if task_names.is_empty() {
+        return Err(RazdError::config(
+            "Task name required; KEY=value arguments only set variables",
+        ));
+    }
+    let task_names = task_names.as_slice();
+
+    let label = describe_tasks(task_names);
+    let mode = if options.parallel && task_names.len() > 1 {
+        " in parallel"
+    } else {
+        ""
+    };
+    output::banner(&format!("🚀 Running {}{}...", label, mode));
 
     // Check trust before executing
     let current_dir = env::current_dir()?;
@@ -23,27 +52,116 @@ pub async fn execute(task_name: &str, args: &[String], custom_path: Option<PathB
     }
 
     // Get workflow config with fallback chain (with custom path support)
-    if let Some(workflow_content) =
-        crate::config::get_workflow_config_with_path(task_name, custom_path)?
+    // The first task decides which workflow is used; the others must exist in it
+    let workflow = crate::config::get_workflow_with_path(&task_names[0], custom_path)?
+        .ok_or_else(|| task_not_found(&task_names[0]))?;
+    if let Some(missing) = task_names
+        .iter()
+        .find(|name| !workflow.config.tasks.contains_key(*name))
     {
-        // Execute via taskfile with the workflow content and CLI arguments
-        if args.is_empty() {
-            taskfile::execute_workflow_task_interactive(task_name, &workflow_content).await?;
-        } else {
-            taskfile::execute_workflow_task_with_args(task_name, &workflow_content, args).await?;
-        }
+        return Err(task_not_found(missing));
+    }
+
+    if options.watch {
+        return taskfile::watch_workflow_tasks(
+            task_names,
+            &workflow,
+            args,
+            &cli_vars,
+            options.parallel,
+            options.continue_on_error,
+        )
+        .await;
+    }
+
+    if task_names.len() == 1 && args.is_empty() && cli_vars.is_empty() {
+        taskfile::execute_workflow_task_interactive(&task_names[0], &workflow).await?;
     } else {
-        return Err(crate::core::RazdError::command(format!(
-            "Task '{}' not found in Razdfile.yml. Try running 'task --list' to see available tasks",
-            task_name
-        )));
-    }
-
-    println!(
-        "{}",
-        format!("✅ Task '{}' completed successfully", task_name)
-            .green()
-            .bold()
-    );
+        taskfile::execute_workflow_tasks(
+            task_names,
+            &workflow,
+            args,
+            &cli_vars,
+            options.parallel,
+            options.continue_on_error,
+        )
+        .await?;
+    }
+
+    if process::dry_run() {
+        return Ok(());
+    }
+
+    output::done(&format!("✅ {} completed successfully", capitalize(&label)));
     Ok(())
 }
+
+/// Separate `KEY=value` variables from the task names they were given with
+fn split_cli_vars(args: &[String]) -> (Vec<String>, Vec<(String, String)>) {
+    let mut task_names = Vec::new();
+    let mut cli_vars = Vec::new();
+    for arg in args {
+        match vars::parse_cli_var(arg) {
+            Some(var) => cli_vars.push(var),
+            None => task_names.push(arg.clone()),
+        }
+    }
+    (task_names, cli_vars)
+}
+
+fn task_not_found(task_name: &str) -> crate::core::RazdError {
+    crate::core::RazdError::command(format!(
+        "Task '{}' not found in Razdfile.yml. Try running 'razd list' to see available tasks",
+        task_name
+    ))
+}
+
+/// Human-readable list of task names, e.g. "task 'lint'" or "tasks 'lint', 'test'"
+fn describe_tasks(task_names: &[String]) -> String {
+    let quoted: Vec<String> = task_names.iter().map(|n| format!("'{}'", n)).collect();
+    if quoted.len() == 1 {
+        format!("task {}", quoted[0])
+    } else {
+        format!("tasks {}", quoted.join(", "))
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_tasks() {
+        assert_eq!(describe_tasks(&["lint".to_string()]), "task 'lint'");
+        assert_eq!(
+            describe_tasks(&["lint".to_string(), "test".to_string()]),
+            "tasks 'lint', 'test'"
+        );
+        assert_eq!(capitalize("task 'lint'"), "Task 'lint'");
+    }
+
+    #[test]
+    fn test_split_cli_vars() {
+        let args: Vec<String> = ["build", "VERSION=1.2", "api:test", "MODE="]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        let (task_names, cli_vars) = split_cli_vars(&args);
+        assert_eq!(task_names, vec!["build", "api:test"]);
+        assert_eq!(
+            cli_vars,
+            vec![
+                ("VERSION".to_string(), "1.2".to_string()),
+                ("MODE".to_string(), String::new())
+            ]
+        );
+    }
+}
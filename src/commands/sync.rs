@@ -0,0 +1,112 @@
+use crate::config::mise_merge::{Change, Side};
+use crate::config::mise_sync::{MiseSyncManager, SyncResult};
+use crate::config::razdfile::resolve_razdfile_path;
+use crate::core::events::{self, Event};
+use crate::core::{output, RazdError, Result};
+use colored::*;
+use std::env;
+use std::ffi::OsStr;
+use std::path::PathBuf;
+
+/// Execute the `razd sync` command: sync Razdfile.yml and mise.toml now
+/// With `--check` or `--diff` nothing is written; `--check` fails when the files disagree.
+/// `custom_path` is the `--razdfile`/`--taskfile` given; its directory is the project synced.
+pub async fn execute(
+    to: Option<&str>,
+    check: bool,
+    diff: bool,
+    custom_path: Option<PathBuf>,
+) -> Result<()> {
+    let project_root = project_root(custom_path)?;
+    let target = to.and_then(Side::parse);
+
+    // An explicit sync runs even with --no-sync
+    let mut config = crate::config::sync_config();
+    config.no_sync = false;
+    let manager = MiseSyncManager::new(project_root, config);
+
+    if check || diff {
+        let planned = manager.planned_changes(target)?;
+        if diff {
+            for (file, changes) in &planned {
+                print_diff(file, changes);
+            }
+        }
+        if manager.in_sync()? {
+            output::success("Razdfile.yml and mise.toml are in sync");
+            return Ok(());
+        }
+        if check {
+            let entries: usize = planned.iter().map(|(_, changes)| changes.len()).sum();
+            return Err(RazdError::config(format!(
+                "Razdfile.yml and mise.toml are out of sync ({} {} would change). Run `razd sync` to update them",
+                entries,
+                if entries == 1 { "entry" } else { "entries" }
+            )));
+        }
+        return Ok(());
+    }
+
+    let result = match target {
+        Some(target) => manager.sync_to(target)?,
+        None => manager.check_and_sync_if_needed()?,
+    };
+    events::emit_if_enabled(|| Event::Sync {
+        result: result.clone(),
+    });
+    match result {
+        SyncResult::NoChangesNeeded => output::success("Razdfile.yml and mise.toml are in sync"),
+        SyncResult::Conflict => {
+            return Err(RazdError::config(
+                "Razdfile.yml and mise.toml both changed and the conflict was not resolved",
+            ))
+        }
+        // The sync manager reports what it wrote
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Directory of the Razdfile to sync: that of `custom_path`, else the current one
+/// mise.toml is synced with the Razdfile.yml next to it, so other file names are refused.
+fn project_root(custom_path: Option<PathBuf>) -> Result<PathBuf> {
+    let current_dir = env::current_dir()?;
+    let Some(path) = resolve_razdfile_path(custom_path.clone())? else {
+        return Ok(current_dir);
+    };
+    if custom_path.is_some() && path.file_name() != Some(OsStr::new("Razdfile.yml")) {
+        return Err(RazdError::config(format!(
+            "razd sync keeps Razdfile.yml and mise.toml in sync; {} is not a Razdfile.yml",
+            path.display()
+        )));
+    }
+    Ok(match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => current_dir.join(dir),
+        _ => current_dir,
+    })
+}
+
+/// Print the entries a sync would change in `file`, one per line
+fn print_diff(file: &str, changes: &[Change]) {
+    if events::enabled() {
+        events::emit(Event::SyncDiff {
+            file: file.to_string(),
+            changes: changes.to_vec(),
+        });
+        return;
+    }
+
+    println!("{}", file.bold());
+    let width = changes.iter().map(|c| c.key.len()).max().unwrap_or(0);
+    for change in changes {
+        let line = match (&change.before, &change.after) {
+            (None, Some(after)) => format!("  + {:<width$}  {}", change.key, after).green(),
+            (Some(before), None) => format!("  - {:<width$}  {}", change.key, before).red(),
+            (Some(before), Some(after)) => {
+                format!("  ~ {:<width$}  {} → {}", change.key, before, after).yellow()
+            }
+            (None, None) => continue,
+        };
+        println!("{}", line);
+    }
+}
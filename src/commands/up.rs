@@ -1,7 +1,8 @@
-use crate::config::get_workflow_config_with_path;
+use crate::config::get_workflow_with_path;
+use crate::core::summary;
 use crate::core::trust::ensure_trusted;
 use crate::core::{output, RazdError, Result};
-use crate::integrations::{git, mise, taskfile};
+use crate::integrations::{git, mise, process, taskfile};
 use std::env;
 use std::fs;
 use std::io::{self, Write};
@@ -16,14 +17,23 @@ pub async fn execute(
 ) -> Result<()> {
     if init {
         // Init mode: create new Razdfile.yml
-        execute_init(custom_path).await
-    } else if let Some(url_str) = url {
+        return execute_init(custom_path).await;
+    }
+
+    // Time every phase for the summary; a dry run has nothing to time
+    if !process::dry_run() {
+        summary::start();
+    }
+    let result = if let Some(url_str) = url {
         // Clone mode: existing behavior
         execute_with_clone(url_str, name, custom_path).await
     } else {
         // Local mode: new behavior
         execute_local_project(custom_path).await
-    }
+    };
+    // Failed runs get their summary here, successful ones in show_success_message
+    summary::finish(&env::current_dir().unwrap_or_default(), &result);
+    result
 }
 
 /// Initialize new Razdfile.yml with project template
@@ -72,6 +82,9 @@ async fn execute_with_clone(
     custom_path: Option<PathBuf>,
 ) -> Result<()> {
     output::info(&format!("Setting up project from {}", url));
+    if process::dry_run() {
+        output::info("Dry run: the repository is cloned for inspection, nothing in it is executed");
+    }
 
     // Step 1: Clone the repository
     let repo_path = git::clone_repository(url, name).await?;
@@ -119,7 +132,8 @@ async fn execute_local_project(custom_path: Option<PathBuf>) -> Result<()> {
         // Step 1: Offer to create configuration interactively
         output::info("No project configuration found.");
 
-        if prompt_yes_no("Would you like to create a Razdfile.yml?", false)? {
+        if !process::dry_run() && prompt_yes_no("Would you like to create a Razdfile.yml?", false)?
+        {
             create_interactive_razdfile(&current_dir).await?;
             output::info("Razdfile.yml created successfully!");
 
@@ -156,9 +170,9 @@ async fn execute_up_workflow(custom_path: Option<PathBuf>) -> Result<()> {
         output::warning(&format!("Mise sync check failed: {}", e));
     }
 
-    if let Some(workflow_content) = get_workflow_config_with_path("default", custom_path)? {
+    if let Some(workflow) = get_workflow_with_path("default", custom_path)? {
         output::step("Executing up workflow...");
-        taskfile::execute_workflow_task_interactive("default", &workflow_content).await?;
+        taskfile::execute_workflow_task_interactive("default", &workflow).await?;
     } else {
         // Fallback to legacy behavior if no workflow is found
         output::warning("No default task found, falling back to legacy setup");
@@ -171,7 +185,13 @@ async fn execute_up_workflow(custom_path: Option<PathBuf>) -> Result<()> {
 
 /// Show success message and next steps
 fn show_success_message() -> Result<()> {
+    if process::dry_run() {
+        output::success("Dry run completed: nothing was installed, trusted or executed");
+        return Ok(());
+    }
+
     output::success("Project setup completed successfully!");
+    summary::finish(&env::current_dir()?, &Ok(()));
     output::info("Next steps:");
     output::info("  razd dev            # Start development workflow");
     output::info("  razd build          # Build project");
@@ -1,6 +1,8 @@
-use crate::config::razdfile::{Command, MiseConfig, RazdfileConfig, ToolConfig};
+use crate::config::mise_sync::parse_other_keys;
+use crate::config::razdfile::{Command, Deferred, MiseConfig, RazdfileConfig, ToolConfig};
 use crate::core::RazdError;
 use indexmap::IndexMap;
+use serde_yaml::Value;
 use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
 
@@ -47,6 +49,17 @@ pub fn canonicalize_razdfile(config: &RazdfileConfig) -> String {
                 writeln!(output, "    {}:{}", name, url).unwrap();
             }
         }
+
+        if let Some(ref min_version) = mise.min_version {
+            writeln!(output, "  min_version:{}", canonical_value(min_version)).unwrap();
+        }
+
+        for (section, entries) in other_sections(mise) {
+            writeln!(output, "  {}:", section).unwrap();
+            for (key, value) in sorted_entries(entries) {
+                writeln!(output, "    {}:{}", key, canonical_value(value)).unwrap();
+            }
+        }
     }
 
     // Tasks (sorted by key)
@@ -91,6 +104,12 @@ pub fn canonicalize_razdfile(config: &RazdfileConfig) -> String {
                     }
                     writeln!(output).unwrap();
                 }
+                Command::Defer { defer } => match defer {
+                    Deferred::String(s) => writeln!(output, "      -defer:{}", s).unwrap(),
+                    Deferred::TaskRef { task, .. } => {
+                        writeln!(output, "      -defer:task:{}", task).unwrap()
+                    }
+                },
             }
         }
 
@@ -132,9 +151,70 @@ pub fn canonicalize_mise_toml(config: &MiseConfig) -> String {
         }
     }
 
+    if let Some(ref min_version) = config.min_version {
+        writeln!(output, "min_version={}", canonical_value(min_version)).unwrap();
+    }
+
+    for (section, entries) in other_sections(config) {
+        writeln!(output, "[{}]", section).unwrap();
+        for (key, value) in sorted_entries(entries) {
+            writeln!(output, "{}={}", key, canonical_value(value)).unwrap();
+        }
+    }
+
     output
 }
 
+/// Sections of a MiseConfig besides tools and plugins, with the keys outside of them last
+fn other_sections(config: &MiseConfig) -> Vec<(&'static str, &IndexMap<String, Value>)> {
+    let sections = [
+        ("env", config.env.as_ref()),
+        ("settings", config.settings.as_ref()),
+        ("alias", config.alias.as_ref()),
+        ("tasks", config.tasks.as_ref()),
+        (
+            "other",
+            Some(&config.other).filter(|other| !other.is_empty()),
+        ),
+    ];
+    sections
+        .into_iter()
+        .filter_map(|(section, entries)| entries.map(|entries| (section, entries)))
+        .collect()
+}
+
+fn sorted_entries(entries: &IndexMap<String, Value>) -> Vec<(&String, &Value)> {
+    let mut sorted: Vec<_> = entries.iter().collect();
+    sorted.sort_by_key(|(key, _)| *key);
+    sorted
+}
+
+/// Deterministic form of a YAML value: mapping keys sorted, no formatting
+fn canonical_value(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => format!("{:?}", s),
+        Value::Sequence(items) => {
+            let items: Vec<String> = items.iter().map(canonical_value).collect();
+            format!("[{}]", items.join(","))
+        }
+        Value::Mapping(mapping) => {
+            let sorted: BTreeMap<String, String> = mapping
+                .iter()
+                .map(|(k, v)| (canonical_value(k), canonical_value(v)))
+                .collect();
+            let entries: Vec<String> = sorted
+                .into_iter()
+                .map(|(k, v)| format!("{}:{}", k, v))
+                .collect();
+            format!("{{{}}}", entries.join(","))
+        }
+        Value::Tagged(tagged) => canonical_value(&tagged.value),
+    }
+}
+
 /// Computes SHA-256 hash of a string
 fn hash_string(content: &str) -> String {
     let mut hasher = Sha256::new();
@@ -179,9 +259,9 @@ pub fn compute_mise_toml_semantic_hash(path: &Path) -> crate::core::Result<Strin
 }
 
 /// Parses mise.toml content into MiseConfig
+/// Unlike the sync parser, tools that are not plain version strings are ignored.
 fn parse_mise_toml(content: &str) -> crate::core::Result<MiseConfig> {
-    let doc: toml_edit::DocumentMut = content
-        .parse()
+    let doc: toml::Table = toml::from_str(content)
         .map_err(|e| RazdError::config(format!("Failed to parse mise.toml: {}", e)))?;
 
     let mut tools = None;
@@ -213,7 +293,13 @@ fn parse_mise_toml(content: &str) -> crate::core::Result<MiseConfig> {
         }
     }
 
-    Ok(MiseConfig { tools, plugins })
+    let mut config = MiseConfig {
+        tools,
+        plugins,
+        ..Default::default()
+    };
+    parse_other_keys(&doc, &mut config)?;
+    Ok(config)
 }
 
 #[cfg(test)]
@@ -232,9 +318,14 @@ mod tests {
                     map
                 }),
                 plugins: None,
+                ..Default::default()
             }),
             env: None,
             vars: None,
+            dotenv: None,
+            includes: None,
+            output: None,
+            version_files: None,
             tasks: {
                 let mut map = IndexMap::new();
                 map.insert(
@@ -248,6 +339,7 @@ mod tests {
                         vars: None,
                         silent: None,
                         platforms: None,
+                        ..Default::default()
                     },
                 );
                 map
@@ -279,9 +371,14 @@ mod tests {
             mise: Some(MiseConfig {
                 tools: Some(tools1),
                 plugins: None,
+                ..Default::default()
             }),
             env: None,
             vars: None,
+            dotenv: None,
+            includes: None,
+            output: None,
+            version_files: None,
             tasks: IndexMap::new(),
         };
 
@@ -290,9 +387,14 @@ mod tests {
             mise: Some(MiseConfig {
                 tools: Some(tools2),
                 plugins: None,
+                ..Default::default()
             }),
             env: None,
             vars: None,
+            dotenv: None,
+            includes: None,
+            output: None,
+            version_files: None,
             tasks: IndexMap::new(),
         };
 
@@ -313,4 +415,25 @@ mod tests {
         assert_ne!(hash1, hash3);
         assert_eq!(hash1.len(), 64); // SHA-256 produces 64 hex characters
     }
+
+    #[test]
+    fn test_mise_toml_hash_covers_env_settings_and_tasks() {
+        let base =
+            "[tools]\nnode = \"22\"\n\n[env]\nPORT = 3000\n\n[tasks.build]\nrun = \"make\"\n";
+        let canonical = |content: &str| canonicalize_mise_toml(&parse_mise_toml(content).unwrap());
+
+        let reordered =
+            "[tasks.build]\nrun = \"make\"\n\n[env]\nPORT = 3000\n\n[tools]\nnode = \"22\"\n";
+        assert_eq!(canonical(base), canonical(reordered));
+
+        assert_ne!(canonical(base), canonical(&base.replace("3000", "3001")));
+        assert_ne!(
+            canonical(base),
+            canonical(&base.replace("make", "make all"))
+        );
+        assert_ne!(
+            canonical(base),
+            canonical(&format!("min_version = \"2024.9.5\"\n{}", base))
+        );
+    }
 }
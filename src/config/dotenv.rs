@@ -0,0 +1,292 @@
+//! Native parser for `.env` files referenced by `dotenv:`
+//!
+//! Supported syntax: `KEY=value` lines with an optional `export` prefix, `#`
+//! comments, single-quoted literals, double-quoted values with escapes that may
+//! span several lines, and `$VAR`, `${VAR}` and `${VAR:-default}` expansion in
+//! unquoted and double-quoted values.
+
+use crate::core::{RazdError, Result};
+use indexmap::IndexMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Read and parse a dotenv file; returns None when the file does not exist
+/// Expansions see earlier entries of the file, then `env`, then the process environment
+pub fn load(path: &Path, env: &IndexMap<String, String>) -> Result<Option<Vec<(String, String)>>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(RazdError::config(format!(
+                "Failed to read dotenv file {}: {}",
+                path.display(),
+                e
+            )))
+        }
+    };
+
+    let lookup = |name: &str| env.get(name).cloned().or_else(|| std::env::var(name).ok());
+    parse(&content, lookup)
+        .map(Some)
+        .map_err(|e| RazdError::config(format!("Invalid dotenv file {}: {}", path.display(), e)))
+}
+
+/// Parse dotenv content into entries in file order; a repeated key keeps its last value
+pub fn parse(
+    content: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> std::result::Result<Vec<(String, String)>, String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let number = index + 1;
+        let line = lines[index].trim();
+        index += 1;
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line
+            .strip_prefix("export ")
+            .map(str::trim_start)
+            .unwrap_or(line);
+        let (key, raw) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected KEY=value", number))?;
+        let key = key.trim();
+        if !is_valid_key(key) {
+            return Err(format!("line {}: invalid variable name '{}'", number, key));
+        }
+
+        let raw = raw.trim_start();
+        let known = |name: &str| {
+            entries
+                .iter()
+                .rev()
+                .find(|(existing, _)| existing == name)
+                .map(|(_, value)| value.clone())
+                .or_else(|| lookup(name))
+        };
+        let value = match raw.chars().next() {
+            Some(quote @ ('\'' | '"')) => {
+                let mut text = raw[1..].to_string();
+                let end = loop {
+                    if let Some(end) = closing_quote(&text, quote) {
+                        break end;
+                    }
+                    if index >= lines.len() {
+                        return Err(format!("line {}: unterminated {} quote", number, quote));
+                    }
+                    text.push('\n');
+                    text.push_str(lines[index]);
+                    index += 1;
+                };
+                let rest = text[end + 1..].trim();
+                if !rest.is_empty() && !rest.starts_with('#') {
+                    return Err(format!(
+                        "line {}: unexpected characters after closing quote",
+                        number
+                    ));
+                }
+                text.truncate(end);
+                if quote == '\'' {
+                    text
+                } else {
+                    expand(&text, true, known)
+                }
+            }
+            _ => expand(strip_inline_comment(raw).trim_end(), false, known),
+        };
+
+        entries.retain(|(existing, _)| existing != key);
+        entries.push((key.to_string(), value));
+    }
+
+    Ok(entries)
+}
+
+/// Variable names: letters, digits, `_` and `.`, not starting with a digit
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+/// Byte offset of the quote closing a value, skipping escaped double quotes
+fn closing_quote(text: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (offset, c) in text.char_indices() {
+        match c {
+            '\\' if quote == '"' && !escaped => escaped = true,
+            c if c == quote && !escaped => return Some(offset),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+/// Cut a ` # comment` off an unquoted value
+fn strip_inline_comment(value: &str) -> &str {
+    value
+        .char_indices()
+        .find(|&(offset, c)| c == '#' && value[..offset].ends_with(|p: char| p.is_whitespace()))
+        .map(|(offset, _)| &value[..offset])
+        .unwrap_or(value)
+}
+
+/// Expand `$VAR`, `${VAR}` and `${VAR:-default}`; with `escapes`, also handle backslash escapes
+/// Unknown variables expand to an empty string
+fn expand(text: &str, escapes: bool, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if escapes => match chars.next() {
+                Some('n') => output.push('\n'),
+                Some('t') => output.push('\t'),
+                Some('r') => output.push('\r'),
+                Some(other) => output.push(other),
+                None => output.push('\\'),
+            },
+            '$' if chars.peek() == Some(&'{') => {
+                chars.next();
+                let mut inner = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        closed = true;
+                        break;
+                    }
+                    inner.push(c);
+                }
+                if !closed {
+                    // Not an expansion after all: keep the text verbatim
+                    output.push_str("${");
+                    output.push_str(&inner);
+                    continue;
+                }
+                let (name, default) = match inner.split_once(":-") {
+                    Some((name, default)) => (name, Some(default)),
+                    None => (inner.as_str(), None),
+                };
+                match lookup(name).filter(|value| !value.is_empty()) {
+                    Some(value) => output.push_str(&value),
+                    None => output.push_str(default.unwrap_or_default()),
+                }
+            }
+            '$' if chars
+                .peek()
+                .is_some_and(|c| c.is_ascii_alphabetic() || *c == '_') =>
+            {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    name.push(c);
+                    chars.next();
+                }
+                output.push_str(&lookup(&name).unwrap_or_default());
+            }
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_with(content: &str, outer: &[(&str, &str)]) -> Vec<(String, String)> {
+        parse(content, |name| {
+            outer
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        })
+        .unwrap()
+    }
+
+    fn pairs(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_basic_syntax() {
+        let entries = parse_with(
+            r#"
+# database settings
+export DB_HOST=localhost
+DB_PORT = 5432   # inline comment
+EMPTY=
+URL=http://example.com/#anchor
+"#,
+            &[],
+        );
+        assert_eq!(
+            entries,
+            pairs(&[
+                ("DB_HOST", "localhost"),
+                ("DB_PORT", "5432"),
+                ("EMPTY", ""),
+                ("URL", "http://example.com/#anchor"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_quotes() {
+        let entries = parse_with(
+            "SINGLE='literal $HOME \\n'\nDOUBLE=\"tab\\there \\\"quoted\\\"\"\nMULTI=\"line one\nline two\"\n",
+            &[("HOME", "/home/me")],
+        );
+        assert_eq!(
+            entries,
+            pairs(&[
+                ("SINGLE", "literal $HOME \\n"),
+                ("DOUBLE", "tab\there \"quoted\""),
+                ("MULTI", "line one\nline two"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_expansion() {
+        let entries = parse_with(
+            "HOST=db\nURL=postgres://${HOST}:${PORT:-5432}/$NAME\nGREETING=\"hi ${USER}\"\nHOST=override\n",
+            &[("NAME", "app"), ("USER", "razd")],
+        );
+        assert_eq!(
+            entries,
+            pairs(&[
+                ("URL", "postgres://db:5432/app"),
+                ("GREETING", "hi razd"),
+                ("HOST", "override"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("NOT A PAIR", |_| None)
+            .unwrap_err()
+            .contains("line 1"));
+        assert!(parse("1BAD=x", |_| None)
+            .unwrap_err()
+            .contains("invalid variable name"));
+        assert!(parse("OPEN=\"never closed\n", |_| None)
+            .unwrap_err()
+            .contains("unterminated"));
+    }
+}
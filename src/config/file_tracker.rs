@@ -1,5 +1,8 @@
 use crate::config::canonical::{compute_mise_toml_semantic_hash, compute_razdfile_semantic_hash};
-use crate::core::{RazdError, Result};
+use crate::config::mise_sync::parse_mise_toml;
+use crate::config::razdfile::MiseConfig;
+use crate::core::{log, RazdError, Result};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::fs;
@@ -22,6 +25,12 @@ pub struct FileTrackingState {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mise_toml_modified: Option<SystemTime>,
     pub last_sync_time: SystemTime,
+    /// mise section both files agreed on at the last sync, the base of three-way merges
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mise_base: Option<MiseConfig>,
+    /// Tool versions each version file and Razdfile.yml agreed on at the last sync
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub version_files: IndexMap<String, IndexMap<String, String>>,
 }
 
 /// Change detection result
@@ -55,7 +64,7 @@ pub fn get_tracking_file_path(project_dir: &Path) -> Result<PathBuf> {
 }
 
 /// Get platform-specific data directory
-fn get_data_dir() -> Result<PathBuf> {
+pub fn get_data_dir() -> Result<PathBuf> {
     #[cfg(target_os = "windows")]
     {
         if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
@@ -80,7 +89,7 @@ fn get_data_dir() -> Result<PathBuf> {
 }
 
 /// Hash a path using SHA256
-fn hash_path(path: &Path) -> String {
+pub fn hash_path(path: &Path) -> String {
     let path_str = path.to_string_lossy();
     let mut hasher = Sha256::new();
     hasher.update(path_str.as_bytes());
@@ -186,30 +195,58 @@ pub fn check_file_changes(project_dir: &Path) -> Result<ChangeDetection> {
         None
     };
 
-    match tracking_state {
+    log::trace("sync", || {
+        format!(
+            "Current hashes: Razdfile.yml {}, mise.toml {}",
+            current_razdfile_hash.as_deref().unwrap_or("(missing)"),
+            current_mise_hash.as_deref().unwrap_or("(missing)")
+        )
+    });
+
+    let detection = match tracking_state {
         None => {
             // First run - no tracking state exists
+            log::debug("sync", || {
+                format!("No tracking state for {} yet", project_dir.display())
+            });
             if current_razdfile_hash.is_some() || current_mise_hash.is_some() {
-                Ok(ChangeDetection::RazdfileChanged) // Treat as Razdfile change to generate mise.toml
+                ChangeDetection::RazdfileChanged // Treat as Razdfile change to generate mise.toml
             } else {
-                Ok(ChangeDetection::NoChanges)
+                ChangeDetection::NoChanges
             }
         }
         Some(state) => {
             let razdfile_changed = current_razdfile_hash != state.razdfile_hash;
             let mise_toml_changed = current_mise_hash != state.mise_toml_hash;
+            log::debug("sync", || {
+                format!(
+                    "Compared with the last sync: Razdfile.yml {}, mise.toml {}",
+                    if razdfile_changed {
+                        "changed"
+                    } else {
+                        "unchanged"
+                    },
+                    if mise_toml_changed {
+                        "changed"
+                    } else {
+                        "unchanged"
+                    }
+                )
+            });
 
             if razdfile_changed && mise_toml_changed {
-                Ok(ChangeDetection::BothChanged)
+                ChangeDetection::BothChanged
             } else if razdfile_changed {
-                Ok(ChangeDetection::RazdfileChanged)
+                ChangeDetection::RazdfileChanged
             } else if mise_toml_changed {
-                Ok(ChangeDetection::MiseTomlChanged)
+                ChangeDetection::MiseTomlChanged
             } else {
-                Ok(ChangeDetection::NoChanges)
+                ChangeDetection::NoChanges
             }
         }
-    }
+    };
+    log::debug("sync", || format!("Change detection: {:?}", detection));
+    Ok(detection)
 }
 
 /// Update tracking state after a sync operation
@@ -229,6 +266,18 @@ pub fn update_tracking_state(project_dir: &Path) -> Result<()> {
         None
     };
 
+    // After a sync mise.toml holds the whole mise section, includes of the Razdfile too
+    let mise_base = fs::read_to_string(&mise_toml_path)
+        .ok()
+        .and_then(|content| parse_mise_toml(&content).ok());
+
+    // Version files are recorded separately, see `record_version_files`
+    let version_files = load_tracking_state(project_dir)
+        .ok()
+        .flatten()
+        .map(|state| state.version_files)
+        .unwrap_or_default();
+
     let state = FileTrackingState {
         razdfile_hash,
         mise_toml_hash,
@@ -236,11 +285,30 @@ pub fn update_tracking_state(project_dir: &Path) -> Result<()> {
         razdfile_modified: None,
         mise_toml_modified: None,
         last_sync_time: SystemTime::now(),
+        mise_base,
+        version_files,
     };
 
     save_tracking_state(project_dir, &state)
 }
 
+/// Record the tool versions of each version file after syncing them
+pub fn record_version_files(
+    project_dir: &Path,
+    version_files: IndexMap<String, IndexMap<String, String>>,
+) -> Result<()> {
+    let mut state = match load_tracking_state(project_dir)? {
+        Some(state) => state,
+        None => {
+            update_tracking_state(project_dir)?;
+            load_tracking_state(project_dir)?
+                .ok_or_else(|| RazdError::config("Failed to create tracking state"))?
+        }
+    };
+    state.version_files = version_files;
+    save_tracking_state(project_dir, &state)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -276,6 +344,8 @@ mod tests {
             razdfile_modified: None,
             mise_toml_modified: None,
             last_sync_time: now,
+            mise_base: None,
+            version_files: IndexMap::new(),
         };
 
         save_tracking_state(temp_dir.path(), &state).unwrap();
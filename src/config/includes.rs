@@ -0,0 +1,417 @@
+//! Resolution of `includes:` into a single merged Razdfile
+//!
+//! Tasks of an included file are exposed as `namespace:task`. References between
+//! tasks of the same file are rewritten to the namespaced names; a leading `:`
+//! (e.g. `task: :setup`) refers to a task of the root Razdfile instead.
+
+use crate::config::razdfile::{
+    Command, Deferred, Dependency, Dotenv, Include, MiseConfig, RazdfileConfig, TaskConfig,
+};
+use crate::core::{RazdError, Result};
+use indexmap::IndexMap;
+use std::path::{Path, PathBuf};
+
+/// Merge all includes of a loaded Razdfile into it
+/// The returned config has no `includes:` left and can be executed directly
+pub fn resolve(config: RazdfileConfig, path: &Path) -> Result<RazdfileConfig> {
+    let mut chain = vec![canonical(path)];
+    let mut resolved = resolve_includes(config, path, &mut chain)?;
+
+    for task in resolved.tasks.values_mut() {
+        rewrite_refs(task, |name| {
+            name.strip_prefix(':').unwrap_or(name).to_string()
+        });
+    }
+
+    Ok(resolved)
+}
+
+fn resolve_includes(
+    mut config: RazdfileConfig,
+    path: &Path,
+    chain: &mut Vec<PathBuf>,
+) -> Result<RazdfileConfig> {
+    let Some(includes) = config.includes.take() else {
+        return Ok(config);
+    };
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+
+    for (namespace, include) in includes {
+        let (file, dir, optional) = match include {
+            Include::Simple(file) => (file, None, false),
+            Include::Complex {
+                taskfile,
+                dir,
+                optional,
+            } => (taskfile, dir, optional),
+        };
+
+        // Collecting the components drops `./` segments from the joined path
+        let mut include_path: PathBuf = base_dir.join(&file).components().collect();
+        if include_path.is_dir() {
+            include_path = include_path.join("Razdfile.yml");
+        }
+
+        let included = match RazdfileConfig::load_from_path(&include_path)? {
+            Some(included) => included,
+            None if optional => continue,
+            None => {
+                return Err(RazdError::config(format!(
+                    "Included Razdfile '{}' not found: {} (included from {})",
+                    namespace,
+                    include_path.display(),
+                    path.display()
+                )))
+            }
+        };
+
+        let canonical_path = canonical(&include_path);
+        if let Some(start) = chain.iter().position(|p| *p == canonical_path) {
+            let mut cycle: Vec<String> = chain[start..]
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect();
+            cycle.push(canonical_path.display().to_string());
+            return Err(RazdError::config(format!(
+                "Razdfile include cycle detected: {}",
+                cycle.join(" -> ")
+            )));
+        }
+
+        chain.push(canonical_path);
+        let included = resolve_includes(included, &include_path, chain)?;
+        chain.pop();
+
+        let task_dir = dir.map(|dir| base_dir.join(dir));
+        merge_included(&mut config, &namespace, included, &include_path, task_dir)?;
+    }
+
+    Ok(config)
+}
+
+/// Add the tasks (and mise tools) of an already resolved include to `config`
+fn merge_included(
+    config: &mut RazdfileConfig,
+    namespace: &str,
+    included: RazdfileConfig,
+    include_path: &Path,
+    task_dir: Option<PathBuf>,
+) -> Result<()> {
+    for (name, mut task) in included.tasks {
+        let full_name = format!("{}:{}", namespace, name);
+        if config.tasks.contains_key(&full_name) {
+            return Err(RazdError::config(format!(
+                "Task '{}' from {} conflicts with an existing task",
+                full_name,
+                include_path.display()
+            )));
+        }
+
+        rewrite_refs(&mut task, |target| {
+            if target.starts_with(':') {
+                target.to_string()
+            } else {
+                format!("{}:{}", namespace, target)
+            }
+        });
+
+        // Global vars, env and dotenv files of the included file apply to its tasks only
+        task.vars = merge_maps(included.vars.as_ref(), task.vars.take());
+        task.env = merge_maps(included.env.as_ref(), task.env.take());
+        if let Some(ref files) = included.dotenv {
+            // Global dotenv files of the included file are relative to that file
+            let include_dir = include_path.parent().unwrap_or(Path::new("."));
+            let mut dotenv: Vec<Dotenv> = files
+                .iter()
+                .map(|file| Dotenv::Complex {
+                    file: include_dir.join(file.file()).to_string_lossy().to_string(),
+                    optional: file.optional(),
+                })
+                .collect();
+            dotenv.extend(task.dotenv.take().into_iter().flatten());
+            task.dotenv = Some(dotenv);
+        }
+
+        if let Some(ref dir) = task_dir {
+            let joined = task.resolve_dir(dir);
+            task.dir = Some(joined.to_string_lossy().to_string());
+        }
+        task.source
+            .get_or_insert_with(|| include_path.to_path_buf());
+
+        config.tasks.insert(full_name, task);
+    }
+
+    // Tools declared by included files are installed too; the including file wins
+    if let Some(included_mise) = included.mise {
+        let mise = config.mise.get_or_insert_with(MiseConfig::default);
+        if mise.min_version.is_none() {
+            mise.min_version = included_mise.min_version;
+        }
+        if let Some(tools) = included_mise.tools {
+            let merged = mise.tools.get_or_insert_with(IndexMap::new);
+            for (name, tool) in tools {
+                merged.entry(name).or_insert(tool);
+            }
+        }
+        if let Some(plugins) = included_mise.plugins {
+            let merged = mise.plugins.get_or_insert_with(IndexMap::new);
+            for (name, url) in plugins {
+                merged.entry(name).or_insert(url);
+            }
+        }
+        let sections = [
+            (&mut mise.env, included_mise.env),
+            (&mut mise.settings, included_mise.settings),
+            (&mut mise.alias, included_mise.alias),
+            (&mut mise.tasks, included_mise.tasks),
+        ];
+        for (merged, entries) in sections {
+            if let Some(entries) = entries {
+                let merged = merged.get_or_insert_with(IndexMap::new);
+                for (key, value) in entries {
+                    merged.entry(key).or_insert(value);
+                }
+            }
+        }
+        for (key, value) in included_mise.other {
+            mise.other.entry(key).or_insert(value);
+        }
+    }
+
+    Ok(())
+}
+
+/// Layer `overrides` on top of `base`, keeping declaration order
+fn merge_maps<V: Clone>(
+    base: Option<&IndexMap<String, V>>,
+    overrides: Option<IndexMap<String, V>>,
+) -> Option<IndexMap<String, V>> {
+    match (base, overrides) {
+        (None, overrides) => overrides,
+        (Some(base), None) => Some(base.clone()),
+        (Some(base), Some(overrides)) => {
+            let mut merged = base.clone();
+            merged.extend(overrides);
+            Some(merged)
+        }
+    }
+}
+
+/// Rename every task a task points at (deps and `task:` commands)
+fn rewrite_refs(task: &mut TaskConfig, rename: impl Fn(&str) -> String) {
+    for dep in task.deps.iter_mut().flatten() {
+        match dep {
+            Dependency::Simple(name) => *name = rename(name),
+            Dependency::Complex { task, .. } => *task = rename(task),
+        }
+    }
+
+    for cmd in task.cmds.iter_mut() {
+        match cmd {
+            Command::TaskRef { task, .. }
+            | Command::Defer {
+                defer: Deferred::TaskRef { task, .. },
+            } => *task = rename(task),
+            _ => {}
+        }
+    }
+}
+
+/// Canonical form of a path for cycle detection, falling back to the path itself
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn load(path: &Path) -> Result<RazdfileConfig> {
+        RazdfileConfig::load_with_includes(path).map(|config| config.unwrap())
+    }
+
+    #[test]
+    fn test_includes_namespace_tasks_and_refs() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("api")).unwrap();
+        fs::write(
+            temp_dir.path().join("Razdfile.yml"),
+            r#"
+includes:
+  api: ./api
+tasks:
+  setup:
+    cmds: [echo setup]
+  default:
+    deps: [api:build]
+    cmds: []
+"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("api/Razdfile.yml"),
+            r#"
+vars:
+  TARGET: api
+tasks:
+  build:
+    deps: [gen, ":setup"]
+    cmds:
+      - task: gen
+  gen:
+    cmds: [echo gen]
+"#,
+        )
+        .unwrap();
+
+        let config = load(&temp_dir.path().join("Razdfile.yml")).unwrap();
+        assert!(config.includes.is_none());
+        let names: Vec<_> = config.tasks.keys().cloned().collect();
+        assert_eq!(names, vec!["setup", "default", "api:build", "api:gen"]);
+
+        let build = config.tasks.get("api:build").unwrap();
+        let deps: Vec<_> = build
+            .deps
+            .iter()
+            .flatten()
+            .map(|dep| match dep {
+                Dependency::Simple(name) => name.clone(),
+                Dependency::Complex { task, .. } => task.clone(),
+            })
+            .collect();
+        assert_eq!(deps, vec!["api:gen", "setup"]);
+        assert!(matches!(
+            &build.cmds[0],
+            Command::TaskRef { task, .. } if task == "api:gen"
+        ));
+        assert!(build.vars.as_ref().unwrap().contains_key("TARGET"));
+        assert_eq!(
+            build.source.as_deref(),
+            Some(temp_dir.path().join("./api/Razdfile.yml").as_path())
+        );
+    }
+
+    #[test]
+    fn test_include_dir_and_optional() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Razdfile.yml"),
+            r#"
+includes:
+  web:
+    taskfile: web.yml
+    dir: frontend
+  extra:
+    taskfile: missing.yml
+    optional: true
+tasks: {}
+"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("web.yml"),
+            "tasks:\n  build:\n    cmds: [echo web]\n",
+        )
+        .unwrap();
+
+        let config = load(&temp_dir.path().join("Razdfile.yml")).unwrap();
+        let build = config.tasks.get("web:build").unwrap();
+        assert_eq!(
+            build.resolve_dir(Path::new("/ignored")),
+            temp_dir.path().join("frontend")
+        );
+        assert_eq!(config.tasks.len(), 1);
+    }
+
+    #[test]
+    fn test_missing_required_include() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Razdfile.yml"),
+            "includes:\n  lib: lib.yml\ntasks: {}\n",
+        )
+        .unwrap();
+
+        let err = load(&temp_dir.path().join("Razdfile.yml"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Included Razdfile 'lib' not found"));
+    }
+
+    #[test]
+    fn test_include_cycle_reports_chain() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Razdfile.yml"),
+            "includes:\n  a: a.yml\ntasks: {}\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("a.yml"),
+            "includes:\n  b: b.yml\ntasks: {}\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("b.yml"),
+            "includes:\n  a: a.yml\ntasks: {}\n",
+        )
+        .unwrap();
+
+        let err = load(&temp_dir.path().join("Razdfile.yml"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("include cycle detected"), "{}", err);
+        assert!(err.contains("a.yml -> "), "{}", err);
+        assert!(err.contains("b.yml -> "), "{}", err);
+    }
+
+    #[test]
+    fn test_included_mise_tools_do_not_override_root() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Razdfile.yml"),
+            "mise:\n  tools:\n    node: '22'\nincludes:\n  lib: lib.yml\ntasks: {}\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("lib.yml"),
+            "mise:\n  tools:\n    node: '18'\n    python: '3.12'\ntasks: {}\n",
+        )
+        .unwrap();
+
+        let config = load(&temp_dir.path().join("Razdfile.yml")).unwrap();
+        let tools = config.mise.unwrap().tools.unwrap();
+        let names: Vec<_> = tools.keys().cloned().collect();
+        assert_eq!(names, vec!["node", "python"]);
+        assert!(matches!(
+            tools.get("node"),
+            Some(crate::config::ToolConfig::Simple(v)) if v == "22"
+        ));
+    }
+
+    #[test]
+    fn test_included_min_version_and_other_keys_merge_into_root() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Razdfile.yml"),
+            "mise:\n  min_version: '2024.9.5'\n  vars:\n    app: web\nincludes:\n  lib: lib.yml\ntasks: {}\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("lib.yml"),
+            "mise:\n  min_version: '2024.1.0'\n  vars:\n    app: lib\n  hooks:\n    enter: echo hi\ntasks: {}\n",
+        )
+        .unwrap();
+
+        let mise = load(&temp_dir.path().join("Razdfile.yml"))
+            .unwrap()
+            .mise
+            .unwrap();
+        assert_eq!(mise.min_version, Some("2024.9.5".into()));
+        assert_eq!(mise.other["vars"]["app"], "web");
+        assert_eq!(mise.other["hooks"]["enter"], "echo hi");
+    }
+}
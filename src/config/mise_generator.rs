@@ -1,14 +1,117 @@
+use crate::config::mise_validator;
 use crate::config::razdfile::{MiseConfig, ToolConfig};
-use crate::core::Result;
-use toml_edit::{value, Array, DocumentMut, InlineTable, Item, Table, Value};
-
-#[cfg(test)]
+use crate::core::{RazdError, Result};
 use indexmap::IndexMap;
+use serde_yaml::Value as YamlValue;
+use toml_edit::{value, Array, DocumentMut, InlineTable, Item, Table, TableLike, Value};
+
 #[cfg(test)]
 use std::collections::HashMap;
 
 /// Generate mise.toml content from MiseConfig
 pub fn generate_mise_toml(mise_config: &MiseConfig) -> Result<String> {
+    Ok(build_document(mise_config)?.to_string())
+}
+
+/// Update existing mise.toml content to match MiseConfig
+/// Only entries whose value changed are rewritten: comments, key order, table
+/// style and the formatting of untouched entries stay exactly as they were.
+/// Keys the config no longer has are removed, new ones are appended to their table.
+pub fn update_mise_toml(existing: &str, mise_config: &MiseConfig) -> Result<String> {
+    let mut toml_doc: DocumentMut = existing
+        .parse()
+        .map_err(|e| RazdError::config(format!("Failed to parse mise.toml: {}", e)))?;
+    let mut desired = build_document(mise_config)?;
+    // Keys razd does not sync stay as they are
+    for (key, item) in toml_doc.iter() {
+        if !mise_validator::is_synced_key(key) {
+            desired.insert(key, item.clone());
+        }
+    }
+    patch_table(toml_doc.as_table_mut(), desired.as_table());
+    Ok(toml_doc.to_string())
+}
+
+/// Make `existing` hold the entries of `desired`, keeping entries that are already equal
+fn patch_table(existing: &mut dyn TableLike, desired: &dyn TableLike) {
+    let removed: Vec<String> = existing
+        .iter()
+        .filter(|(key, _)| !desired.contains_key(key))
+        .map(|(key, _)| key.to_string())
+        .collect();
+    for key in removed {
+        existing.remove(&key);
+    }
+
+    for (key, wanted) in desired.iter() {
+        match existing.get_mut(key) {
+            Some(current) => patch_item(current, wanted),
+            None => {
+                existing.insert(key, wanted.clone());
+            }
+        }
+    }
+}
+
+fn patch_item(current: &mut Item, wanted: &Item) {
+    if same_item(current, wanted) {
+        return;
+    }
+    // Patch tables entry by entry so `[section]`, dotted and inline styles are kept
+    if let (Some(current_table), Some(wanted_table)) =
+        (current.as_table_like_mut(), wanted.as_table_like())
+    {
+        patch_table(current_table, wanted_table);
+        return;
+    }
+    match (current, wanted.clone().into_value()) {
+        // Keep the spacing and trailing comment of the old value
+        (Item::Value(current), Ok(mut wanted)) => {
+            *wanted.decor_mut() = current.decor().clone();
+            *current = wanted;
+        }
+        (current, _) => *current = wanted.clone(),
+    }
+}
+
+/// Whether two items hold the same data, whatever their formatting or table style
+fn same_item(a: &Item, b: &Item) -> bool {
+    match (a.as_table_like(), b.as_table_like()) {
+        (Some(a), Some(b)) => same_table(a, b),
+        (None, None) => match (a, b) {
+            (Item::Value(a), Item::Value(b)) => same_value(a, b),
+            (Item::ArrayOfTables(a), Item::ArrayOfTables(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same_table(a, b))
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn same_table(a: &dyn TableLike, b: &dyn TableLike) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .all(|(key, item)| b.get(key).is_some_and(|other| same_item(item, other)))
+}
+
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => a.value() == b.value(),
+        (Value::Integer(a), Value::Integer(b)) => a.value() == b.value(),
+        (Value::Float(a), Value::Float(b)) => a.value() == b.value(),
+        (Value::Boolean(a), Value::Boolean(b)) => a.value() == b.value(),
+        (Value::Datetime(a), Value::Datetime(b)) => a.value() == b.value(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same_value(a, b))
+        }
+        (Value::InlineTable(a), Value::InlineTable(b)) => same_table(a, b),
+        _ => false,
+    }
+}
+
+/// Fresh mise.toml document for MiseConfig
+fn build_document(mise_config: &MiseConfig) -> Result<DocumentMut> {
     let mut toml_doc = DocumentMut::new();
 
     // Add tools section if present
@@ -72,7 +175,119 @@ pub fn generate_mise_toml(mise_config: &MiseConfig) -> Result<String> {
         }
     }
 
-    Ok(toml_doc.to_string())
+    if let Some(ref min_version) = mise_config.min_version {
+        if let Some(toml_value) = toml_value(min_version)? {
+            toml_doc.insert("min_version", value(toml_value));
+        }
+    }
+
+    // mise tasks and settings groups read best as their own [section.name] tables
+    let sections = [
+        ("env", &mise_config.env, false),
+        ("settings", &mise_config.settings, true),
+        ("alias", &mise_config.alias, false),
+        ("tasks", &mise_config.tasks, true),
+    ];
+    for (key, entries, nested_tables) in sections {
+        if let Some(entries) = entries {
+            toml_doc.insert(key, Item::Table(section_table(entries, nested_tables)?));
+        }
+    }
+
+    for (key, entry) in &mise_config.other {
+        match entry {
+            YamlValue::Mapping(mapping) => {
+                let entries = mapping
+                    .iter()
+                    .map(|(k, v)| (yaml_key(k), v.clone()))
+                    .collect();
+                toml_doc.insert(key, Item::Table(section_table(&entries, true)?));
+            }
+            _ => {
+                if let Some(toml_value) = toml_value(entry)? {
+                    toml_doc.insert(key, value(toml_value));
+                }
+            }
+        }
+    }
+
+    Ok(toml_doc)
+}
+
+/// Table of a mise.toml section; with `nested_tables` mappings become `[section.name]` tables
+fn section_table(entries: &IndexMap<String, YamlValue>, nested_tables: bool) -> Result<Table> {
+    let mut table = Table::new();
+    // An implicit table with only sub-tables is not printed as an empty `[section]`
+    table.set_implicit(true);
+    for (key, entry) in entries {
+        match entry {
+            YamlValue::Mapping(mapping) if nested_tables => {
+                let mut sub_table = Table::new();
+                for (k, v) in mapping {
+                    if let Some(toml_value) = toml_value(v)? {
+                        sub_table.insert(&yaml_key(k), value(toml_value));
+                    }
+                }
+                table.insert(key, Item::Table(sub_table));
+            }
+            _ => {
+                if let Some(toml_value) = toml_value(entry)? {
+                    table.insert(key, value(toml_value));
+                }
+            }
+        }
+    }
+    Ok(table)
+}
+
+/// Convert a YAML value of Razdfile.yml to TOML
+/// Returns None for null, which TOML cannot express; such entries are left out.
+/// Integers beyond TOML's 64-bit signed range are an error rather than a rounded float.
+fn toml_value(entry: &YamlValue) -> Result<Option<Value>> {
+    Ok(match entry {
+        YamlValue::Null => None,
+        YamlValue::Bool(b) => Some(Value::from(*b)),
+        YamlValue::Number(n) => match n.as_i64() {
+            Some(i) => Some(Value::from(i)),
+            None if n.is_f64() => n.as_f64().map(Value::from),
+            None => {
+                return Err(RazdError::config(format!(
+                    "Integer {} in the mise section does not fit in a TOML integer",
+                    n
+                )))
+            }
+        },
+        YamlValue::String(s) => Some(Value::from(s.as_str())),
+        YamlValue::Sequence(items) => {
+            let mut array = Array::new();
+            for item in items {
+                if let Some(toml_value) = toml_value(item)? {
+                    array.push(toml_value);
+                }
+            }
+            Some(Value::from(array))
+        }
+        YamlValue::Mapping(mapping) => {
+            let mut inline = InlineTable::new();
+            for (k, v) in mapping {
+                if let Some(toml_value) = toml_value(v)? {
+                    inline.insert(yaml_key(k), toml_value);
+                }
+            }
+            Some(Value::from(inline))
+        }
+        YamlValue::Tagged(tagged) => return toml_value(&tagged.value),
+    })
+}
+
+/// TOML key for a YAML mapping key; YAML allows non-string keys such as `3.11: ...`
+fn yaml_key(key: &YamlValue) -> String {
+    match key {
+        YamlValue::String(s) => s.clone(),
+        other => serde_yaml::to_string(other)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default(),
+    }
 }
 
 #[cfg(test)]
@@ -88,6 +303,7 @@ mod tests {
         let mise_config = MiseConfig {
             tools: Some(tools),
             plugins: None,
+            ..Default::default()
         };
 
         let toml = generate_mise_toml(&mise_config).unwrap();
@@ -113,6 +329,7 @@ mod tests {
         let mise_config = MiseConfig {
             tools: Some(tools),
             plugins: None,
+            ..Default::default()
         };
 
         let toml = generate_mise_toml(&mise_config).unwrap();
@@ -144,6 +361,7 @@ mod tests {
         let mise_config = MiseConfig {
             tools: Some(tools),
             plugins: None,
+            ..Default::default()
         };
 
         let toml = generate_mise_toml(&mise_config).unwrap();
@@ -171,6 +389,7 @@ mod tests {
         let mise_config = MiseConfig {
             tools: None,
             plugins: Some(plugins),
+            ..Default::default()
         };
 
         let toml = generate_mise_toml(&mise_config).unwrap();
@@ -194,6 +413,7 @@ mod tests {
         let mise_config = MiseConfig {
             tools: Some(tools),
             plugins: Some(plugins),
+            ..Default::default()
         };
 
         let toml = generate_mise_toml(&mise_config).unwrap();
@@ -209,6 +429,7 @@ mod tests {
         let mise_config = MiseConfig {
             tools: None,
             plugins: None,
+            ..Default::default()
         };
 
         let toml = generate_mise_toml(&mise_config).unwrap();
@@ -222,6 +443,7 @@ mod tests {
         let mise_config = MiseConfig {
             tools: Some(IndexMap::new()),
             plugins: Some(IndexMap::new()),
+            ..Default::default()
         };
 
         let toml = generate_mise_toml(&mise_config).unwrap();
@@ -229,4 +451,138 @@ mod tests {
         // Should generate empty document for empty maps
         assert!(toml.trim().is_empty());
     }
+
+    #[test]
+    fn test_generate_env_tasks_and_other_keys() {
+        let mise_config: MiseConfig = serde_yaml::from_str(
+            r#"
+min_version: 2024.9.5
+env:
+  PORT: 3000
+  UNSET: null
+  _:
+    file: .env
+tasks:
+  build:
+    run: cargo build
+hooks:
+  enter: echo hi
+"#,
+        )
+        .unwrap();
+
+        let toml = generate_mise_toml(&mise_config).unwrap();
+
+        assert!(toml.contains("min_version = \"2024.9.5\""));
+        assert!(toml.contains("[env]\nPORT = 3000\n_ = { file = \".env\" }"));
+        assert!(!toml.contains("UNSET"));
+        assert!(!toml.contains("[tasks]\n"));
+        assert!(toml.contains("[tasks.build]\nrun = \"cargo build\""));
+        assert!(toml.contains("[hooks]\nenter = \"echo hi\""));
+    }
+
+    const COMMENTED_MISE_TOML: &str = r#"# Toolchain for the app
+[tools]
+node = "22"   # LTS
+python = { version = "3.12", postinstall = "pip install -U pip" }
+go = "1.22"
+
+[env]
+_.file = ".env"
+
+[tasks.build]
+run = "cargo build"
+"#;
+
+    #[test]
+    fn test_update_keeps_unchanged_file_byte_identical() {
+        let mise_config: MiseConfig = serde_yaml::from_str(
+            r#"
+tools:
+  go: '1.22'
+  node: '22'
+  python:
+    version: '3.12'
+    postinstall: pip install -U pip
+env:
+  _:
+    file: .env
+tasks:
+  build:
+    run: cargo build
+"#,
+        )
+        .unwrap();
+
+        let updated = update_mise_toml(COMMENTED_MISE_TOML, &mise_config).unwrap();
+        assert_eq!(updated, COMMENTED_MISE_TOML);
+    }
+
+    #[test]
+    fn test_update_patches_only_changed_entries() {
+        let mise_config: MiseConfig = serde_yaml::from_str(
+            r#"
+tools:
+  node: '24'
+  python:
+    version: '3.12'
+    postinstall: pip install -U pip
+  rust: '1.82'
+env:
+  _:
+    file: .env
+tasks:
+  build:
+    run: cargo build
+  test:
+    run: cargo test
+"#,
+        )
+        .unwrap();
+
+        let updated = update_mise_toml(COMMENTED_MISE_TOML, &mise_config).unwrap();
+        assert_eq!(
+            updated,
+            r#"# Toolchain for the app
+[tools]
+node = "24"   # LTS
+python = { version = "3.12", postinstall = "pip install -U pip" }
+rust = "1.82"
+
+[env]
+_.file = ".env"
+
+[tasks.build]
+run = "cargo build"
+
+[tasks.test]
+run = "cargo test"
+"#
+        );
+    }
+
+    #[test]
+    fn test_update_keeps_keys_razd_does_not_sync() {
+        let existing = "[tools]\nnode = \"22\"\n\n[monorepo]\nconfig_roots = [\"apps/*\"]\n";
+        let mise_config: MiseConfig = serde_yaml::from_str("tools:\n  node: '24'\n").unwrap();
+
+        let updated = update_mise_toml(existing, &mise_config).unwrap();
+        assert_eq!(
+            updated,
+            "[tools]\nnode = \"24\"\n\n[monorepo]\nconfig_roots = [\"apps/*\"]\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_rejects_integers_toml_cannot_hold() {
+        let mise_config: MiseConfig =
+            serde_yaml::from_str("settings:\n  jobs: 18446744073709551615\n").unwrap();
+        let err = generate_mise_toml(&mise_config).unwrap_err();
+        assert!(err.to_string().contains("18446744073709551615"));
+    }
+
+    #[test]
+    fn test_update_rejects_invalid_toml() {
+        assert!(update_mise_toml("[tools\n", &MiseConfig::default()).is_err());
+    }
 }
@@ -0,0 +1,481 @@
+//! Three-way merge of the mise configuration
+//!
+//! When Razdfile.yml and mise.toml both changed since the last sync, each side is
+//! compared with the mise section recorded at that sync (the merge base) one
+//! entry at a time: a tool, a plugin, an env var, a setting, an alias or a mise
+//! task. An entry changed on one side only takes that change; an entry changed
+//! the same way on both sides is kept. Only entries changed differently on both
+//! sides are conflicts, which are handed to a resolver.
+//!
+//! `diff` compares two mise sections the same way, for `razd sync --diff`.
+
+use crate::config::razdfile::MiseConfig;
+use crate::core::Result;
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::fmt;
+
+/// Side whose value wins a conflict
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Razdfile,
+    Mise,
+}
+
+impl Side {
+    /// Parse the value of `--prefer`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "razdfile" => Some(Side::Razdfile),
+            "mise" => Some(Side::Mise),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Side::Razdfile => "Razdfile.yml",
+            Side::Mise => "mise.toml",
+        })
+    }
+}
+
+/// An entry both files changed in different ways
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// Dotted path of the entry, e.g. `tools.node` or `env.PORT`
+    pub key: String,
+    /// Value in Razdfile.yml; None when it was removed there
+    pub razdfile: Option<String>,
+    /// Value in mise.toml; None when it was removed there
+    pub mise: Option<String>,
+}
+
+/// Outcome of a merge that was not aborted
+#[derive(Debug, Clone)]
+pub struct Merge {
+    pub config: MiseConfig,
+    /// Entries taken from Razdfile.yml because only it changed them, or it won a conflict
+    pub from_razdfile: usize,
+    /// Entries taken from mise.toml because only it changed them, or it won a conflict
+    pub from_mise: usize,
+    /// Conflicts and the side chosen for each
+    pub resolved: Vec<(Conflict, Side)>,
+}
+
+/// Merge the mise sections of Razdfile.yml (`razdfile`) and mise.toml (`mise`)
+/// `resolve` picks the side of each conflict; returning None aborts the merge,
+/// in which case None is returned and nothing should be written.
+pub fn merge<F>(
+    base: &MiseConfig,
+    razdfile: &MiseConfig,
+    mise: &MiseConfig,
+    mut resolve: F,
+) -> Result<Option<Merge>>
+where
+    F: FnMut(&Conflict) -> Result<Option<Side>>,
+{
+    let mut merger = Merger {
+        resolve: &mut resolve,
+        from_razdfile: 0,
+        from_mise: 0,
+        resolved: Vec::new(),
+        aborted: false,
+    };
+
+    let min_version = merger
+        .entries(
+            "",
+            &min_version_entry(base),
+            &min_version_entry(razdfile),
+            &min_version_entry(mise),
+        )?
+        .and_then(|mut merged| merged.swap_remove("min_version"));
+
+    let config = MiseConfig {
+        min_version,
+        tools: merger.section("tools", &base.tools, &razdfile.tools, &mise.tools)?,
+        plugins: merger.section("plugins", &base.plugins, &razdfile.plugins, &mise.plugins)?,
+        env: merger.section("env", &base.env, &razdfile.env, &mise.env)?,
+        settings: merger.section(
+            "settings",
+            &base.settings,
+            &razdfile.settings,
+            &mise.settings,
+        )?,
+        alias: merger.section("alias", &base.alias, &razdfile.alias, &mise.alias)?,
+        tasks: merger.section("tasks", &base.tasks, &razdfile.tasks, &mise.tasks)?,
+        other: merger
+            .entries("", &base.other, &razdfile.other, &mise.other)?
+            .unwrap_or_default(),
+    };
+
+    if merger.aborted {
+        return Ok(None);
+    }
+    Ok(Some(Merge {
+        config,
+        from_razdfile: merger.from_razdfile,
+        from_mise: merger.from_mise,
+        resolved: merger.resolved,
+    }))
+}
+
+struct Merger<'a> {
+    resolve: &'a mut dyn FnMut(&Conflict) -> Result<Option<Side>>,
+    from_razdfile: usize,
+    from_mise: usize,
+    resolved: Vec<(Conflict, Side)>,
+    aborted: bool,
+}
+
+impl Merger<'_> {
+    /// Merge an optional section; a section left without entries is None
+    fn section<V: Clone + PartialEq + Serialize>(
+        &mut self,
+        name: &str,
+        base: &Option<IndexMap<String, V>>,
+        razdfile: &Option<IndexMap<String, V>>,
+        mise: &Option<IndexMap<String, V>>,
+    ) -> Result<Option<IndexMap<String, V>>> {
+        let empty = IndexMap::new();
+        let merged = self.entries(
+            &format!("{}.", name),
+            base.as_ref().unwrap_or(&empty),
+            razdfile.as_ref().unwrap_or(&empty),
+            mise.as_ref().unwrap_or(&empty),
+        )?;
+        Ok(merged.filter(|merged| !merged.is_empty()))
+    }
+
+    /// Merge the entries of one section, in Razdfile.yml order followed by entries only mise.toml has
+    /// Returns None once the resolver aborted.
+    fn entries<V: Clone + PartialEq + Serialize>(
+        &mut self,
+        prefix: &str,
+        base: &IndexMap<String, V>,
+        razdfile: &IndexMap<String, V>,
+        mise: &IndexMap<String, V>,
+    ) -> Result<Option<IndexMap<String, V>>> {
+        let mut merged = IndexMap::new();
+        let keys = razdfile
+            .keys()
+            .chain(mise.keys().filter(|key| !razdfile.contains_key(*key)))
+            .chain(
+                base.keys()
+                    .filter(|key| !razdfile.contains_key(*key) && !mise.contains_key(*key)),
+            );
+        for key in keys {
+            if self.aborted {
+                return Ok(None);
+            }
+            let (ancestor, ours, theirs) = (base.get(key), razdfile.get(key), mise.get(key));
+            let side = if ours == theirs {
+                Side::Razdfile
+            } else if theirs == ancestor {
+                self.from_razdfile += 1;
+                Side::Razdfile
+            } else if ours == ancestor {
+                self.from_mise += 1;
+                Side::Mise
+            } else {
+                let conflict = Conflict {
+                    key: format!("{}{}", prefix, key),
+                    razdfile: ours.map(describe),
+                    mise: theirs.map(describe),
+                };
+                match (self.resolve)(&conflict)? {
+                    Some(side) => {
+                        match side {
+                            Side::Razdfile => self.from_razdfile += 1,
+                            Side::Mise => self.from_mise += 1,
+                        }
+                        self.resolved.push((conflict, side));
+                        side
+                    }
+                    None => {
+                        self.aborted = true;
+                        return Ok(None);
+                    }
+                }
+            };
+            let value = match side {
+                Side::Razdfile => ours,
+                Side::Mise => theirs,
+            };
+            if let Some(value) = value {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+        Ok(Some(merged))
+    }
+}
+
+/// An entry a sync adds, changes or removes in one of the files
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    /// Dotted path of the entry, e.g. `tools.node` or `env.PORT`
+    pub key: String,
+    /// Current value; None when the entry is added
+    pub before: Option<String>,
+    /// Value after the sync; None when the entry is removed
+    pub after: Option<String>,
+}
+
+/// Entries that differ between `from` and `to`, in the order of `to` followed by removed entries
+pub fn diff(from: &MiseConfig, to: &MiseConfig) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_entries(
+        "",
+        &min_version_entry(from),
+        &min_version_entry(to),
+        &mut changes,
+    );
+    diff_section("tools", &from.tools, &to.tools, &mut changes);
+    diff_section("plugins", &from.plugins, &to.plugins, &mut changes);
+    diff_section("env", &from.env, &to.env, &mut changes);
+    diff_section("settings", &from.settings, &to.settings, &mut changes);
+    diff_section("alias", &from.alias, &to.alias, &mut changes);
+    diff_section("tasks", &from.tasks, &to.tasks, &mut changes);
+    diff_entries("", &from.other, &to.other, &mut changes);
+    changes
+}
+
+fn diff_section<V: PartialEq + Serialize>(
+    name: &str,
+    from: &Option<IndexMap<String, V>>,
+    to: &Option<IndexMap<String, V>>,
+    changes: &mut Vec<Change>,
+) {
+    let empty = IndexMap::new();
+    diff_entries(
+        &format!("{}.", name),
+        from.as_ref().unwrap_or(&empty),
+        to.as_ref().unwrap_or(&empty),
+        changes,
+    );
+}
+
+fn diff_entries<V: PartialEq + Serialize>(
+    prefix: &str,
+    from: &IndexMap<String, V>,
+    to: &IndexMap<String, V>,
+    changes: &mut Vec<Change>,
+) {
+    let keys = to
+        .keys()
+        .chain(from.keys().filter(|key| !to.contains_key(*key)));
+    for key in keys {
+        let (before, after) = (from.get(key), to.get(key));
+        if before != after {
+            changes.push(Change {
+                key: format!("{}{}", prefix, key),
+                before: before.map(describe),
+                after: after.map(describe),
+            });
+        }
+    }
+}
+
+/// Entries of `config` the root Razdfile has to declare itself: those it declares already
+/// in `root`, its own mise section, and those its includes do not provide with the same
+/// value. `resolved` is its mise section with the includes resolved.
+pub fn root_entries(config: &MiseConfig, root: &MiseConfig, resolved: &MiseConfig) -> MiseConfig {
+    MiseConfig {
+        min_version: own_entries(
+            &min_version_entry(config),
+            &min_version_entry(root),
+            &min_version_entry(resolved),
+        )
+        .swap_remove("min_version"),
+        tools: own_section(&config.tools, &root.tools, &resolved.tools),
+        plugins: own_section(&config.plugins, &root.plugins, &resolved.plugins),
+        env: own_section(&config.env, &root.env, &resolved.env),
+        settings: own_section(&config.settings, &root.settings, &resolved.settings),
+        alias: own_section(&config.alias, &root.alias, &resolved.alias),
+        tasks: own_section(&config.tasks, &root.tasks, &resolved.tasks),
+        other: own_entries(&config.other, &root.other, &resolved.other),
+    }
+}
+
+fn own_section<V: Clone + PartialEq>(
+    config: &Option<IndexMap<String, V>>,
+    root: &Option<IndexMap<String, V>>,
+    resolved: &Option<IndexMap<String, V>>,
+) -> Option<IndexMap<String, V>> {
+    let empty = IndexMap::new();
+    let entries = own_entries(
+        config.as_ref().unwrap_or(&empty),
+        root.as_ref().unwrap_or(&empty),
+        resolved.as_ref().unwrap_or(&empty),
+    );
+    (!entries.is_empty()).then_some(entries)
+}
+
+fn own_entries<V: Clone + PartialEq>(
+    config: &IndexMap<String, V>,
+    root: &IndexMap<String, V>,
+    resolved: &IndexMap<String, V>,
+) -> IndexMap<String, V> {
+    config
+        .iter()
+        .filter(|(key, value)| root.contains_key(*key) || resolved.get(*key) != Some(*value))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// `min_version` as a one-entry section, so it merges and diffs like the others
+fn min_version_entry(config: &MiseConfig) -> IndexMap<String, serde_yaml::Value> {
+    config
+        .min_version
+        .iter()
+        .map(|version| ("min_version".to_string(), version.clone()))
+        .collect()
+}
+
+/// Short one-line form of a value for conflict prompts and diffs
+fn describe<V: Serialize>(value: &V) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s,
+        Ok(other) => other.to_string(),
+        Err(_) => "?".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(yaml: &str) -> MiseConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn no_conflicts(conflict: &Conflict) -> Result<Option<Side>> {
+        panic!("unexpected conflict on {}", conflict.key)
+    }
+
+    #[test]
+    fn test_non_overlapping_edits_merge() {
+        let base = config("tools: {node: '20', python: '3.11', go: '1.21'}\nenv: {PORT: 3000}");
+        // Razdfile.yml bumps node and adds rust, mise.toml bumps python and drops go
+        let razdfile = config(
+            "tools: {node: '22', python: '3.11', go: '1.21', rust: '1.82'}\nenv: {PORT: 3000}",
+        );
+        let mise = config("tools: {node: '20', python: '3.12'}\nenv: {PORT: 3000, DEBUG: 1}");
+
+        let merge = merge(&base, &razdfile, &mise, no_conflicts)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            merge.config,
+            config(
+                "tools: {node: '22', python: '3.12', rust: '1.82'}\nenv: {PORT: 3000, DEBUG: 1}"
+            )
+        );
+        assert_eq!((merge.from_razdfile, merge.from_mise), (2, 3));
+        assert!(merge.resolved.is_empty());
+    }
+
+    #[test]
+    fn test_same_change_on_both_sides_is_not_a_conflict() {
+        let base = config("tools: {node: '20'}");
+        let both = config("tools: {node: '22'}");
+
+        let merge = merge(&base, &both, &both, no_conflicts).unwrap().unwrap();
+        assert_eq!(merge.config, both);
+    }
+
+    #[test]
+    fn test_conflicts_go_to_the_resolver() {
+        let base = config("min_version: 2024.1.0\ntools: {node: '20', python: '3.11'}");
+        let razdfile = config("min_version: 2024.1.0\ntools: {node: '22', python: '3.12'}");
+        let mise = config("min_version: 2024.9.0\ntools: {node: '21'}");
+
+        let mut seen = Vec::new();
+        let merge = merge(&base, &razdfile, &mise, |conflict| {
+            seen.push(conflict.clone());
+            Ok(Some(if conflict.key == "tools.node" {
+                Side::Mise
+            } else {
+                Side::Razdfile
+            }))
+        })
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(
+            seen,
+            [
+                Conflict {
+                    key: "tools.node".to_string(),
+                    razdfile: Some("22".to_string()),
+                    mise: Some("21".to_string()),
+                },
+                Conflict {
+                    key: "tools.python".to_string(),
+                    razdfile: Some("3.12".to_string()),
+                    mise: None,
+                },
+            ]
+        );
+        assert_eq!(
+            merge.config,
+            config("min_version: 2024.9.0\ntools: {node: '21', python: '3.12'}")
+        );
+        assert_eq!(merge.resolved.len(), 2);
+    }
+
+    #[test]
+    fn test_resolver_can_abort() {
+        let base = config("tools: {node: '20'}");
+        let razdfile = config("tools: {node: '22'}");
+        let mise = config("tools: {node: '21'}");
+
+        assert!(merge(&base, &razdfile, &mise, |_| Ok(None))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_root_entries_leave_out_what_includes_provide() {
+        let root = config("tools:\n  node: '22'\n");
+        let resolved =
+            config("tools:\n  node: '22'\n  go: '1.22'\n  python: '3.12'\nenv:\n  CI: 'true'\n");
+        let synced = config(
+            "tools:\n  node: '24'\n  go: '1.22'\n  python: '3.13'\n  rust: '1.77'\nenv:\n  CI: 'true'\n",
+        );
+
+        let own = root_entries(&synced, &root, &resolved);
+        assert_eq!(
+            own,
+            config("tools:\n  node: '24'\n  python: '3.13'\n  rust: '1.77'\n")
+        );
+    }
+
+    #[test]
+    fn test_diff_lists_added_changed_and_removed_entries() {
+        let from = config("tools: {node: '20', go: '1.21'}\nenv: {PORT: 3000}");
+        let to = config(
+            "min_version: 2024.9.0\ntools: {node: '22', rust: {version: '1.82'}}\nenv: {PORT: 3000}",
+        );
+
+        let change = |key: &str, before: Option<&str>, after: Option<&str>| Change {
+            key: key.to_string(),
+            before: before.map(String::from),
+            after: after.map(String::from),
+        };
+        assert_eq!(
+            diff(&from, &to),
+            [
+                change("min_version", None, Some("2024.9.0")),
+                change("tools.node", Some("20"), Some("22")),
+                change("tools.rust", None, Some("{\"version\":\"1.82\"}")),
+                change("tools.go", Some("1.21"), None),
+            ]
+        );
+        assert!(diff(&to, &to).is_empty());
+    }
+}
@@ -1,10 +1,15 @@
 use crate::config::file_tracker::{self, ChangeDetection};
-use crate::config::mise_generator::generate_mise_toml;
-use crate::config::razdfile::RazdfileConfig;
-use crate::core::{RazdError, Result};
+use crate::config::mise_generator::{generate_mise_toml, update_mise_toml};
+use crate::config::mise_merge::{self, Change, Conflict, Side};
+use crate::config::mise_validator::{self, OTHER_MISE_KEYS};
+use crate::config::razdfile::{MiseConfig, RazdfileConfig};
+use crate::config::version_files::{self, VersionFile};
+use crate::core::{output, RazdError, Result};
+use dialoguer::{theme::ColorfulTheme, Select};
 use indexmap::IndexMap;
+use serde::Serialize;
 use std::fs;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 use std::path::{Path, PathBuf};
 
 /// Configuration for sync behavior
@@ -16,6 +21,8 @@ pub struct SyncConfig {
     pub auto_approve: bool,
     /// Create backups before overwriting files
     pub create_backups: bool,
+    /// Side that wins entries both files changed differently, instead of asking
+    pub prefer: Option<Side>,
 }
 
 impl Default for SyncConfig {
@@ -24,12 +31,14 @@ impl Default for SyncConfig {
             no_sync: false,
             auto_approve: false,
             create_backups: true,
+            prefer: None,
         }
     }
 }
 
 /// Result of a sync operation
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SyncResult {
     /// No sync was needed
     NoChangesNeeded,
@@ -39,10 +48,34 @@ pub enum SyncResult {
     MiseToRazdfile,
     /// Sync was skipped (no-sync flag or user declined)
     Skipped,
+    /// Both files changed and their edits were merged into both
+    Merged,
+    /// Only the version files listed under `version_files:` were synced
+    VersionFiles,
     /// Conflict detected, user needs to resolve manually
     Conflict,
 }
 
+/// A version file listed in Razdfile.yml, as found before a sync
+struct PlannedFile {
+    file: VersionFile,
+    /// Current content; None when the file does not exist
+    existing: Option<String>,
+    /// Tool versions the file holds now
+    current: IndexMap<String, String>,
+}
+
+/// Outcome of syncing the version files with a mise section, see `plan_version_files`
+struct VersionFilePlan {
+    /// The mise section once the edits of the version files are imported
+    synced: MiseConfig,
+    /// Files whose edits are imported into Razdfile.yml, with the versions they hold after the sync
+    imported: Vec<(VersionFile, IndexMap<String, String>)>,
+    files: Vec<PlannedFile>,
+    /// Tool versions recorded at the last sync, per file name
+    bases: IndexMap<String, IndexMap<String, String>>,
+}
+
 /// Sync manager for coordinating file synchronization
 pub struct MiseSyncManager {
     project_root: PathBuf,
@@ -73,21 +106,349 @@ impl MiseSyncManager {
 
         let changes = file_tracker::check_file_changes(&self.project_root)?;
 
-        match changes {
-            ChangeDetection::NoChanges => Ok(SyncResult::NoChangesNeeded),
-            ChangeDetection::RazdfileChanged => self.sync_razdfile_to_mise(),
-            ChangeDetection::MiseTomlChanged => self.sync_mise_to_razdfile(),
-            ChangeDetection::BothChanged => self.handle_conflict(),
+        let result = match changes {
+            ChangeDetection::NoChanges => SyncResult::NoChangesNeeded,
+            ChangeDetection::RazdfileChanged => self.sync_razdfile_to_mise()?,
+            ChangeDetection::MiseTomlChanged => self.sync_mise_to_razdfile()?,
+            ChangeDetection::BothChanged => self.handle_conflict()?,
+        };
+        self.with_version_files(result, None)
+    }
+
+    /// Sync the version files after the mise.toml sync that gave `result`
+    /// Nothing is done when that sync was skipped or left a conflict.
+    fn with_version_files(&self, result: SyncResult, to: Option<Side>) -> Result<SyncResult> {
+        if matches!(result, SyncResult::Skipped | SyncResult::Conflict) {
+            return Ok(result);
+        }
+        let synced = self.sync_version_files(to)?;
+        Ok(match result {
+            SyncResult::NoChangesNeeded if synced => SyncResult::VersionFiles,
+            result => result,
+        })
+    }
+
+    /// Determine the sync `check_and_sync_if_needed` would perform, without writing anything
+    /// A conflict is reported as such even when `--yes` would resolve it in favor of Razdfile.yml
+    pub fn planned_sync(&self) -> Result<SyncResult> {
+        if self.config.no_sync {
+            return Ok(SyncResult::Skipped);
+        }
+
+        let razdfile_path = self.project_root.join("Razdfile.yml");
+        let mise_toml_path = self.project_root.join("mise.toml");
+
+        if !razdfile_path.exists() && !mise_toml_path.exists() {
+            return Ok(SyncResult::NoChangesNeeded);
+        }
+
+        match file_tracker::check_file_changes(&self.project_root)? {
+            ChangeDetection::NoChanges => {
+                let (razdfile, _) = self.current_sections()?;
+                if self.version_files_in_sync(&razdfile)? {
+                    Ok(SyncResult::NoChangesNeeded)
+                } else {
+                    Ok(SyncResult::VersionFiles)
+                }
+            }
+            ChangeDetection::RazdfileChanged => {
+                let has_mise_section = RazdfileConfig::load_with_includes(&razdfile_path)?
+                    .is_some_and(|razdfile| razdfile.mise.is_some());
+                if has_mise_section {
+                    Ok(SyncResult::RazdfileToMise)
+                } else if mise_toml_path.exists() {
+                    Ok(SyncResult::MiseToRazdfile)
+                } else {
+                    Ok(SyncResult::NoChangesNeeded)
+                }
+            }
+            ChangeDetection::MiseTomlChanged => Ok(SyncResult::MiseToRazdfile),
+            ChangeDetection::BothChanged => Ok(SyncResult::Conflict),
         }
     }
 
+    /// Sync towards `target` whatever changed since the last sync, as `razd sync --to` does
+    /// The version files are written from Razdfile.yml for `--to mise`, imported for `--to razdfile`.
+    pub fn sync_to(&self, target: Side) -> Result<SyncResult> {
+        let result = match target {
+            Side::Mise => {
+                let has_mise_section =
+                    RazdfileConfig::load_with_includes(self.project_root.join("Razdfile.yml"))?
+                        .ok_or_else(|| RazdError::config("Razdfile.yml not found"))?
+                        .mise
+                        .is_some();
+                if !has_mise_section {
+                    return Err(RazdError::config(
+                        "Razdfile.yml has no mise section to write to mise.toml",
+                    ));
+                }
+                self.sync_razdfile_to_mise()?
+            }
+            Side::Razdfile => {
+                if !self.project_root.join("mise.toml").exists() {
+                    return Err(RazdError::config("mise.toml not found"));
+                }
+                self.sync_mise_to_razdfile()?
+            }
+        };
+        self.with_version_files(result, Some(target))
+    }
+
+    /// Whether Razdfile.yml, mise.toml and the version files hold the same mise configuration,
+    /// whatever their formatting
+    pub fn in_sync(&self) -> Result<bool> {
+        let (razdfile, mise) = self.current_sections()?;
+        Ok(razdfile == mise && self.version_files_in_sync(&razdfile)?)
+    }
+
+    /// Entries a sync would change in each file, without writing anything
+    /// `to` forces the direction like `sync_to`; otherwise it is the one `planned_sync` picks.
+    /// When both files changed the merge is previewed with conflicts going to the `--prefer`
+    /// side, Razdfile.yml by default; the same goes for the version files.
+    pub fn planned_changes(&self, to: Option<Side>) -> Result<Vec<(String, Vec<Change>)>> {
+        let (razdfile, mise) = self.current_sections()?;
+        let prefer = self.config.prefer.unwrap_or(Side::Razdfile);
+
+        let target = match to {
+            Some(target) => Some(target),
+            None => match self.planned_sync()? {
+                SyncResult::MiseToRazdfile => Some(Side::Razdfile),
+                SyncResult::Conflict => None,
+                // Files that disagree although the tracking state says otherwise follow Razdfile.yml
+                _ => Some(Side::Mise),
+            },
+        };
+        let mut synced = match target {
+            Some(Side::Mise) => razdfile.clone(),
+            Some(Side::Razdfile) => mise.clone(),
+            None => {
+                let base = self.merge_base()?.unwrap_or_default();
+                mise_merge::merge(&base, &razdfile, &mise, |_| Ok(Some(prefer)))?
+                    .map_or_else(|| razdfile.clone(), |merge| merge.config)
+            }
+        };
+
+        let mut planned = Vec::new();
+        if let Some(plan) = self.plan_version_files(&synced, to, |_, _| Ok(Some(prefer)))? {
+            for planned_file in &plan.files {
+                let after = planned_file.file.tools_of(&plan.synced);
+                planned.push((
+                    planned_file.file.to_string(),
+                    mise_merge::diff(
+                        &version_files::as_config(&planned_file.current),
+                        &version_files::as_config(&after),
+                    ),
+                ));
+            }
+            synced = plan.synced;
+        }
+        planned.insert(
+            0,
+            (Side::Mise.to_string(), mise_merge::diff(&mise, &synced)),
+        );
+        planned.insert(
+            0,
+            (
+                Side::Razdfile.to_string(),
+                mise_merge::diff(&razdfile, &synced),
+            ),
+        );
+        Ok(planned
+            .into_iter()
+            .filter(|(_, changes)| !changes.is_empty())
+            .collect())
+    }
+
+    /// mise sections of Razdfile.yml, includes resolved, and of mise.toml
+    /// A missing file or section counts as an empty configuration.
+    fn current_sections(&self) -> Result<(MiseConfig, MiseConfig)> {
+        let razdfile_path = self.project_root.join("Razdfile.yml");
+        let mise_toml_path = self.project_root.join("mise.toml");
+
+        let razdfile = RazdfileConfig::load_with_includes(&razdfile_path)?
+            .and_then(|razdfile| razdfile.mise)
+            .unwrap_or_default();
+        let mise = if mise_toml_path.exists() {
+            let toml_content = fs::read_to_string(&mise_toml_path)
+                .map_err(|e| RazdError::config(format!("Failed to read mise.toml: {}", e)))?;
+            parse_mise_toml(&toml_content)?
+        } else {
+            MiseConfig::default()
+        };
+        Ok((razdfile, mise))
+    }
+
+    /// mise section both files agreed on at the last sync, if razd recorded one
+    fn merge_base(&self) -> Result<Option<MiseConfig>> {
+        Ok(
+            file_tracker::load_tracking_state(&self.project_root)?
+                .and_then(|state| state.mise_base),
+        )
+    }
+
+    /// Sync the version files listed under `version_files:` with `mise.tools` of Razdfile.yml
+    /// Edited files are imported into Razdfile.yml and mise.toml, then every file is written
+    /// from the result. Returns whether any file was written.
+    fn sync_version_files(&self, to: Option<Side>) -> Result<bool> {
+        let razdfile_path = self.project_root.join("Razdfile.yml");
+        let razdfile_mise = RazdfileConfig::load_with_includes(&razdfile_path)?
+            .and_then(|razdfile| razdfile.mise)
+            .unwrap_or_default();
+        let Some(plan) = self.plan_version_files(&razdfile_mise, to, |conflict, file| {
+            self.resolve_conflict(conflict, file.name())
+        })?
+        else {
+            return Ok(false);
+        };
+        let mut written = false;
+
+        if !plan.imported.is_empty() {
+            let mut razdfile = RazdfileConfig::load_from_path(&razdfile_path)?
+                .ok_or_else(|| RazdError::config("Failed to load Razdfile.yml"))?;
+            let mise = razdfile.mise.get_or_insert_with(MiseConfig::default);
+            for (file, versions) in &plan.imported {
+                file.apply(mise, versions);
+            }
+            self.write_razdfile(razdfile)?;
+            // mise.toml follows the imported versions
+            if let Some(mise) =
+                RazdfileConfig::load_with_includes(&razdfile_path)?.and_then(|r| r.mise)
+            {
+                self.write_mise_toml(&mise)?;
+            }
+            file_tracker::update_tracking_state(&self.project_root)?;
+
+            let files: Vec<&str> = plan.imported.iter().map(|(file, _)| file.name()).collect();
+            output::note(&format!("✓ Synced {} → Razdfile.yml", files.join(", ")));
+            written = true;
+        }
+
+        let mut bases = plan.bases.clone();
+        for planned in &plan.files {
+            let versions = planned.file.tools_of(&plan.synced);
+            let content = planned
+                .file
+                .render(planned.existing.as_deref(), &versions)?;
+            if content != planned.existing {
+                let path = self.project_root.join(planned.file.name());
+                self.backup_before_writing(&path, planned.file.name())?;
+                match content {
+                    Some(content) => fs::write(&path, content)?,
+                    None => fs::remove_file(&path)?,
+                }
+                output::note(&format!("✓ Synced Razdfile.yml → {}", planned.file));
+                written = true;
+            }
+            bases.insert(planned.file.name().to_string(), versions);
+        }
+        if bases != plan.bases {
+            file_tracker::record_version_files(&self.project_root, bases)?;
+        }
+        Ok(written)
+    }
+
+    /// Sync the version files listed in Razdfile.yml with `razdfile_mise` without writing anything
+    /// `to` picks the side like `sync_to`; otherwise each file is merged with `razdfile_mise`
+    /// against the versions recorded at the last sync, `resolve` picking the side of conflicts.
+    /// A file whose merge is aborted is left out. None when Razdfile.yml lists no version files.
+    fn plan_version_files<F>(
+        &self,
+        razdfile_mise: &MiseConfig,
+        to: Option<Side>,
+        mut resolve: F,
+    ) -> Result<Option<VersionFilePlan>>
+    where
+        F: FnMut(&Conflict, VersionFile) -> Result<Option<Side>>,
+    {
+        let files = RazdfileConfig::load_from_path(self.project_root.join("Razdfile.yml"))?
+            .and_then(|razdfile| razdfile.version_files)
+            .unwrap_or_default();
+        if files.is_empty() {
+            return Ok(None);
+        }
+
+        let mut plan = VersionFilePlan {
+            synced: razdfile_mise.clone(),
+            imported: Vec::new(),
+            files: Vec::new(),
+            bases: file_tracker::load_tracking_state(&self.project_root)?
+                .map(|state| state.version_files)
+                .unwrap_or_default(),
+        };
+        for file in files {
+            let existing = fs::read_to_string(self.project_root.join(file.name())).ok();
+            let current = match &existing {
+                Some(content) => file.parse(content)?,
+                None => IndexMap::new(),
+            };
+            let ours = file.tools_of(&plan.synced);
+            // A missing file is written again rather than read as all its tools removed
+            let theirs = if existing.is_some() {
+                current.clone()
+            } else {
+                ours.clone()
+            };
+
+            let merged = match to {
+                Some(Side::Mise) => ours.clone(),
+                Some(Side::Razdfile) => theirs,
+                None => {
+                    let base = plan
+                        .bases
+                        .get(file.name())
+                        .map(version_files::as_config)
+                        .unwrap_or_default();
+                    let merge = mise_merge::merge(
+                        &base,
+                        &version_files::as_config(&ours),
+                        &version_files::as_config(&theirs),
+                        |conflict| resolve(conflict, file),
+                    )?;
+                    match merge {
+                        Some(merge) => file.tools_of(&merge.config),
+                        None => {
+                            output::note(&format!(
+                                "Skipping {} sync. Please resolve manually.",
+                                file
+                            ));
+                            continue;
+                        }
+                    }
+                }
+            };
+            if merged != ours {
+                file.apply(&mut plan.synced, &merged);
+                plan.imported.push((file, merged));
+            }
+            plan.files.push(PlannedFile {
+                file,
+                existing,
+                current,
+            });
+        }
+        Ok(Some(plan))
+    }
+
+    /// Whether every version file listed in Razdfile.yml holds the versions of `razdfile_mise`
+    fn version_files_in_sync(&self, razdfile_mise: &MiseConfig) -> Result<bool> {
+        let Some(plan) =
+            self.plan_version_files(razdfile_mise, Some(Side::Mise), |_, _| Ok(None))?
+        else {
+            return Ok(true);
+        };
+        Ok(plan
+            .files
+            .iter()
+            .all(|planned| planned.current == planned.file.tools_of(razdfile_mise)))
+    }
+
     /// Sync Razdfile.yml mise config to mise.toml
     fn sync_razdfile_to_mise(&self) -> Result<SyncResult> {
         let razdfile_path = self.project_root.join("Razdfile.yml");
         let mise_toml_path = self.project_root.join("mise.toml");
 
         // Load Razdfile
-        let razdfile = RazdfileConfig::load_from_path(&razdfile_path)?
+        let razdfile = RazdfileConfig::load_with_includes(&razdfile_path)?
             .ok_or_else(|| RazdError::config("Razdfile.yml not found"))?;
 
         // Check if there's mise config to sync
@@ -97,8 +458,8 @@ impl MiseSyncManager {
                 // No mise config in Razdfile, but mise.toml exists
                 if mise_toml_path.exists() {
                     if !self.config.auto_approve {
-                        println!("⚠️  Razdfile.yml has no mise config, but mise.toml exists.");
-                        println!("   Sync mise.toml → Razdfile.yml? [Y/n]");
+                        output::note("⚠️  Razdfile.yml has no mise config, but mise.toml exists.");
+                        output::note("   Sync mise.toml → Razdfile.yml? [Y/n]");
 
                         if !self.prompt_user_approval()? {
                             return Ok(SyncResult::Skipped);
@@ -112,30 +473,16 @@ impl MiseSyncManager {
             }
         };
 
-        // Generate mise.toml content
-        let toml_content = generate_mise_toml(mise_config)?;
-
-        // Ask user about backup if file exists
-        if mise_toml_path.exists() && self.config.create_backups {
-            if !self.config.auto_approve {
-                println!("⚠️  mise.toml will be overwritten. Overwrite WITHOUT backup? [Y/n]");
-                if !self.prompt_user_approval()? {
-                    self.create_backup(&mise_toml_path)?;
-                }
-            } else {
-                // Auto-approve: create backup without prompt
-                self.create_backup(&mise_toml_path)?;
-            }
+        if !self.write_mise_toml(mise_config)? {
+            file_tracker::update_tracking_state(&self.project_root)?;
+            output::note("✓ mise.toml already matches Razdfile.yml");
+            return Ok(SyncResult::NoChangesNeeded);
         }
 
-        // Write mise.toml
-        let mut file = fs::File::create(&mise_toml_path)?;
-        file.write_all(toml_content.as_bytes())?;
-
         // Update tracking state
         file_tracker::update_tracking_state(&self.project_root)?;
 
-        println!("✓ Synced Razdfile.yml → mise.toml");
+        output::note("✓ Synced Razdfile.yml → mise.toml");
         Ok(SyncResult::RazdfileToMise)
     }
 
@@ -148,7 +495,8 @@ impl MiseSyncManager {
         let toml_content = fs::read_to_string(&mise_toml_path)
             .map_err(|e| RazdError::config(format!("Failed to read mise.toml: {}", e)))?;
 
-        let mise_config = self.parse_mise_toml(&toml_content)?;
+        let mise_config = parse_mise_toml(&toml_content)?;
+        warn_about_unknown_keys(&toml_content);
 
         // Load or create Razdfile
         let mut razdfile = if razdfile_path.exists() {
@@ -157,7 +505,7 @@ impl MiseSyncManager {
         } else {
             // Prompt user before creating new Razdfile
             if !self.config.auto_approve {
-                println!("⚠️  Razdfile.yml does not exist. Create it with mise config? [Y/n]");
+                output::note("⚠️  Razdfile.yml does not exist. Create it with mise config? [Y/n]");
                 if !self.prompt_user_approval()? {
                     return Ok(SyncResult::Skipped);
                 }
@@ -168,29 +516,184 @@ impl MiseSyncManager {
                 mise: None,
                 env: None,
                 vars: None,
+                dotenv: None,
+                includes: None,
+                output: None,
+                version_files: None,
                 tasks: IndexMap::new(),
             }
         };
 
-        // Update mise config
-        razdfile.mise = Some(mise_config);
+        // Update mise config; entries the includes provide unchanged stay with them
+        let root = razdfile.mise.clone().unwrap_or_default();
+        let resolved = RazdfileConfig::load_with_includes(&razdfile_path)?
+            .and_then(|razdfile| razdfile.mise)
+            .unwrap_or_default();
+        razdfile.mise = Some(mise_merge::root_entries(&mise_config, &root, &resolved));
+        self.write_razdfile(razdfile)?;
 
-        // Sort tasks in preferred order
-        razdfile.tasks = Self::sort_tasks(razdfile.tasks);
+        // Update tracking state
+        file_tracker::update_tracking_state(&self.project_root)?;
 
-        // Ask user about backup if file exists
-        if razdfile_path.exists() && self.config.create_backups {
-            if !self.config.auto_approve {
-                println!("⚠️  Razdfile.yml will be modified. Modify WITHOUT backup? [Y/n]");
-                if !self.prompt_user_approval()? {
-                    self.create_backup(&razdfile_path)?;
-                }
-            } else {
-                // Auto-approve: create backup without prompt
-                self.create_backup(&razdfile_path)?;
+        output::note("✓ Synced mise.toml → Razdfile.yml");
+        Ok(SyncResult::MiseToRazdfile)
+    }
+
+    /// Handle conflict when both files changed: three-way merge against the last synced state
+    fn handle_conflict(&self) -> Result<SyncResult> {
+        let razdfile_path = self.project_root.join("Razdfile.yml");
+        let mise_toml_path = self.project_root.join("mise.toml");
+
+        output::note("⚠️  Conflict detected: Both Razdfile.yml and mise.toml have been modified.");
+
+        let mut razdfile = RazdfileConfig::load_from_path(&razdfile_path)?
+            .ok_or_else(|| RazdError::config("Failed to load Razdfile.yml"))?;
+        let razdfile_mise =
+            RazdfileConfig::load_with_includes(&razdfile_path)?.and_then(|razdfile| razdfile.mise);
+        let toml_content = fs::read_to_string(&mise_toml_path)
+            .map_err(|e| RazdError::config(format!("Failed to read mise.toml: {}", e)))?;
+        let mise = parse_mise_toml(&toml_content)?;
+        warn_about_unknown_keys(&toml_content);
+
+        let base = match self.merge_base()? {
+            Some(base) => base,
+            None => {
+                output::note(
+                    "   No earlier sync to merge from: every entry that differs is a conflict.",
+                );
+                MiseConfig::default()
             }
+        };
+        // Without a mise section Razdfile.yml leaves the mise configuration to mise.toml
+        let ours = razdfile_mise.clone().unwrap_or_else(|| base.clone());
+
+        let Some(merge) = mise_merge::merge(&base, &ours, &mise, |conflict| {
+            self.resolve_conflict(conflict, "mise.toml")
+        })?
+        else {
+            output::note("Skipping sync. Please resolve manually.");
+            return Ok(SyncResult::Conflict);
+        };
+
+        // The root Razdfile keeps leaving to its includes what they provide
+        let root = razdfile.mise.clone().unwrap_or_default();
+        let own =
+            mise_merge::root_entries(&merge.config, &root, &razdfile_mise.unwrap_or_default());
+        if own != root {
+            razdfile.mise = Some(own);
+            self.write_razdfile(razdfile)?;
+        }
+        self.write_mise_toml(&merge.config)?;
+        file_tracker::update_tracking_state(&self.project_root)?;
+
+        let resolved = match merge.resolved.len() {
+            0 => String::new(),
+            1 => ", 1 conflict resolved".to_string(),
+            n => format!(", {} conflicts resolved", n),
+        };
+        output::note(&format!(
+            "✓ Merged Razdfile.yml and mise.toml ({} from Razdfile.yml, {} from mise.toml{})",
+            merge.from_razdfile, merge.from_mise, resolved
+        ));
+        Ok(SyncResult::Merged)
+    }
+
+    /// Pick the side of an entry both Razdfile.yml and `file` changed: `--prefer`, `--yes`
+    /// (Razdfile.yml) or a prompt. `--prefer mise` picks `file`, mise.toml or a version file.
+    /// Returns None when the user skips the sync or there is no terminal to ask on.
+    fn resolve_conflict(&self, conflict: &Conflict, file: &str) -> Result<Option<Side>> {
+        let value =
+            |value: &Option<String>| value.clone().unwrap_or_else(|| "(removed)".to_string());
+        let razdfile = value(&conflict.razdfile);
+        let mise = value(&conflict.mise);
+
+        let preferred = match self.config.prefer {
+            Some(side) => Some(side),
+            None if self.config.auto_approve => Some(Side::Razdfile),
+            None => None,
+        };
+        if let Some(side) = preferred {
+            let (kept, from, dropped) = match side {
+                Side::Razdfile => (&razdfile, "Razdfile.yml", &mise),
+                Side::Mise => (&mise, file, &razdfile),
+            };
+            output::note(&format!(
+                "   {}: keeping {} from {} over {}",
+                conflict.key, kept, from, dropped
+            ));
+            return Ok(Some(side));
         }
 
+        if !std::io::stdin().is_terminal() {
+            output::note(&format!(
+                "   {}: Razdfile.yml has {}, {} has {}",
+                conflict.key, razdfile, file, mise
+            ));
+            output::note("   Use --prefer razdfile or --prefer mise to resolve conflicts without a terminal.");
+            return Ok(None);
+        }
+
+        let items = [
+            format!("Keep Razdfile.yml: {}", razdfile),
+            format!("Keep {}: {}", file, mise),
+            "Skip sync (resolve manually)".to_string(),
+        ];
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "{} changed in Razdfile.yml and {}",
+                conflict.key, file
+            ))
+            .items(&items)
+            .default(0)
+            .interact()
+            .map_err(|e| RazdError::config(format!("Failed to get user input: {}", e)))?;
+
+        Ok(match selection {
+            0 => Some(Side::Razdfile),
+            1 => Some(Side::Mise),
+            _ => None,
+        })
+    }
+
+    /// Write mise.toml for `mise_config`, patching the existing file so its comments and
+    /// formatting survive. Returns false when the file already matched and was left alone.
+    fn write_mise_toml(&self, mise_config: &MiseConfig) -> Result<bool> {
+        let mise_toml_path = self.project_root.join("mise.toml");
+
+        let existing = fs::read_to_string(&mise_toml_path).ok();
+        let toml_content = match existing
+            .as_deref()
+            .map(|e| update_mise_toml(e, mise_config))
+        {
+            Some(Ok(content)) => content,
+            Some(Err(e)) => {
+                output::warning(&format!("{}; regenerating mise.toml", e));
+                generate_mise_toml(mise_config)?
+            }
+            None => generate_mise_toml(mise_config)?,
+        };
+
+        if existing.as_deref() == Some(toml_content.as_str()) {
+            return Ok(false);
+        }
+
+        self.backup_before_writing(&mise_toml_path, "mise.toml")?;
+
+        // Write mise.toml
+        let mut file = fs::File::create(&mise_toml_path)?;
+        file.write_all(toml_content.as_bytes())?;
+        Ok(true)
+    }
+
+    /// Write Razdfile.yml, with its tasks in the preferred order
+    fn write_razdfile(&self, mut razdfile: RazdfileConfig) -> Result<()> {
+        let razdfile_path = self.project_root.join("Razdfile.yml");
+
+        // Sort tasks in preferred order
+        razdfile.tasks = Self::sort_tasks(razdfile.tasks);
+
+        self.backup_before_writing(&razdfile_path, "Razdfile.yml")?;
+
         // Write Razdfile
         let yaml_content = serde_yaml::to_string(&razdfile)
             .map_err(|e| RazdError::config(format!("Failed to serialize Razdfile: {}", e)))?;
@@ -200,43 +703,25 @@ impl MiseSyncManager {
 
         let mut file = fs::File::create(&razdfile_path)?;
         file.write_all(formatted_yaml.as_bytes())?;
-
-        // Update tracking state
-        file_tracker::update_tracking_state(&self.project_root)?;
-
-        println!("✓ Synced mise.toml → Razdfile.yml");
-        Ok(SyncResult::MiseToRazdfile)
+        Ok(())
     }
 
-    /// Handle conflict when both files changed
-    fn handle_conflict(&self) -> Result<SyncResult> {
-        println!("⚠️  Conflict detected: Both Razdfile.yml and mise.toml have been modified.");
-
-        if self.config.auto_approve {
-            println!("   Auto-approve enabled: preferring Razdfile.yml as source of truth.");
-            return self.sync_razdfile_to_mise();
+    /// Back up an existing file about to be overwritten, unless the user declines
+    fn backup_before_writing(&self, file_path: &Path, name: &str) -> Result<()> {
+        if !file_path.exists() || !self.config.create_backups {
+            return Ok(());
         }
-
-        println!("\nOptions:");
-        println!("  1) Use Razdfile.yml (overwrite mise.toml)");
-        println!("  2) Use mise.toml (update Razdfile.yml)");
-        println!("  3) Skip sync (resolve manually)");
-        println!("\nYour choice [1-3]:");
-
-        // Read user input
-        let mut input = String::new();
-        std::io::stdin()
-            .read_line(&mut input)
-            .map_err(|e| RazdError::config(format!("Failed to read input: {}", e)))?;
-
-        match input.trim() {
-            "1" => self.sync_razdfile_to_mise(),
-            "2" => self.sync_mise_to_razdfile(),
-            _ => {
-                println!("Skipping sync. Please resolve manually.");
-                Ok(SyncResult::Conflict)
+        if !self.config.auto_approve {
+            output::note(&format!(
+                "⚠️  {} will be overwritten. Overwrite WITHOUT backup? [Y/n]",
+                name
+            ));
+            if self.prompt_user_approval()? {
+                return Ok(());
             }
         }
+        // Auto-approve: create backup without prompt
+        self.create_backup(file_path)
     }
 
     /// Create backup of a file
@@ -253,108 +738,10 @@ impl MiseSyncManager {
         fs::copy(file_path, &backup_path)
             .map_err(|e| RazdError::config(format!("Failed to create backup: {}", e)))?;
 
-        println!("  Created backup: {}", backup_path.display());
+        output::note(&format!("  Created backup: {}", backup_path.display()));
         Ok(())
     }
 
-    /// Parse mise.toml into MiseConfig structure
-    fn parse_mise_toml(&self, toml_content: &str) -> Result<crate::config::razdfile::MiseConfig> {
-        use crate::config::razdfile::{MiseConfig, ToolConfig};
-
-        let doc: toml::Value = toml::from_str(toml_content)
-            .map_err(|e| RazdError::config(format!("Invalid mise.toml: {}", e)))?;
-
-        let mut tools = IndexMap::new();
-        let mut plugins = IndexMap::new();
-
-        // Parse [tools] section
-        if let Some(tools_table) = doc.get("tools").and_then(|v| v.as_table()) {
-            for (name, value) in tools_table {
-                let tool_config = match value {
-                    toml::Value::String(version) => ToolConfig::Simple(version.clone()),
-                    toml::Value::Table(table) => {
-                        let version = table
-                            .get("version")
-                            .and_then(|v| v.as_str())
-                            .ok_or_else(|| {
-                                RazdError::config(format!(
-                                    "Tool '{}' is missing 'version' field",
-                                    name
-                                ))
-                            })?
-                            .to_string();
-
-                        let postinstall = table
-                            .get("postinstall")
-                            .and_then(|v| v.as_str())
-                            .map(String::from);
-
-                        let os = table.get("os").and_then(|v| match v {
-                            toml::Value::String(s) => Some(vec![s.clone()]),
-                            toml::Value::Array(arr) => {
-                                let os_list: Vec<String> = arr
-                                    .iter()
-                                    .filter_map(|item| item.as_str().map(String::from))
-                                    .collect();
-                                if os_list.is_empty() {
-                                    None
-                                } else {
-                                    Some(os_list)
-                                }
-                            }
-                            _ => None,
-                        });
-
-                        let install_env =
-                            table
-                                .get("install_env")
-                                .and_then(|v| v.as_table())
-                                .map(|env_table| {
-                                    env_table
-                                        .iter()
-                                        .map(|(k, v)| {
-                                            (k.clone(), v.as_str().unwrap_or("").to_string())
-                                        })
-                                        .collect()
-                                });
-
-                        ToolConfig::Complex {
-                            version,
-                            postinstall,
-                            os,
-                            install_env,
-                        }
-                    }
-                    _ => {
-                        return Err(RazdError::config(format!(
-                            "Invalid tool config for '{}'",
-                            name
-                        )))
-                    }
-                };
-                tools.insert(name.clone(), tool_config);
-            }
-        }
-
-        // Parse [plugins] section
-        if let Some(plugins_table) = doc.get("plugins").and_then(|v| v.as_table()) {
-            for (name, value) in plugins_table {
-                if let Some(url) = value.as_str() {
-                    plugins.insert(name.clone(), url.to_string());
-                }
-            }
-        }
-
-        Ok(MiseConfig {
-            tools: if tools.is_empty() { None } else { Some(tools) },
-            plugins: if plugins.is_empty() {
-                None
-            } else {
-                Some(plugins)
-            },
-        })
-    }
-
     /// Prompt user for approval (returns true if approved)
     fn prompt_user_approval(&self) -> Result<bool> {
         let mut input = String::new();
@@ -421,7 +808,7 @@ impl MiseSyncManager {
             .iter()
             .filter(|(name, _)| !preferred_order.contains(&name.as_str()))
             .collect();
-        remaining.sort_by(|(a, _), (b, _)| a.cmp(b));
+        remaining.sort_by_key(|(a, _)| *a);
 
         for (name, config) in remaining {
             sorted.insert(name.clone(), config.clone());
@@ -431,9 +818,176 @@ impl MiseSyncManager {
     }
 }
 
+/// Parse mise.toml into MiseConfig structure
+pub fn parse_mise_toml(toml_content: &str) -> Result<MiseConfig> {
+    use crate::config::razdfile::ToolConfig;
+
+    let doc: toml::Value = toml::from_str(toml_content)
+        .map_err(|e| RazdError::config(format!("Invalid mise.toml: {}", e)))?;
+
+    let mut tools = IndexMap::new();
+    let mut plugins = IndexMap::new();
+
+    // Parse [tools] section
+    if let Some(tools_table) = doc.get("tools").and_then(|v| v.as_table()) {
+        for (name, value) in tools_table {
+            let tool_config = match value {
+                toml::Value::String(version) => ToolConfig::Simple(version.clone()),
+                toml::Value::Table(table) => {
+                    let version = table
+                        .get("version")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| {
+                            RazdError::config(format!("Tool '{}' is missing 'version' field", name))
+                        })?
+                        .to_string();
+
+                    let postinstall = table
+                        .get("postinstall")
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
+
+                    let os = table.get("os").and_then(|v| match v {
+                        toml::Value::String(s) => Some(vec![s.clone()]),
+                        toml::Value::Array(arr) => {
+                            let os_list: Vec<String> = arr
+                                .iter()
+                                .filter_map(|item| item.as_str().map(String::from))
+                                .collect();
+                            if os_list.is_empty() {
+                                None
+                            } else {
+                                Some(os_list)
+                            }
+                        }
+                        _ => None,
+                    });
+
+                    let install_env =
+                        table
+                            .get("install_env")
+                            .and_then(|v| v.as_table())
+                            .map(|env_table| {
+                                env_table
+                                    .iter()
+                                    .map(|(k, v)| (k.clone(), v.as_str().unwrap_or("").to_string()))
+                                    .collect()
+                            });
+
+                    ToolConfig::Complex {
+                        version,
+                        postinstall,
+                        os,
+                        install_env,
+                    }
+                }
+                _ => {
+                    return Err(RazdError::config(format!(
+                        "Invalid tool config for '{}'",
+                        name
+                    )))
+                }
+            };
+            tools.insert(name.clone(), tool_config);
+        }
+    }
+
+    // Parse [plugins] section
+    if let Some(plugins_table) = doc.get("plugins").and_then(|v| v.as_table()) {
+        for (name, value) in plugins_table {
+            if let Some(url) = value.as_str() {
+                plugins.insert(name.clone(), url.to_string());
+            }
+        }
+    }
+
+    let mut config = MiseConfig {
+        tools: if tools.is_empty() { None } else { Some(tools) },
+        plugins: if plugins.is_empty() {
+            None
+        } else {
+            Some(plugins)
+        },
+        ..Default::default()
+    };
+    if let toml::Value::Table(ref table) = doc {
+        parse_other_keys(table, &mut config)?;
+    }
+    Ok(config)
+}
+
+/// Warn about top-level keys of mise.toml that razd leaves out of Razdfile.yml
+fn warn_about_unknown_keys(toml_content: &str) {
+    let Ok(doc) = toml_content.parse::<toml::Table>() else {
+        return;
+    };
+    for key in doc.keys().filter(|key| !mise_validator::is_synced_key(key)) {
+        output::warning(&format!(
+            "Unknown mise.toml key '{}' is kept in mise.toml but not synced to Razdfile.yml",
+            key
+        ));
+    }
+}
+
+/// Read the top-level mise.toml keys besides `[tools]` and `[plugins]` into `config`
+/// Keys razd does not know are skipped, so they never end up in Razdfile.yml.
+pub(crate) fn parse_other_keys(doc: &toml::Table, config: &mut MiseConfig) -> Result<()> {
+    for (key, value) in doc {
+        let section = match key.as_str() {
+            "tools" | "plugins" => continue,
+            "min_version" => {
+                config.min_version = Some(toml_to_yaml(value));
+                continue;
+            }
+            "env" => &mut config.env,
+            "settings" => &mut config.settings,
+            "alias" => &mut config.alias,
+            "tasks" => &mut config.tasks,
+            _ if OTHER_MISE_KEYS.contains(&key.as_str()) => {
+                config.other.insert(key.clone(), toml_to_yaml(value));
+                continue;
+            }
+            // Left to mise; imports warn about them
+            _ => continue,
+        };
+        let table = value.as_table().ok_or_else(|| {
+            RazdError::config(format!("Invalid mise.toml: [{}] must be a table", key))
+        })?;
+        *section = Some(
+            table
+                .iter()
+                .map(|(name, value)| (name.clone(), toml_to_yaml(value)))
+                .collect(),
+        );
+    }
+    Ok(())
+}
+
+/// Convert a TOML value to the YAML value stored in Razdfile.yml
+/// YAML has no datetime type, so TOML datetimes become strings.
+pub(crate) fn toml_to_yaml(value: &toml::Value) -> serde_yaml::Value {
+    use serde_yaml::Value;
+
+    match value {
+        toml::Value::String(s) => Value::String(s.clone()),
+        toml::Value::Integer(i) => Value::Number((*i).into()),
+        toml::Value::Float(f) => Value::Number((*f).into()),
+        toml::Value::Boolean(b) => Value::Bool(*b),
+        toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+        toml::Value::Array(items) => Value::Sequence(items.iter().map(toml_to_yaml).collect()),
+        toml::Value::Table(table) => Value::Mapping(
+            table
+                .iter()
+                .map(|(k, v)| (Value::String(k.clone()), toml_to_yaml(v)))
+                .collect(),
+        ),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::razdfile::ToolConfig;
     use tempfile::TempDir;
 
     fn create_test_razdfile(dir: &Path) -> Result<()> {
@@ -456,6 +1010,7 @@ mod tests {
         let mise_config = crate::config::razdfile::MiseConfig {
             tools: Some(tools),
             plugins: Some(plugins),
+            ..Default::default()
         };
 
         let razdfile = crate::config::razdfile::RazdfileConfig {
@@ -463,6 +1018,10 @@ mod tests {
             mise: Some(mise_config),
             env: None,
             vars: None,
+            dotenv: None,
+            includes: None,
+            output: None,
+            version_files: None,
             tasks: IndexMap::new(),
         };
 
@@ -482,6 +1041,7 @@ mod tests {
             no_sync: false,
             auto_approve: true,
             create_backups: false,
+            prefer: None,
         };
 
         let manager = MiseSyncManager::new(project_root.clone(), config);
@@ -496,6 +1056,281 @@ mod tests {
         assert!(mise_content.contains("python"));
     }
 
+    #[test]
+    fn test_sync_razdfile_to_mise_keeps_comments() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_root = temp_dir.path().to_path_buf();
+
+        create_test_razdfile(&project_root).unwrap();
+        fs::write(
+            project_root.join("mise.toml"),
+            "[tools]\nnode = \"20\" # pinned for the CI image\npython = \"3.11\"\n",
+        )
+        .unwrap();
+
+        let config = SyncConfig {
+            no_sync: false,
+            auto_approve: true,
+            create_backups: false,
+            prefer: None,
+        };
+
+        let manager = MiseSyncManager::new(project_root.clone(), config);
+        assert_eq!(
+            manager.sync_razdfile_to_mise().unwrap(),
+            SyncResult::RazdfileToMise
+        );
+
+        let mise_content = fs::read_to_string(project_root.join("mise.toml")).unwrap();
+        assert!(mise_content
+            .starts_with("[tools]\nnode = \"22\" # pinned for the CI image\npython = \"3.11\"\n"));
+        assert!(mise_content.contains("[plugins]"));
+    }
+
+    #[test]
+    fn test_conflict_merges_edits_of_both_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_root = temp_dir.path().to_path_buf();
+
+        create_test_razdfile(&project_root).unwrap();
+        let config = SyncConfig {
+            no_sync: false,
+            auto_approve: true,
+            create_backups: false,
+            prefer: Some(Side::Mise),
+        };
+        let manager = MiseSyncManager::new(project_root.clone(), config);
+        manager.sync_razdfile_to_mise().unwrap();
+
+        // Razdfile.yml bumps node and python, mise.toml bumps python differently and adds go
+        let razdfile_path = project_root.join("Razdfile.yml");
+        let razdfile = fs::read_to_string(&razdfile_path).unwrap();
+        fs::write(
+            &razdfile_path,
+            razdfile
+                .replace("node: '22'", "node: '24'")
+                .replace("python: '3.11'", "python: '3.12'"),
+        )
+        .unwrap();
+        let mise_path = project_root.join("mise.toml");
+        let mise = fs::read_to_string(&mise_path).unwrap();
+        fs::write(
+            &mise_path,
+            mise.replace("python = \"3.11\"", "python = \"3.13\"\ngo = \"1.23\""),
+        )
+        .unwrap();
+
+        assert_eq!(
+            manager.check_and_sync_if_needed().unwrap(),
+            SyncResult::Merged
+        );
+
+        let mise_content = fs::read_to_string(&mise_path).unwrap();
+        assert!(
+            mise_content.starts_with("[tools]\nnode = \"24\"\npython = \"3.13\"\ngo = \"1.23\"\n")
+        );
+        let tools = RazdfileConfig::load_from_path(&razdfile_path)
+            .unwrap()
+            .unwrap()
+            .mise
+            .unwrap()
+            .tools
+            .unwrap();
+        assert_eq!(
+            tools.get("python"),
+            Some(&ToolConfig::Simple("3.13".to_string()))
+        );
+        assert_eq!(
+            tools.get("go"),
+            Some(&ToolConfig::Simple("1.23".to_string()))
+        );
+        assert_eq!(
+            manager.check_and_sync_if_needed().unwrap(),
+            SyncResult::NoChangesNeeded
+        );
+    }
+
+    #[test]
+    fn test_merge_leaves_included_entries_to_includes() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_root = temp_dir.path().to_path_buf();
+
+        fs::create_dir(project_root.join("shared")).unwrap();
+        fs::write(
+            project_root.join("shared/Razdfile.yml"),
+            "version: '3'\nmise:\n  tools:\n    go: '1.22'\ntasks: {}\n",
+        )
+        .unwrap();
+        let razdfile_path = project_root.join("Razdfile.yml");
+        fs::write(
+            &razdfile_path,
+            "version: '3'\nincludes:\n  shared: shared/Razdfile.yml\nmise:\n  tools:\n    node: '22'\ntasks: {}\n",
+        )
+        .unwrap();
+        let config = SyncConfig {
+            no_sync: false,
+            auto_approve: true,
+            create_backups: false,
+            prefer: Some(Side::Mise),
+        };
+        let manager = MiseSyncManager::new(project_root.clone(), config);
+        manager.check_and_sync_if_needed().unwrap();
+
+        let razdfile = fs::read_to_string(&razdfile_path).unwrap();
+        fs::write(&razdfile_path, razdfile.replace("node: '22'", "node: '24'")).unwrap();
+        let mise_path = project_root.join("mise.toml");
+        let mise = fs::read_to_string(&mise_path).unwrap();
+        fs::write(&mise_path, format!("{}python = \"3.12\"\n", mise)).unwrap();
+
+        assert_eq!(
+            manager.check_and_sync_if_needed().unwrap(),
+            SyncResult::Merged
+        );
+        let tools = RazdfileConfig::load_from_path(&razdfile_path)
+            .unwrap()
+            .unwrap()
+            .mise
+            .unwrap()
+            .tools
+            .unwrap();
+        assert_eq!(tools.keys().collect::<Vec<_>>(), ["node", "python"]);
+        assert!(fs::read_to_string(&mise_path)
+            .unwrap()
+            .contains("go = \"1.22\""));
+    }
+
+    #[test]
+    fn test_sync_mise_to_razdfile_leaves_included_entries_to_includes() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_root = temp_dir.path().to_path_buf();
+
+        fs::create_dir(project_root.join("shared")).unwrap();
+        fs::write(
+            project_root.join("shared/Razdfile.yml"),
+            "version: '3'\nmise:\n  tools:\n    go: '1.22'\n    rust: '1.77'\ntasks: {}\n",
+        )
+        .unwrap();
+        let razdfile_path = project_root.join("Razdfile.yml");
+        fs::write(
+            &razdfile_path,
+            "version: '3'\nincludes:\n  shared: shared/Razdfile.yml\nmise:\n  tools:\n    node: '22'\ntasks: {}\n",
+        )
+        .unwrap();
+        fs::write(
+            project_root.join("mise.toml"),
+            "[tools]\nnode = \"24\"\ngo = \"1.22\"\nrust = \"1.78\"\n",
+        )
+        .unwrap();
+
+        let config = SyncConfig {
+            no_sync: false,
+            auto_approve: true,
+            create_backups: false,
+            prefer: None,
+        };
+        let manager = MiseSyncManager::new(project_root.clone(), config);
+        assert_eq!(
+            manager.sync_mise_to_razdfile().unwrap(),
+            SyncResult::MiseToRazdfile
+        );
+
+        // go comes unchanged from the include; the rust bump overrides it in the root
+        let tools = RazdfileConfig::load_from_path(&razdfile_path)
+            .unwrap()
+            .unwrap()
+            .mise
+            .unwrap()
+            .tools
+            .unwrap();
+        assert_eq!(tools.keys().collect::<Vec<_>>(), ["node", "rust"]);
+        assert_eq!(
+            tools.get("rust"),
+            Some(&ToolConfig::Simple("1.78".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_version_files_are_written_and_imported() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_root = temp_dir.path().to_path_buf();
+
+        create_test_razdfile(&project_root).unwrap();
+        let razdfile_path = project_root.join("Razdfile.yml");
+        let razdfile = fs::read_to_string(&razdfile_path).unwrap();
+        fs::write(
+            &razdfile_path,
+            format!("{}version_files:\n- .tool-versions\n- .nvmrc\n", razdfile),
+        )
+        .unwrap();
+        let config = SyncConfig {
+            no_sync: false,
+            auto_approve: true,
+            create_backups: false,
+            prefer: None,
+        };
+        let manager = MiseSyncManager::new(project_root.clone(), config);
+
+        assert_eq!(
+            manager.check_and_sync_if_needed().unwrap(),
+            SyncResult::RazdfileToMise
+        );
+        assert_eq!(
+            fs::read_to_string(project_root.join(".tool-versions")).unwrap(),
+            "node 22\npython 3.11\n"
+        );
+        assert_eq!(
+            fs::read_to_string(project_root.join(".nvmrc")).unwrap(),
+            "22\n"
+        );
+        assert!(manager.in_sync().unwrap());
+
+        // An nvm user bumps node: Razdfile.yml, mise.toml and .tool-versions follow
+        fs::write(project_root.join(".nvmrc"), "v24.1.0\n").unwrap();
+        assert!(!manager.in_sync().unwrap());
+        assert_eq!(
+            manager.check_and_sync_if_needed().unwrap(),
+            SyncResult::VersionFiles
+        );
+        let tools = RazdfileConfig::load_from_path(&razdfile_path)
+            .unwrap()
+            .unwrap()
+            .mise
+            .unwrap()
+            .tools
+            .unwrap();
+        assert_eq!(
+            tools.get("node"),
+            Some(&ToolConfig::Simple("24.1.0".to_string()))
+        );
+        assert!(fs::read_to_string(project_root.join("mise.toml"))
+            .unwrap()
+            .contains("node = \"24.1.0\""));
+        assert_eq!(
+            fs::read_to_string(project_root.join(".tool-versions")).unwrap(),
+            "node 24.1.0\npython 3.11\n"
+        );
+        assert_eq!(
+            fs::read_to_string(project_root.join(".nvmrc")).unwrap(),
+            "v24.1.0\n"
+        );
+        assert_eq!(
+            manager.check_and_sync_if_needed().unwrap(),
+            SyncResult::NoChangesNeeded
+        );
+    }
+
+    #[test]
+    fn test_planned_sync_does_not_write() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_root = temp_dir.path().to_path_buf();
+
+        create_test_razdfile(&project_root).unwrap();
+
+        let manager = MiseSyncManager::new(project_root.clone(), SyncConfig::default());
+        assert_eq!(manager.planned_sync().unwrap(), SyncResult::RazdfileToMise);
+        assert!(!project_root.join("mise.toml").exists());
+    }
+
     #[test]
     fn test_sync_mise_to_razdfile() {
         let temp_dir = TempDir::new().unwrap();
@@ -516,6 +1351,7 @@ node = "https://github.com/asdf-vm/asdf-nodejs.git"
             no_sync: false,
             auto_approve: true,
             create_backups: false,
+            prefer: None,
         };
 
         let manager = MiseSyncManager::new(project_root.clone(), config);
@@ -546,6 +1382,7 @@ node = "https://github.com/asdf-vm/asdf-nodejs.git"
             no_sync: true,
             auto_approve: false,
             create_backups: false,
+            prefer: None,
         };
 
         let manager = MiseSyncManager::new(project_root.clone(), config);
@@ -557,9 +1394,6 @@ node = "https://github.com/asdf-vm/asdf-nodejs.git"
 
     #[test]
     fn test_parse_complex_mise_toml() {
-        let temp_dir = TempDir::new().unwrap();
-        let project_root = temp_dir.path().to_path_buf();
-
         let mise_content = r#"
 [tools]
 python = "3.11"
@@ -569,9 +1403,7 @@ node = { version = "22", postinstall = "corepack enable" }
 node = "https://github.com/asdf-vm/asdf-nodejs.git"
 "#;
 
-        let config = SyncConfig::default();
-        let manager = MiseSyncManager::new(project_root, config);
-        let parsed = manager.parse_mise_toml(mise_content).unwrap();
+        let parsed = parse_mise_toml(mise_content).unwrap();
 
         assert!(parsed.tools.is_some());
         let tools = parsed.tools.unwrap();
@@ -585,4 +1417,58 @@ node = "https://github.com/asdf-vm/asdf-nodejs.git"
             Some(crate::config::razdfile::ToolConfig::Complex { .. })
         ));
     }
+
+    #[test]
+    fn test_mise_toml_round_trips_every_section() {
+        let mise_content = r#"
+min_version = "2024.9.5"
+
+[env]
+PORT = 3000
+_.path = ["./bin"]
+
+[tools]
+node = "22"
+
+[settings]
+experimental = true
+
+[settings.python]
+uv_venv_auto = true
+
+[alias.node.versions]
+lts = "22"
+
+[tasks.build]
+run = ["cargo build", "echo done"]
+depends = ["lint"]
+
+[hooks]
+enter = "echo hi"
+"#;
+
+        let parsed = parse_mise_toml(mise_content).unwrap();
+        assert!(parsed.other.contains_key("hooks"));
+
+        // Through Razdfile.yml and back to mise.toml
+        let yaml = serde_yaml::to_string(&parsed).unwrap();
+        let from_yaml: MiseConfig = serde_yaml::from_str(&yaml).unwrap();
+        let generated = generate_mise_toml(&from_yaml).unwrap();
+
+        let original: toml::Value = toml::from_str(mise_content).unwrap();
+        let regenerated: toml::Value = toml::from_str(&generated).unwrap();
+        assert_eq!(original, regenerated);
+    }
+
+    #[test]
+    fn test_parse_leaves_out_unknown_keys() {
+        let parsed =
+            parse_mise_toml("[tools]\nnode = \"22\"\n\n[monorepo]\nconfig_roots = []\n").unwrap();
+        assert!(parsed.other.is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_non_table_env() {
+        assert!(parse_mise_toml("env = \"production\"\n").is_err());
+    }
 }
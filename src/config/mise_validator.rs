@@ -215,6 +215,39 @@ pub fn validate_plugin_url(url: &str) -> Result<(), RazdError> {
     Ok(())
 }
 
+/// Top-level mise.toml keys razd passes through as is, besides the sections
+/// `MiseConfig` has fields for
+pub const OTHER_MISE_KEYS: &[&str] = &[
+    "vars",
+    "hooks",
+    "watch_files",
+    "redactions",
+    "task_config",
+    "tool_alias",
+    "shell_alias",
+];
+
+/// Whether razd syncs the top-level mise.toml `key` with Razdfile.yml
+pub fn is_synced_key(key: &str) -> bool {
+    matches!(
+        key,
+        "min_version" | "tools" | "plugins" | "env" | "settings" | "alias" | "tasks"
+    ) || OTHER_MISE_KEYS.contains(&key)
+}
+
+/// Validate a top-level key of the `mise:` section that has no field of its own
+/// Unknown keys are most likely typos (`tool:`, `setings:`) mise would ignore.
+pub fn validate_other_key(key: &str) -> Result<(), RazdError> {
+    if OTHER_MISE_KEYS.contains(&key) {
+        return Ok(());
+    }
+    Err(RazdError::config(format!(
+        "Unknown mise key '{}'. Expected min_version, tools, plugins, env, settings, alias, tasks or one of: {}",
+        key,
+        OTHER_MISE_KEYS.join(", ")
+    )))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -405,4 +438,14 @@ mod tests {
         assert!(validate_plugin_url("not-a-url").is_err());
         assert!(validate_plugin_url("ftp://invalid.com").is_err());
     }
+
+    // ==================== Other mise key tests ====================
+
+    #[test]
+    fn test_other_mise_keys() {
+        assert!(validate_other_key("hooks").is_ok());
+        assert!(validate_other_key("vars").is_ok());
+        assert!(validate_other_key("tool").is_err());
+        assert!(validate_other_key("setings").is_err());
+    }
 }
@@ -1,47 +1,102 @@
 pub mod canonical;
 pub mod defaults;
 pub mod detection;
+pub mod dotenv;
 pub mod file_tracker;
+pub mod includes;
 pub mod mise_generator;
+pub mod mise_merge;
 pub mod mise_sync;
 pub mod mise_validator;
 pub mod razdfile;
+pub mod version_files;
 
 pub use razdfile::*;
 
-use crate::core::Result;
-use mise_sync::{MiseSyncManager, SyncConfig};
+use crate::core::events::{self, Event, Status};
+use crate::core::summary::{self, Phase};
+use crate::core::{output, Result};
+use mise_sync::{MiseSyncManager, SyncConfig, SyncResult};
 use std::env;
 use std::path::Path;
+use std::time::Instant;
 
 /// Check and perform mise configuration sync if needed
-/// Respects the RAZD_NO_SYNC and RAZD_AUTO_YES environment variables
+/// Respects the global sync flags, see `sync_config`
 pub fn check_and_sync_mise(project_dir: &Path) -> Result<()> {
+    let started = Instant::now();
+    let result = sync_mise(project_dir);
+    summary::record(
+        Phase::Sync,
+        "mise.toml",
+        *result.as_ref().unwrap_or(&Status::Failed),
+        started.elapsed(),
+    );
+    result.map(|_| ())
+}
+
+/// Sync settings from the global flags
+/// Respects the RAZD_NO_SYNC, RAZD_AUTO_YES and RAZD_PREFER environment variables
+pub fn sync_config() -> SyncConfig {
     // Check if sync is disabled
     let no_sync = env::var("RAZD_NO_SYNC").unwrap_or_default() == "1";
 
     // Check if auto-approve is enabled
     let auto_yes = env::var("RAZD_AUTO_YES").unwrap_or_default() == "1";
 
-    let config = SyncConfig {
+    // --prefer settles entries both files changed differently
+    let prefer = env::var("RAZD_PREFER")
+        .ok()
+        .and_then(|value| mise_merge::Side::parse(&value));
+
+    SyncConfig {
         no_sync,
         auto_approve: auto_yes,
         create_backups: true,
-    };
+        prefer,
+    }
+}
+
+/// Sync of `check_and_sync_mise`; UpToDate when both files already agree
+fn sync_mise(project_dir: &Path) -> Result<Status> {
+    let manager = MiseSyncManager::new(project_dir.to_path_buf(), sync_config());
 
-    let manager = MiseSyncManager::new(project_dir.to_path_buf(), config);
+    // With --dry-run only report what would be synced
+    if env::var("RAZD_DRY_RUN").unwrap_or_default() == "1" {
+        output::info(&describe_planned_sync(&manager.planned_sync()?));
+        return Ok(Status::Skipped);
+    }
+
+    // The sync manager prints what it synced; the event reports every outcome
     let result = manager.check_and_sync_if_needed()?;
+    let status = match result {
+        SyncResult::NoChangesNeeded => Status::UpToDate,
+        SyncResult::RazdfileToMise
+        | SyncResult::MiseToRazdfile
+        | SyncResult::Merged
+        | SyncResult::VersionFiles => Status::Succeeded,
+        SyncResult::Skipped | SyncResult::Conflict => Status::Skipped,
+    };
+    events::emit_if_enabled(|| Event::Sync { result });
 
-    // Only print message if sync actually happened
+    Ok(status)
+}
+
+/// Human readable description of a planned sync for `--dry-run`
+fn describe_planned_sync(result: &mise_sync::SyncResult) -> String {
     use mise_sync::SyncResult;
     match result {
-        SyncResult::RazdfileToMise | SyncResult::MiseToRazdfile => {
-            // Message already printed by sync manager
+        SyncResult::NoChangesNeeded => "Dry run: mise configuration is in sync".to_string(),
+        SyncResult::RazdfileToMise => "Dry run: would sync Razdfile.yml → mise.toml".to_string(),
+        SyncResult::MiseToRazdfile => "Dry run: would sync mise.toml → Razdfile.yml".to_string(),
+        SyncResult::Skipped => "Dry run: mise sync is disabled (--no-sync)".to_string(),
+        SyncResult::Merged => "Dry run: would merge Razdfile.yml and mise.toml".to_string(),
+        SyncResult::VersionFiles => {
+            "Dry run: would sync the version files listed in Razdfile.yml".to_string()
         }
-        _ => {
-            // No sync needed or skipped
+        SyncResult::Conflict => {
+            "Dry run: Razdfile.yml and mise.toml both changed; sync would merge them and ask about entries changed in both"
+                .to_string()
         }
     }
-
-    Ok(())
 }
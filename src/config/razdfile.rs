@@ -6,7 +6,10 @@ use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::core::RazdError;
+use crate::config::includes;
+use crate::config::version_files::VersionFile;
+use crate::core::duration::HumanDuration;
+use crate::core::{log, RazdError};
 use crate::defaults;
 
 /// Default version for Razdfile.yml (Taskfile v3 format)
@@ -41,7 +44,42 @@ pub enum Command {
         set: Option<Vec<String>>,
         #[serde(skip_serializing_if = "Option::is_none")]
         shopt: Option<Vec<String>>,
+        /// Stop the command when it runs longer than this
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timeout: Option<HumanDuration>,
+        /// Run the command again this many times when it fails
+        #[serde(skip_serializing_if = "Option::is_none")]
+        retries: Option<u32>,
+        /// Wait before the first retry, doubled for every further one
+        #[serde(skip_serializing_if = "Option::is_none")]
+        retry_delay: Option<HumanDuration>,
     },
+    /// Cleanup run when the task finishes, whether it succeeded, failed or was interrupted
+    Defer { defer: Deferred },
+}
+
+/// Command scheduled with `defer:`, a command line or a task reference
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Deferred {
+    String(String),
+    TaskRef {
+        task: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        vars: Option<HashMap<String, Value>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        silent: Option<bool>,
+    },
+}
+
+impl Deferred {
+    /// Name of the referenced task, if this is a task reference
+    pub fn task(&self) -> Option<&str> {
+        match self {
+            Deferred::String(_) => None,
+            Deferred::TaskRef { task, .. } => Some(task),
+        }
+    }
 }
 
 /// Task dependency representation
@@ -71,16 +109,170 @@ pub struct RazdfileConfig {
     pub env: Option<IndexMap<String, Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub vars: Option<IndexMap<String, Value>>,
+    /// Dotenv files loaded for every task, relative to the project directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dotenv: Option<Vec<Dotenv>>,
+    /// Other Razdfiles whose tasks are exposed as `namespace:task`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub includes: Option<IndexMap<String, Include>>,
+    /// How the output of task commands is shown, unless `--output` is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Output>,
+    /// Legacy version files (`.tool-versions`, `.nvmrc`, ...) kept in sync with `mise.tools`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_files: Option<Vec<VersionFile>>,
     #[serde(default)]
     pub tasks: IndexMap<String, TaskConfig>,
 }
 
+/// Included Razdfile: a path or a path with options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Include {
+    /// Path to a Razdfile or to a directory containing Razdfile.yml
+    Simple(String),
+    /// Path with the directory its tasks run in and whether it may be missing
+    Complex {
+        taskfile: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        dir: Option<String>,
+        #[serde(default, skip_serializing_if = "is_false")]
+        optional: bool,
+    },
+}
+
+/// Precondition of a task: a command, or a command with the message shown when it fails
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Precondition {
+    Simple(String),
+    Complex {
+        sh: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        msg: Option<String>,
+    },
+}
+
+impl Precondition {
+    pub fn command(&self) -> &str {
+        match self {
+            Precondition::Simple(sh) | Precondition::Complex { sh, .. } => sh,
+        }
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            Precondition::Simple(_) => None,
+            Precondition::Complex { msg, .. } => msg.as_deref(),
+        }
+    }
+}
+
+/// Dotenv file: a path, or a path with whether it may be missing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Dotenv {
+    /// Path to a file that must exist
+    Simple(String),
+    /// Path with options; a missing optional file only produces a warning
+    Complex {
+        file: String,
+        #[serde(default, skip_serializing_if = "is_false")]
+        optional: bool,
+    },
+}
+
+impl Dotenv {
+    pub fn file(&self) -> &str {
+        match self {
+            Dotenv::Simple(file) | Dotenv::Complex { file, .. } => file,
+        }
+    }
+
+    pub fn optional(&self) -> bool {
+        matches!(self, Dotenv::Complex { optional: true, .. })
+    }
+}
+
+/// How the output of concurrently running commands is shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    /// Output is passed through as it arrives
+    #[default]
+    Interleaved,
+    /// Every line is tagged with a colored `[task]` label
+    Prefixed,
+    /// Output of a task is printed as one block when it finishes
+    #[serde(rename = "group", alias = "grouped")]
+    Grouped,
+}
+
+impl std::str::FromStr for OutputMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        match value {
+            "interleaved" => Ok(OutputMode::Interleaved),
+            "prefixed" => Ok(OutputMode::Prefixed),
+            "grouped" | "group" => Ok(OutputMode::Grouped),
+            other => Err(format!(
+                "unknown output mode '{}' (expected interleaved, prefixed or grouped)",
+                other
+            )),
+        }
+    }
+}
+
+/// `output:` setting: a mode, or grouped output with lines printed around each block
+/// (Taskfile compatible, e.g. `::group::{{.TASK}}` and `::endgroup::` for CI log folding)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Output {
+    Mode(OutputMode),
+    Grouped {
+        #[serde(rename = "group", alias = "grouped")]
+        group: GroupMarkers,
+    },
+}
+
+/// Lines printed before and after a grouped block; `{{.TASK}}` is the task name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GroupMarkers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub begin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Output::Mode(OutputMode::default())
+    }
+}
+
+impl Output {
+    pub fn mode(&self) -> OutputMode {
+        match self {
+            Output::Mode(mode) => *mode,
+            Output::Grouped { .. } => OutputMode::Grouped,
+        }
+    }
+
+    pub fn markers(&self) -> Option<&GroupMarkers> {
+        match self {
+            Output::Mode(_) => None,
+            Output::Grouped { group } => Some(group),
+        }
+    }
+}
+
 /// Returns true if the boolean value is false (used for skip_serializing_if)
 fn is_false(value: &bool) -> bool {
     !*value
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TaskConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub desc: Option<String>,
@@ -93,23 +285,82 @@ pub struct TaskConfig {
     pub env: Option<IndexMap<String, Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub vars: Option<IndexMap<String, Value>>,
+    /// Dotenv files loaded for this task, relative to the task directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dotenv: Option<Vec<Dotenv>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub silent: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub platforms: Option<Vec<String>>,
+    /// Glob patterns of input files; the task is skipped when their checksum is unchanged
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sources: Option<Vec<String>>,
+    /// Glob patterns of files the task produces; all must exist for the task to be up to date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generates: Option<Vec<String>>,
+    /// Directory the task runs in, relative to the project directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+    /// Commands that all succeed when the task has nothing to do; the task is then skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Vec<String>>,
+    /// Commands that must succeed before the task runs; the first failure aborts it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preconditions: Option<Vec<Precondition>>,
+    /// Stop the task when its commands run longer than this, including the tasks it calls
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<HumanDuration>,
+    /// Run the whole task again this many times when it fails
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    /// Wait before the first retry of the task, doubled for every further one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_delay: Option<HumanDuration>,
+    /// Razdfile an included task was defined in (None for tasks of the root file)
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
+}
+
+impl TaskConfig {
+    /// Directory the task runs in, resolved against the project directory
+    pub fn resolve_dir(&self, base: &Path) -> PathBuf {
+        match self.dir {
+            Some(ref dir) => base.join(dir),
+            None => base.to_path_buf(),
+        }
+    }
 }
 
 /// Mise configuration section in Razdfile.yml
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Mirrors the top-level keys of mise.toml so syncing in either direction keeps all of them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct MiseConfig {
+    /// Minimum mise version: a version string or `{ hard, soft }`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_version: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<IndexMap<String, ToolConfig>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub plugins: Option<IndexMap<String, String>>,
+    /// `[env]` of mise.toml, including `_.file`/`_.path` directives
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<IndexMap<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<IndexMap<String, Value>>,
+    /// `[alias]` of mise.toml: per-tool version aliases or backend overrides
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<IndexMap<String, Value>>,
+    /// `[tasks]` of mise.toml; these are mise tasks, not razd tasks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tasks: Option<IndexMap<String, Value>>,
+    /// Other documented top-level mise.toml keys (`vars`, `hooks`, `redactions`, ...), kept as is
+    /// Keys outside `mise_validator::OTHER_MISE_KEYS` are rejected when loading Razdfile.yml.
+    #[serde(flatten)]
+    pub other: IndexMap<String, Value>,
 }
 
 /// Tool configuration supporting both simple versions and complex options
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ToolConfig {
     /// Simple version string: "22"
@@ -135,30 +386,10 @@ impl RazdfileConfig {
 
     /// Load configuration from custom path or default Razdfile.yml
     pub fn load_with_path(custom_path: Option<PathBuf>) -> Result<Option<Self>, RazdError> {
-        let path = match custom_path {
-            Some(p) => {
-                if !p.exists() {
-                    return Err(RazdError::config(format!(
-                        "Specified configuration file not found: {}",
-                        p.display()
-                    )));
-                }
-                p
-            }
-            None => {
-                let default = env::current_dir()
-                    .map_err(|e| {
-                        RazdError::config(format!("Failed to get current directory: {}", e))
-                    })?
-                    .join("Razdfile.yml");
-                if !default.exists() {
-                    return Ok(None);
-                }
-                default
-            }
-        };
-
-        Self::load_from_path(path)
+        match resolve_razdfile_path(custom_path)? {
+            Some(path) => Self::load_from_path(path),
+            None => Ok(None),
+        }
     }
 
     /// Load Razdfile.yml from a specific path
@@ -183,6 +414,16 @@ impl RazdfileConfig {
         Ok(Some(config))
     }
 
+    /// Load a Razdfile and merge the tasks of its `includes:` into it
+    /// Use `load_from_path` instead when the file is going to be written back
+    pub fn load_with_includes<P: AsRef<Path>>(path: P) -> Result<Option<Self>, RazdError> {
+        let path = path.as_ref();
+        match Self::load_from_path(path)? {
+            Some(config) => includes::resolve(config, path).map(Some),
+            None => Ok(None),
+        }
+    }
+
     /// Validate mise configuration
     fn validate_mise_config(&self, mise_config: &MiseConfig) -> Result<(), RazdError> {
         use crate::config::mise_validator;
@@ -202,6 +443,10 @@ impl RazdfileConfig {
             }
         }
 
+        for key in mise_config.other.keys() {
+            mise_validator::validate_other_key(key)?;
+        }
+
         Ok(())
     }
 
@@ -226,47 +471,108 @@ impl RazdfileConfig {
     }
 }
 
-/// Get workflow configuration with fallback chain
-/// Priority: Razdfile.yml → built-in defaults
-/// For "default" task: uses get_primary_task which returns "default" task
-pub fn get_workflow_config(command: &str) -> Result<Option<String>, RazdError> {
-    get_workflow_config_with_path(command, None)
+/// Resolve the Razdfile path from a custom path or the current directory
+/// Returns None when no custom path is given and Razdfile.yml does not exist
+pub fn resolve_razdfile_path(custom_path: Option<PathBuf>) -> Result<Option<PathBuf>, RazdError> {
+    match custom_path {
+        Some(p) => {
+            if !p.exists() {
+                return Err(RazdError::config(format!(
+                    "Specified configuration file not found: {}",
+                    p.display()
+                )));
+            }
+            Ok(Some(p))
+        }
+        None => {
+            let default = env::current_dir()
+                .map_err(|e| RazdError::config(format!("Failed to get current directory: {}", e)))?
+                .join("Razdfile.yml");
+            if default.exists() {
+                log::debug("config", || format!("Found {}", default.display()));
+                Ok(Some(default))
+            } else {
+                log::debug("config", || format!("No {}", default.display()));
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// A resolved workflow: the task configuration together with where it came from
+#[derive(Debug, Clone)]
+pub struct Workflow {
+    pub config: RazdfileConfig,
+    /// Path of the Razdfile the workflow was loaded from (None for built-in defaults)
+    pub source: Option<PathBuf>,
+}
+
+impl Workflow {
+    /// Human-readable origin of the workflow, used in error messages
+    pub fn source_display(&self) -> String {
+        match self.source {
+            Some(ref path) => path.display().to_string(),
+            None => "built-in workflows".to_string(),
+        }
+    }
+
+    /// Serialize the workflow back to Taskfile-compatible YAML
+    pub fn to_yaml(&self) -> Result<String, RazdError> {
+        serde_yaml::to_string(&self.config)
+            .map_err(|e| RazdError::config(format!("Failed to serialize Razdfile.yml: {}", e)))
+    }
+}
+
+/// Get the parsed workflow with fallback chain
+pub fn get_workflow(command: &str) -> Result<Option<Workflow>, RazdError> {
+    get_workflow_with_path(command, None)
 }
 
-/// Get workflow configuration with custom path support
-pub fn get_workflow_config_with_path(
+/// Get the parsed workflow with custom path support
+pub fn get_workflow_with_path(
     command: &str,
     custom_path: Option<PathBuf>,
-) -> Result<Option<String>, RazdError> {
+) -> Result<Option<Workflow>, RazdError> {
     // Try to load Razdfile.yml first
-    if let Some(razdfile) = RazdfileConfig::load_with_path(custom_path)? {
-        let task_name = if command == "default" {
-            // For "default" command, use get_primary_task
-            razdfile.get_primary_task()
-        } else {
-            // For other commands, use exact task name
-            if razdfile.has_task(command) {
+    if let Some(path) = resolve_razdfile_path(custom_path)? {
+        if let Some(razdfile) = RazdfileConfig::load_with_includes(&path)? {
+            let task_name = if command == "default" {
+                // For "default" command, use get_primary_task
+                razdfile.get_primary_task()
+            } else if razdfile.has_task(command) {
+                // For other commands, use exact task name
                 Some(command)
             } else {
                 None
+            };
+
+            log::debug("config", || match task_name {
+                Some(task) => format!("Task '{}' found in {}", task, path.display()),
+                None => format!("No task '{}' in {}", command, path.display()),
+            });
+            if task_name.is_some() {
+                return Ok(Some(Workflow {
+                    config: razdfile,
+                    source: Some(path),
+                }));
+            } else if command == "default" {
+                // Special handling for "default" command when no default task found
+                return Err(RazdError::no_default_task());
             }
-        };
-
-        if let Some(_task) = task_name {
-            // Convert back to YAML for taskfile execution
-            let yaml_content = serde_yaml::to_string(&razdfile).map_err(|e| {
-                RazdError::config(format!("Failed to serialize Razdfile.yml: {}", e))
-            })?;
-            return Ok(Some(yaml_content));
-        } else if command == "default" {
-            // Special handling for "default" command when no default task found
-            return Err(RazdError::no_default_task());
         }
     }
 
     // Fallback to built-in defaults
     if defaults::has_default_workflow(command) {
-        return Ok(Some(defaults::DEFAULT_WORKFLOWS.to_string()));
+        log::debug("config", || {
+            format!("Using the built-in workflow for '{}'", command)
+        });
+        let config: RazdfileConfig = serde_yaml::from_str(defaults::DEFAULT_WORKFLOWS)
+            .map_err(|e| RazdError::config(format!("Failed to parse built-in workflows: {}", e)))?;
+        return Ok(Some(Workflow {
+            config,
+            source: None,
+        }));
     }
 
     Ok(None)
@@ -319,10 +625,10 @@ tasks:
         let temp_dir = TempDir::new().unwrap();
         std::env::set_current_dir(temp_dir.path()).unwrap();
 
-        let result = get_workflow_config("dev").unwrap();
+        let result = get_workflow("dev").unwrap();
         assert!(result.is_some());
 
-        let workflow = result.unwrap();
+        let workflow = result.unwrap().to_yaml().unwrap();
         assert!(workflow.contains("version: '3'"));
         assert!(workflow.contains("dev:"));
     }
@@ -769,6 +1075,27 @@ tasks:
         assert!(config.mise.is_none());
     }
 
+    #[test]
+    fn test_unknown_mise_key_is_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let razdfile_path = temp_dir.path().join("Razdfile.yml");
+
+        fs::write(
+            &razdfile_path,
+            "version: '3'\nmise:\n  tool:\n    node: '22'\ntasks: {}\n",
+        )
+        .unwrap();
+        let err = RazdfileConfig::load_from_path(&razdfile_path).unwrap_err();
+        assert!(err.to_string().contains("Unknown mise key 'tool'"));
+
+        fs::write(
+            &razdfile_path,
+            "version: '3'\nmise:\n  hooks:\n    enter: echo hi\ntasks: {}\n",
+        )
+        .unwrap();
+        assert!(RazdfileConfig::load_from_path(&razdfile_path).is_ok());
+    }
+
     #[test]
     fn test_task_config_omits_default_internal() {
         let task = TaskConfig {
@@ -780,6 +1107,7 @@ tasks:
             vars: None,
             silent: None,
             platforms: None,
+            ..Default::default()
         };
         let yaml = serde_yaml::to_string(&task).unwrap();
         assert!(
@@ -800,6 +1128,7 @@ tasks:
             vars: None,
             silent: None,
             platforms: None,
+            ..Default::default()
         };
         let yaml = serde_yaml::to_string(&task).unwrap();
         assert!(
@@ -915,6 +1244,147 @@ tasks:
         assert!(yaml.contains("'3'") || yaml.contains("\"3\"") || yaml.contains("version: 3"));
     }
 
+    #[test]
+    fn test_task_status_and_preconditions_parse() {
+        let yaml = r#"
+tasks:
+  build:
+    status:
+      - test -f out/app
+    preconditions:
+      - which cargo
+      - sh: test -f Cargo.lock
+        msg: Cargo.lock is missing
+    cmds:
+      - cargo build
+"#;
+        let config: RazdfileConfig = serde_yaml::from_str(yaml).unwrap();
+        let task = &config.tasks["build"];
+
+        assert_eq!(task.status, Some(vec!["test -f out/app".to_string()]));
+        let preconditions = task.preconditions.as_ref().unwrap();
+        assert_eq!(preconditions[0].command(), "which cargo");
+        assert_eq!(preconditions[0].message(), None);
+        assert_eq!(preconditions[1].command(), "test -f Cargo.lock");
+        assert_eq!(preconditions[1].message(), Some("Cargo.lock is missing"));
+    }
+
+    #[test]
+    fn test_defer_commands_parse() {
+        let yaml = r#"
+tasks:
+  serve:
+    cmds:
+      - defer: rm -f server.pid
+      - defer: { task: stop-db, vars: { FORCE: "1" } }
+      - ./server
+"#;
+        let config: RazdfileConfig = serde_yaml::from_str(yaml).unwrap();
+        let cmds = &config.tasks["serve"].cmds;
+
+        match &cmds[0] {
+            Command::Defer {
+                defer: Deferred::String(line),
+            } => assert_eq!(line, "rm -f server.pid"),
+            other => panic!("unexpected command {:?}", other),
+        }
+        match &cmds[1] {
+            Command::Defer { defer } => assert_eq!(defer.task(), Some("stop-db")),
+            other => panic!("unexpected command {:?}", other),
+        }
+        assert!(matches!(cmds[2], Command::String(_)));
+    }
+
+    #[test]
+    fn test_task_and_command_timeouts_parse() {
+        let yaml = r#"
+tasks:
+  test:
+    timeout: 10m
+    cmds:
+      - cmd: cargo test
+        timeout: 90
+"#;
+        let config: RazdfileConfig = serde_yaml::from_str(yaml).unwrap();
+        let task = &config.tasks["test"];
+
+        assert_eq!(
+            task.timeout.map(HumanDuration::as_duration),
+            Some(std::time::Duration::from_secs(600))
+        );
+        match &task.cmds[0] {
+            Command::Complex { timeout, .. } => assert_eq!(
+                timeout.map(HumanDuration::as_duration),
+                Some(std::time::Duration::from_secs(90))
+            ),
+            other => panic!("unexpected command {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_task_and_command_retries_parse() {
+        let yaml = r#"
+tasks:
+  setup:
+    retries: 2
+    cmds:
+      - cmd: npm ci
+        retries: 3
+        retry_delay: 500ms
+"#;
+        let config: RazdfileConfig = serde_yaml::from_str(yaml).unwrap();
+        let task = &config.tasks["setup"];
+
+        assert_eq!(task.retries, Some(2));
+        assert!(task.retry_delay.is_none());
+        match &task.cmds[0] {
+            Command::Complex {
+                retries,
+                retry_delay,
+                ..
+            } => {
+                assert_eq!(*retries, Some(3));
+                assert_eq!(
+                    retry_delay.map(HumanDuration::as_duration),
+                    Some(std::time::Duration::from_millis(500))
+                );
+            }
+            other => panic!("unexpected command {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_output_setting_parses_modes_and_group_markers() {
+        let config: RazdfileConfig = serde_yaml::from_str("output: prefixed\ntasks: {}").unwrap();
+        assert_eq!(config.output, Some(Output::Mode(OutputMode::Prefixed)));
+
+        let config: RazdfileConfig = serde_yaml::from_str("output: grouped\ntasks: {}").unwrap();
+        assert_eq!(config.output.unwrap().mode(), OutputMode::Grouped);
+
+        let yaml = r#"
+output:
+  group:
+    begin: "::group::{{.TASK}}"
+    end: "::endgroup::"
+tasks: {}
+"#;
+        let config: RazdfileConfig = serde_yaml::from_str(yaml).unwrap();
+        let output = config.output.unwrap();
+        assert_eq!(output.mode(), OutputMode::Grouped);
+        assert_eq!(
+            output.markers().unwrap().begin.as_deref(),
+            Some("::group::{{.TASK}}")
+        );
+
+        // Serialized in the form Taskfile understands
+        assert_eq!(
+            serde_yaml::to_string(&Output::Mode(OutputMode::Grouped)).unwrap(),
+            "group\n"
+        );
+        assert_eq!("grouped".parse::<OutputMode>(), Ok(OutputMode::Grouped));
+        assert!("verbose".parse::<OutputMode>().is_err());
+    }
+
     #[test]
     fn test_workflow_config_loads_task() {
         let temp_dir = TempDir::new().unwrap();
@@ -929,10 +1399,10 @@ tasks:
 
         fs::write(&razdfile_path, content).unwrap();
 
-        let result = get_workflow_config_with_path("test", Some(razdfile_path)).unwrap();
+        let result = get_workflow_with_path("test", Some(razdfile_path)).unwrap();
 
         assert!(result.is_some());
-        let yaml = result.unwrap();
+        let yaml = result.unwrap().to_yaml().unwrap();
 
         // Verify task is present in generated YAML
         assert!(yaml.contains("test"));
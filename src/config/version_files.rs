@@ -0,0 +1,422 @@
+//! Legacy version files kept in sync with the Razdfile's `mise.tools`
+//!
+//! Projects coming from asdf, nvm, pyenv or rustup keep the files those tools
+//! read. The ones listed under `version_files:` in Razdfile.yml are rewritten
+//! when `mise.tools` changes and imported into it when they are edited; edits of
+//! both are merged against the versions recorded at the last sync, like those of
+//! mise.toml.
+
+use crate::config::razdfile::{MiseConfig, ToolConfig};
+use crate::core::{RazdError, Result};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Range;
+
+/// A version file razd can sync with `mise.tools`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VersionFile {
+    /// asdf's `.tool-versions`: a `tool version` line per tool
+    #[serde(rename = ".tool-versions")]
+    ToolVersions,
+    /// nvm's `.nvmrc`: the node version
+    #[serde(rename = ".nvmrc")]
+    Nvmrc,
+    /// pyenv's `.python-version`: the python version
+    #[serde(rename = ".python-version")]
+    PythonVersion,
+    /// rustup's `rust-toolchain.toml`: the rust channel
+    #[serde(rename = "rust-toolchain.toml")]
+    RustToolchain,
+}
+
+/// asdf plugin names mise knows under another name
+const ASDF_NAMES: [(&str, &str); 2] = [("nodejs", "node"), ("golang", "go")];
+
+impl VersionFile {
+    /// File name, relative to the project directory
+    pub fn name(self) -> &'static str {
+        match self {
+            VersionFile::ToolVersions => ".tool-versions",
+            VersionFile::Nvmrc => ".nvmrc",
+            VersionFile::PythonVersion => ".python-version",
+            VersionFile::RustToolchain => "rust-toolchain.toml",
+        }
+    }
+
+    /// Whether the file holds the version of `tool`
+    pub fn covers(self, tool: &str) -> bool {
+        match self {
+            // Tools of other backends (npm:, cargo:, ...) are not asdf plugins
+            VersionFile::ToolVersions => !tool.contains(':'),
+            VersionFile::Nvmrc => tool == "node",
+            VersionFile::PythonVersion => tool == "python",
+            VersionFile::RustToolchain => tool == "rust",
+        }
+    }
+
+    /// Versions of the tools of `mise` this file holds
+    pub fn tools_of(self, mise: &MiseConfig) -> IndexMap<String, String> {
+        mise.tools
+            .iter()
+            .flatten()
+            .filter(|(name, _)| self.covers(name))
+            .map(|(name, tool)| match tool {
+                ToolConfig::Simple(version) | ToolConfig::Complex { version, .. } => {
+                    (name.clone(), version.clone())
+                }
+            })
+            .collect()
+    }
+
+    /// Set the tools of `mise` this file holds to `versions`
+    /// Options of complex tools are kept; tools missing from `versions` are removed.
+    pub fn apply(self, mise: &mut MiseConfig, versions: &IndexMap<String, String>) {
+        let tools = mise.tools.get_or_insert_with(IndexMap::new);
+        tools.retain(|name, _| !self.covers(name) || versions.contains_key(name));
+        for (name, new_version) in versions {
+            match tools.get_mut(name) {
+                Some(ToolConfig::Simple(version)) | Some(ToolConfig::Complex { version, .. }) => {
+                    version.clone_from(new_version)
+                }
+                None => {
+                    tools.insert(name.clone(), ToolConfig::Simple(new_version.clone()));
+                }
+            }
+        }
+        if tools.is_empty() {
+            mise.tools = None;
+        }
+    }
+
+    /// Tool versions written in the file
+    pub fn parse(self, content: &str) -> Result<IndexMap<String, String>> {
+        let mut tools = IndexMap::new();
+        match self {
+            VersionFile::ToolVersions => {
+                for line in content.lines() {
+                    let mut parts = strip_comment(line).split_whitespace();
+                    // `mise.tools` holds the first version; further ones stay in the file
+                    if let (Some(name), Some(version)) = (parts.next(), parts.next()) {
+                        tools.insert(mise_name(name).to_string(), version.to_string());
+                    }
+                }
+            }
+            VersionFile::Nvmrc | VersionFile::PythonVersion => {
+                let version = content
+                    .lines()
+                    .map(|line| strip_comment(line).trim())
+                    .find(|line| !line.is_empty());
+                if let Some(version) = version {
+                    let version = match self {
+                        // nvm accepts `v20.11.0`, mise versions have no prefix
+                        VersionFile::Nvmrc => version
+                            .strip_prefix('v')
+                            .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+                            .unwrap_or(version),
+                        _ => version,
+                    };
+                    tools.insert(self.tool().to_string(), version.to_string());
+                }
+            }
+            VersionFile::RustToolchain => {
+                let doc = content.parse::<toml::Table>().map_err(|e| {
+                    RazdError::config(format!("Failed to parse {}: {}", self.name(), e))
+                })?;
+                let channel = doc
+                    .get("toolchain")
+                    .and_then(|toolchain| toolchain.get("channel"))
+                    .and_then(|channel| channel.as_str());
+                if let Some(channel) = channel {
+                    tools.insert(self.tool().to_string(), channel.to_string());
+                }
+            }
+        }
+        Ok(tools)
+    }
+
+    /// Content of the file holding `versions`, keeping the comments and other lines or
+    /// keys of `existing`. None when no tool is left and the file should not exist.
+    pub fn render(
+        self,
+        existing: Option<&str>,
+        versions: &IndexMap<String, String>,
+    ) -> Result<Option<String>> {
+        if versions.is_empty() {
+            return Ok(None);
+        }
+        if let Some(existing) = existing {
+            if self.parse(existing).ok().as_ref() == Some(versions) {
+                return Ok(Some(existing.to_string()));
+            }
+        }
+
+        let content = match self {
+            VersionFile::ToolVersions => {
+                let mut remaining = versions.clone();
+                let mut lines = Vec::new();
+                let mut uses_asdf_names = false;
+                for line in existing.unwrap_or_default().lines() {
+                    let (Some(name), Some(range)) = (
+                        strip_comment(line).split_whitespace().next(),
+                        first_version_range(line),
+                    ) else {
+                        lines.push(line.to_string());
+                        continue;
+                    };
+                    uses_asdf_names |= mise_name(name) != name;
+                    // Lines of removed tools are dropped; only the first version of kept
+                    // ones is replaced, so names, spacing, further versions and comments stay
+                    if let Some(version) = remaining.shift_remove(mise_name(name)) {
+                        lines.push(format!(
+                            "{}{}{}",
+                            &line[..range.start],
+                            version,
+                            &line[range.end..]
+                        ));
+                    }
+                }
+                // New tools follow the naming the file already uses
+                lines.extend(remaining.iter().map(|(name, version)| {
+                    let name = if uses_asdf_names {
+                        asdf_name(name)
+                    } else {
+                        name
+                    };
+                    format!("{} {}", name, version)
+                }));
+                lines.join("\n") + "\n"
+            }
+            VersionFile::Nvmrc | VersionFile::PythonVersion => {
+                format!("{}\n", versions.values().next().unwrap_or(&String::new()))
+            }
+            VersionFile::RustToolchain => {
+                let mut doc = existing
+                    .unwrap_or_default()
+                    .parse::<toml_edit::DocumentMut>()
+                    .map_err(|e| {
+                        RazdError::config(format!("Failed to parse {}: {}", self.name(), e))
+                    })?;
+                let channel = versions.values().next().cloned().unwrap_or_default();
+                if !doc.contains_key("toolchain") {
+                    doc["toolchain"] = toml_edit::table();
+                }
+                doc["toolchain"]["channel"] = toml_edit::value(channel);
+                doc.to_string()
+            }
+        };
+        Ok(Some(content))
+    }
+
+    /// The one tool a single-tool file holds
+    fn tool(self) -> &'static str {
+        match self {
+            VersionFile::ToolVersions => "",
+            VersionFile::Nvmrc => "node",
+            VersionFile::PythonVersion => "python",
+            VersionFile::RustToolchain => "rust",
+        }
+    }
+}
+
+impl fmt::Display for VersionFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Tool versions as a mise section, for merging and diffing them like mise.toml entries
+pub fn as_config(versions: &IndexMap<String, String>) -> MiseConfig {
+    MiseConfig {
+        tools: (!versions.is_empty()).then(|| {
+            versions
+                .iter()
+                .map(|(name, version)| (name.clone(), ToolConfig::Simple(version.clone())))
+                .collect()
+        }),
+        ..Default::default()
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    line.split('#').next().unwrap_or_default()
+}
+
+/// Byte range of the first version on a `.tool-versions` line, after the tool name
+fn first_version_range(line: &str) -> Option<Range<usize>> {
+    let content = strip_comment(line);
+    let name_start = content.find(|c: char| !c.is_whitespace())?;
+    let name_end = name_start + content[name_start..].find(char::is_whitespace)?;
+    let start = name_end + content[name_end..].find(|c: char| !c.is_whitespace())?;
+    let end = content[start..]
+        .find(char::is_whitespace)
+        .map_or(content.len(), |len| start + len);
+    Some(start..end)
+}
+
+fn mise_name(asdf_name: &str) -> &str {
+    ASDF_NAMES
+        .iter()
+        .find(|(asdf, _)| *asdf == asdf_name)
+        .map_or(asdf_name, |(_, mise)| mise)
+}
+
+fn asdf_name(mise_name: &str) -> &str {
+    ASDF_NAMES
+        .iter()
+        .find(|(_, mise)| *mise == mise_name)
+        .map_or(mise_name, |(asdf, _)| asdf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions(entries: &[(&str, &str)]) -> IndexMap<String, String> {
+        entries
+            .iter()
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_tool_versions() {
+        let content =
+            "# runtimes\nnodejs 20.11.0\npython 3.12.1 3.11.7 # fallback\n\ngolang 1.22\n";
+        assert_eq!(
+            VersionFile::ToolVersions.parse(content).unwrap(),
+            versions(&[("node", "20.11.0"), ("python", "3.12.1"), ("go", "1.22")])
+        );
+    }
+
+    #[test]
+    fn test_parse_single_tool_files() {
+        assert_eq!(
+            VersionFile::Nvmrc.parse("v20.11.0\n").unwrap(),
+            versions(&[("node", "20.11.0")])
+        );
+        assert_eq!(
+            VersionFile::Nvmrc.parse("lts/iron\n").unwrap(),
+            versions(&[("node", "lts/iron")])
+        );
+        assert_eq!(
+            VersionFile::PythonVersion.parse("\n3.12\n").unwrap(),
+            versions(&[("python", "3.12")])
+        );
+        assert_eq!(
+            VersionFile::RustToolchain
+                .parse("[toolchain]\nchannel = \"1.77\"\ncomponents = [\"clippy\"]\n")
+                .unwrap(),
+            versions(&[("rust", "1.77")])
+        );
+        assert!(VersionFile::RustToolchain.parse("[toolchain").is_err());
+    }
+
+    #[test]
+    fn test_render_tool_versions_keeps_comments_and_asdf_names() {
+        let existing = "# runtimes\nnodejs 20.11.0 # LTS\npython 3.12.1\nterraform 1.7.0\n";
+        let rendered = VersionFile::ToolVersions
+            .render(
+                Some(existing),
+                &versions(&[("node", "22.1.0"), ("python", "3.12.1"), ("go", "1.22")]),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "# runtimes\nnodejs 22.1.0 # LTS\npython 3.12.1\ngolang 1.22\n"
+        );
+
+        // A file without asdf names gets mise names
+        let rendered = VersionFile::ToolVersions
+            .render(
+                Some("node 20.11.0\n"),
+                &versions(&[("node", "20.11.0"), ("go", "1.22")]),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(rendered, "node 20.11.0\ngo 1.22\n");
+    }
+
+    #[test]
+    fn test_render_tool_versions_keeps_further_versions_and_spacing() {
+        let existing = "python   3.12.1 3.11.7  # fallbacks\nnodejs\t20.11.0\n";
+        let rendered = VersionFile::ToolVersions
+            .render(
+                Some(existing),
+                &versions(&[("python", "3.12.1"), ("node", "22.1.0")]),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "python   3.12.1 3.11.7  # fallbacks\nnodejs\t22.1.0\n"
+        );
+
+        let rendered = VersionFile::ToolVersions
+            .render(
+                Some(existing),
+                &versions(&[("python", "3.13.0"), ("node", "20.11.0")]),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "python   3.13.0 3.11.7  # fallbacks\nnodejs\t20.11.0\n"
+        );
+    }
+
+    #[test]
+    fn test_render_unchanged_file_is_left_alone() {
+        let existing = "v20.11.0";
+        assert_eq!(
+            VersionFile::Nvmrc
+                .render(Some(existing), &versions(&[("node", "20.11.0")]))
+                .unwrap()
+                .as_deref(),
+            Some(existing)
+        );
+        assert_eq!(
+            VersionFile::Nvmrc
+                .render(Some(existing), &IndexMap::new())
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_render_rust_toolchain_keeps_other_keys() {
+        let existing = "[toolchain]\nchannel = \"1.76\" # pinned\ncomponents = [\"clippy\"]\n";
+        let rendered = VersionFile::RustToolchain
+            .render(Some(existing), &versions(&[("rust", "1.77")]))
+            .unwrap()
+            .unwrap();
+        assert!(rendered.contains("channel = \"1.77\""));
+        assert!(rendered.contains("components = [\"clippy\"]"));
+
+        let created = VersionFile::RustToolchain
+            .render(None, &versions(&[("rust", "stable")]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(created, "[toolchain]\nchannel = \"stable\"\n");
+    }
+
+    #[test]
+    fn test_apply_keeps_tool_options() {
+        let mut mise: MiseConfig = serde_yaml::from_str(
+            "tools:\n  node:\n    version: '20'\n    postinstall: corepack enable\n  python: '3.11'\n  npm:prettier: '3'\n",
+        )
+        .unwrap();
+        VersionFile::ToolVersions.apply(&mut mise, &versions(&[("node", "22"), ("go", "1.22")]));
+
+        let tools = mise.tools.unwrap();
+        assert_eq!(
+            tools.keys().collect::<Vec<_>>(),
+            ["node", "npm:prettier", "go"]
+        );
+        assert!(matches!(
+            &tools["node"],
+            ToolConfig::Complex { version, postinstall: Some(_), .. } if version == "22"
+        ));
+    }
+}
@@ -0,0 +1,179 @@
+//! Human-readable durations such as `90s`, `5m`, `1h30m` or `250ms`
+//!
+//! Used by Razdfile settings like `timeout:` and by command line flags. A plain
+//! number is a number of seconds, so `timeout: 30` means thirty seconds.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct HumanDuration(pub Duration);
+
+impl HumanDuration {
+    pub fn as_duration(self) -> Duration {
+        self.0
+    }
+}
+
+impl From<Duration> for HumanDuration {
+    fn from(duration: Duration) -> Self {
+        HumanDuration(duration)
+    }
+}
+
+impl FromStr for HumanDuration {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let text = value.trim();
+        let invalid = || {
+            format!(
+                "invalid duration '{}' (expected e.g. 30s, 5m, 1h30m or 250ms)",
+                value
+            )
+        };
+        if text.is_empty() {
+            return Err(invalid());
+        }
+        if let Ok(seconds) = text.parse::<f64>() {
+            return seconds_to_duration(seconds)
+                .map(HumanDuration)
+                .ok_or_else(invalid);
+        }
+
+        let mut total = Duration::ZERO;
+        let mut rest = text;
+        while !rest.is_empty() {
+            let number_len = rest
+                .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .ok_or_else(invalid)?;
+            let number: f64 = rest[..number_len].parse().map_err(|_| invalid())?;
+            rest = &rest[number_len..];
+
+            let unit_len = rest
+                .find(|c: char| c.is_ascii_digit() || c == '.')
+                .unwrap_or(rest.len());
+            let scale = match &rest[..unit_len] {
+                "ms" => 0.001,
+                "s" => 1.0,
+                "m" => 60.0,
+                "h" => 3600.0,
+                _ => return Err(invalid()),
+            };
+            rest = &rest[unit_len..];
+            total += seconds_to_duration(number * scale).ok_or_else(invalid)?;
+        }
+        Ok(HumanDuration(total))
+    }
+}
+
+/// Convert a non-negative, finite number of seconds
+fn seconds_to_duration(seconds: f64) -> Option<Duration> {
+    (seconds.is_finite() && seconds >= 0.0).then(|| Duration::from_secs_f64(seconds))
+}
+
+/// Shortest form in the same syntax, e.g. `1h30m`, `2.5s` or `250ms`
+/// Precision is limited to milliseconds
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let millis = self.0.as_millis();
+        if millis < 1000 {
+            return write!(f, "{}ms", millis);
+        }
+
+        let hours = millis / 3_600_000;
+        let minutes = millis / 60_000 % 60;
+        let seconds = millis / 1000 % 60;
+        let fraction = millis % 1000;
+        if hours > 0 {
+            write!(f, "{}h", hours)?;
+        }
+        if minutes > 0 {
+            write!(f, "{}m", minutes)?;
+        }
+        if fraction > 0 {
+            let fraction = format!("{:03}", fraction);
+            write!(f, "{}.{}s", seconds, fraction.trim_end_matches('0'))?;
+        } else if seconds > 0 {
+            write!(f, "{}s", seconds)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for HumanDuration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for HumanDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Seconds(f64),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Seconds(seconds) => seconds_to_duration(seconds)
+                .map(HumanDuration)
+                .ok_or_else(|| serde::de::Error::custom(format!("invalid duration {}", seconds))),
+            Raw::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> Duration {
+        value.parse::<HumanDuration>().unwrap().as_duration()
+    }
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!(parse("30"), Duration::from_secs(30));
+        assert_eq!(parse("1.5"), Duration::from_millis(1500));
+        assert_eq!(parse("250ms"), Duration::from_millis(250));
+        assert_eq!(parse("90s"), Duration::from_secs(90));
+        assert_eq!(parse("5m"), Duration::from_secs(300));
+        assert_eq!(parse("1h30m"), Duration::from_secs(5400));
+        assert_eq!(parse("2.5s"), Duration::from_millis(2500));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        for value in ["", "abc", "5x", "m", "-1", "1d"] {
+            assert!(value.parse::<HumanDuration>().is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for (millis, text) in [
+            (250, "250ms"),
+            (2_500, "2.5s"),
+            (90_000, "1m30s"),
+            (5_400_000, "1h30m"),
+            (600_103, "10m0.103s"),
+        ] {
+            let duration = HumanDuration(Duration::from_millis(millis));
+            assert_eq!(duration.to_string(), text);
+            assert_eq!(parse(text), duration.as_duration());
+        }
+    }
+
+    #[test]
+    fn test_deserialize_number_or_text() {
+        let seconds: HumanDuration = serde_yaml::from_str("45").unwrap();
+        assert_eq!(seconds.as_duration(), Duration::from_secs(45));
+        let text: HumanDuration = serde_yaml::from_str("10m").unwrap();
+        assert_eq!(text.as_duration(), Duration::from_secs(600));
+        assert!(serde_yaml::from_str::<HumanDuration>("soon").is_err());
+    }
+}
@@ -1,3 +1,6 @@
+use super::duration::HumanDuration;
+use super::signals::Signal;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -11,6 +14,31 @@ pub enum RazdError {
     #[error("Task operation failed: {0}")]
     Task(String),
 
+    /// The message names the failed task itself
+    #[error("{message}")]
+    TaskFailed { message: String, exit_code: i32 },
+
+    #[error("Task '{task}' timed out after {elapsed}: `{command}` was stopped (timeout {limit})")]
+    TimedOut {
+        task: String,
+        command: String,
+        elapsed: HumanDuration,
+        limit: HumanDuration,
+    },
+
+    #[error("Interrupted by {signal}")]
+    Interrupted { signal: Signal },
+
+    #[error("Task '{task}' was stopped")]
+    Stopped { task: String },
+
+    #[error("{what} failed after {attempts} attempts\n{last}")]
+    RetriesExhausted {
+        what: String,
+        attempts: u32,
+        last: Box<RazdError>,
+    },
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -55,6 +83,85 @@ impl RazdError {
         Self::Task(msg.into())
     }
 
+    pub fn task_failed<S: Into<String>>(msg: S, exit_code: i32) -> Self {
+        Self::TaskFailed {
+            message: msg.into(),
+            exit_code,
+        }
+    }
+
+    pub fn timed_out<S: Into<String>>(
+        task: S,
+        command: S,
+        elapsed: Duration,
+        limit: Duration,
+    ) -> Self {
+        Self::TimedOut {
+            task: task.into(),
+            command: command.into(),
+            elapsed: elapsed.into(),
+            limit: limit.into(),
+        }
+    }
+
+    pub fn interrupted(signal: Signal) -> Self {
+        Self::Interrupted { signal }
+    }
+
+    pub fn stopped<S: Into<String>>(task: S) -> Self {
+        Self::Stopped { task: task.into() }
+    }
+
+    pub fn retries_exhausted<S: Into<String>>(what: S, attempts: u32, last: RazdError) -> Self {
+        Self::RetriesExhausted {
+            what: what.into(),
+            attempts,
+            last: Box::new(last),
+        }
+    }
+
+    /// Whether trying the same step again could succeed
+    /// Interruptions and stops are requested by the user and must not be retried.
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self, Self::Interrupted { .. } | Self::Stopped { .. })
+    }
+
+    /// Process exit code razd should use when failing with this error
+    /// Failed task commands pass their own exit code through; timeouts use 124 like `timeout(1)`
+    /// and signals 128 + the signal number like shells do.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::TaskFailed { exit_code, .. } => *exit_code,
+            Self::TimedOut { .. } => 124,
+            Self::Interrupted { signal } => signal.exit_code(),
+            Self::RetriesExhausted { last, .. } => last.exit_code(),
+            _ => 1,
+        }
+    }
+
+    /// Name of the variant, reported as `kind` in `--format json` error events
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Git(_) => "git",
+            Self::Mise(_) => "mise",
+            Self::Task(_) => "task",
+            Self::TaskFailed { .. } => "task_failed",
+            Self::TimedOut { .. } => "timed_out",
+            Self::Interrupted { .. } => "interrupted",
+            Self::Stopped { .. } => "stopped",
+            Self::RetriesExhausted { .. } => "retries_exhausted",
+            Self::Io(_) => "io",
+            Self::InvalidUrl(_) => "invalid_url",
+            Self::MissingTool { .. } => "missing_tool",
+            Self::Config(_) => "config",
+            Self::Command(_) => "command",
+            Self::NoProjectConfig { .. } => "no_project_config",
+            Self::NoDefaultTask => "no_default_task",
+            Self::SetupCancelled => "setup_cancelled",
+            Self::UnknownProjectType { .. } => "unknown_project_type",
+        }
+    }
+
     #[allow(dead_code)]
     pub fn invalid_url<S: Into<String>>(msg: S) -> Self {
         RazdError::InvalidUrl(msg.into())
@@ -0,0 +1,247 @@
+//! Machine-readable event stream (`--format json`)
+//!
+//! With `--format json` razd prints one JSON object per line on stdout instead
+//! of its usual text, so IDE plugins and CI dashboards can follow a run:
+//!
+//! ```text
+//! {"timestamp":"2026-10-17T09:30:12.345Z","event":"task_started","task":"build"}
+//! {"timestamp":"2026-10-17T09:30:12.351Z","event":"output","task":"build","stream":"stderr","line":"Compiling razd"}
+//! {"timestamp":"2026-10-17T09:30:20.120Z","event":"task_finished","task":"build","status":"succeeded","duration_ms":7769,"exit_code":0}
+//! ```
+//!
+//! Messages that are text otherwise become `message` events; the output of
+//! spawned commands either becomes `output` events or goes to stderr.
+
+use super::RazdError;
+use crate::config::mise_merge::Change;
+use crate::config::mise_sync::SyncResult;
+use crate::executor::logs;
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Whether events are printed instead of text
+/// Set with the global `--format json` flag (RAZD_FORMAT)
+pub fn enabled() -> bool {
+    std::env::var("RAZD_FORMAT").unwrap_or_default() == "json"
+}
+
+/// Severity of a `message` event, after the `output` function that printed it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    Step,
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+/// How a step ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Succeeded,
+    Failed,
+    /// Not run, e.g. a task for another platform
+    Skipped,
+    /// Not run because its `sources` or `status` say nothing changed
+    UpToDate,
+}
+
+/// What `ensure_trusted` decided about a project
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustDecision {
+    /// Trusted before this run
+    Trusted,
+    /// Trusted during this run, with `--yes` or at the prompt
+    NewlyTrusted,
+    Declined,
+    Ignored,
+}
+
+/// One line of the event stream
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    Message {
+        level: Level,
+        message: String,
+    },
+    CloneStarted {
+        url: String,
+        path: PathBuf,
+    },
+    CloneFinished {
+        url: String,
+        path: PathBuf,
+        status: Status,
+        duration_ms: u64,
+    },
+    Trust {
+        path: PathBuf,
+        decision: TrustDecision,
+    },
+    Sync {
+        result: SyncResult,
+    },
+    /// Entries `razd sync --diff` or `--check` found a sync would change in `file`
+    SyncDiff {
+        file: String,
+        changes: Vec<Change>,
+    },
+    /// `tool` is None when every tool of the mise configuration is installed
+    ToolInstallStarted {
+        tool: Option<String>,
+    },
+    ToolInstallFinished {
+        tool: Option<String>,
+        status: Status,
+        duration_ms: u64,
+    },
+    TaskStarted {
+        task: String,
+    },
+    TaskFinished {
+        task: String,
+        status: Status,
+        duration_ms: u64,
+        exit_code: i32,
+    },
+    /// A command line a task starts
+    Command {
+        task: String,
+        command: String,
+    },
+    /// A line a task's command printed
+    Output {
+        task: String,
+        stream: Stream,
+        line: String,
+    },
+    /// What `--dry-run` would execute, as listed by the text plan
+    Plan {
+        steps: Vec<crate::executor::plan::PlanStep>,
+    },
+    /// `razd list`, in the shape of `razd list --json`
+    TaskList {
+        #[serde(flatten)]
+        list: serde_json::Value,
+    },
+    /// Timed steps of `razd up`, in the order they finished
+    Summary {
+        status: Status,
+        duration_ms: u64,
+        steps: Vec<crate::core::summary::Step>,
+    },
+    /// The error razd exits with
+    Error {
+        kind: &'static str,
+        message: String,
+        exit_code: i32,
+    },
+}
+
+/// Output stream of a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    timestamp: String,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+/// Print an event as one line on stdout
+pub fn emit(event: Event) {
+    let record = Record {
+        timestamp: logs::timestamp(SystemTime::now()),
+        event: &event,
+    };
+    if let Ok(line) = serde_json::to_string(&record) {
+        let mut stdout = std::io::stdout().lock();
+        let _ = writeln!(stdout, "{}", line);
+        let _ = stdout.flush();
+    }
+}
+
+/// Print an event when `--format json` is on; does nothing otherwise
+/// The event is only built when it is printed.
+pub fn emit_if_enabled(event: impl FnOnce() -> Event) {
+    if enabled() {
+        emit(event());
+    }
+}
+
+/// Event reporting the error razd exits with
+pub fn error(error: &RazdError) -> Event {
+    Event::Error {
+        kind: error.kind(),
+        message: error.to_string(),
+        exit_code: error.exit_code(),
+    }
+}
+
+/// Milliseconds of a duration, as reported in `duration_ms`
+pub fn millis(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+/// Status of a finished step from its result
+pub fn status<T>(result: &Result<T, RazdError>) -> Status {
+    match result {
+        Ok(_) => Status::Succeeded,
+        Err(_) => Status::Failed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_json(event: Event) -> serde_json::Value {
+        serde_json::to_value(Record {
+            timestamp: "2026-10-17T09:30:12.345Z".to_string(),
+            event: &event,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_events_serialize_flat_with_snake_case_tags() {
+        let json = to_json(Event::TaskFinished {
+            task: "build".to_string(),
+            status: Status::UpToDate,
+            duration_ms: 12,
+            exit_code: 0,
+        });
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "timestamp": "2026-10-17T09:30:12.345Z",
+                "event": "task_finished",
+                "task": "build",
+                "status": "up_to_date",
+                "duration_ms": 12,
+                "exit_code": 0,
+            })
+        );
+
+        let json = to_json(Event::Sync {
+            result: SyncResult::MiseToRazdfile,
+        });
+        assert_eq!(json["event"], "sync");
+        assert_eq!(json["result"], "mise_to_razdfile");
+
+        let json = to_json(error(&RazdError::task_failed("boom", 3)));
+        assert_eq!(json["kind"], "task_failed");
+        assert_eq!(json["exit_code"], 3);
+    }
+}
@@ -0,0 +1,183 @@
+//! Leveled logging
+//!
+//! Everything razd reports goes through a level: `core::output` messages are
+//! `info` (warnings `warn`, errors `error`), and internal decisions such as
+//! which config file was picked or which process was spawned are `debug` or
+//! `trace`. Debug and trace lines go to stderr so stdout stays clean.
+//!
+//! The threshold comes from `-q` (warnings and errors only), `-v` (debug) and
+//! `-vv` (trace), or from `RAZD_LOG`: a comma separated list of a default
+//! level and `target=level` directives, e.g. `RAZD_LOG=info,process=trace`.
+//! Targets name the part of razd a line comes from: `config`, `sync`,
+//! `process`, `executor`, `taskfile` and `output`.
+
+use colored::*;
+use std::fmt;
+use std::str::FromStr;
+
+/// Importance of a log line; a filter at one level shows it and everything above
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Ok(Level::Off),
+            "error" => Ok(Level::Error),
+            "warn" | "warning" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            other => Err(format!("unknown log level '{}'", other)),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Off => "off",
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Which lines are shown: a default level and per-target overrides
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    default: Level,
+    targets: Vec<(String, Level)>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self {
+            default: Level::Info,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Filter {
+    /// Parse `RAZD_LOG` directives; entries that do not parse are skipped
+    pub fn parse(spec: &str) -> Self {
+        let mut filter = Filter::default();
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    if let Ok(level) = level.parse() {
+                        filter.targets.push((target.trim().to_string(), level));
+                    }
+                }
+                None => {
+                    if let Ok(level) = directive.parse() {
+                        filter.default = level;
+                    }
+                }
+            }
+        }
+        filter
+    }
+
+    /// Filter of this run, from the global flags (RAZD_VERBOSITY) and RAZD_LOG
+    /// `-q`/`-v`/`-vv` replace the default level of RAZD_LOG but keep its targets.
+    pub fn current() -> Self {
+        let mut filter = Filter::parse(&std::env::var("RAZD_LOG").unwrap_or_default());
+        if let Some(level) = std::env::var("RAZD_VERBOSITY")
+            .ok()
+            .and_then(|value| value.parse().ok())
+        {
+            filter.default = level;
+        }
+        filter
+    }
+
+    /// Whether a line of `target` at `level` is shown
+    /// The most specific directive wins: `process=trace` also covers `process.probe`.
+    pub fn enabled(&self, target: &str, level: Level) -> bool {
+        let threshold = self
+            .targets
+            .iter()
+            .filter(|(prefix, _)| {
+                target == prefix
+                    || target
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with('.'))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.default, |(_, level)| *level);
+        level != Level::Off && level <= threshold
+    }
+}
+
+/// Whether a line of `target` at `level` is shown in this run
+pub fn enabled(target: &str, level: Level) -> bool {
+    Filter::current().enabled(target, level)
+}
+
+/// Log an internal decision; `message` is only built when the line is shown
+pub fn debug(target: &str, message: impl FnOnce() -> String) {
+    write(target, Level::Debug, message);
+}
+
+/// Log fine-grained detail, such as every probe of a command; see `debug`
+pub fn trace(target: &str, message: impl FnOnce() -> String) {
+    write(target, Level::Trace, message);
+}
+
+fn write(target: &str, level: Level, message: impl FnOnce() -> String) {
+    if !enabled(target, level) {
+        return;
+    }
+    let label = format!("[{} {}]", level, target);
+    let label = match level {
+        Level::Trace => label.dimmed(),
+        _ => label.magenta(),
+    };
+    eprintln!("{} {}", label, message());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_defaults_to_info() {
+        let filter = Filter::default();
+        assert!(filter.enabled("config", Level::Info));
+        assert!(filter.enabled("config", Level::Warn));
+        assert!(!filter.enabled("config", Level::Debug));
+    }
+
+    #[test]
+    fn test_filter_parses_default_and_target_directives() {
+        let filter = Filter::parse("warn, process=trace,sync=debug,bogus=loud");
+        assert!(!filter.enabled("config", Level::Info));
+        assert!(filter.enabled("config", Level::Warn));
+        assert!(filter.enabled("process", Level::Trace));
+        assert!(filter.enabled("process.probe", Level::Trace));
+        assert!(!filter.enabled("processes", Level::Debug));
+        assert!(filter.enabled("sync", Level::Debug));
+        assert!(!filter.enabled("sync", Level::Trace));
+    }
+
+    #[test]
+    fn test_filter_off_hides_everything() {
+        let filter = Filter::parse("off");
+        assert!(!filter.enabled("output", Level::Error));
+    }
+}
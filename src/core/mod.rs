@@ -1,5 +1,11 @@
+pub mod duration;
 pub mod error;
+pub mod events;
+pub mod log;
 pub mod output;
+pub mod retry;
+pub mod signals;
+pub mod summary;
 pub mod trust;
 
 pub use error::{RazdError, Result};
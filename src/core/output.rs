@@ -1,22 +1,118 @@
+use super::events::{self, Event, Level};
+use super::log;
 use colored::*;
 
+/// Report a message as a `message` event instead of text with `--format json`
+/// Returns whether the message was handled: printed as an event or hidden by
+/// the log level (`-q` hides everything below warnings).
+fn as_event(level: Level, msg: &str) -> bool {
+    let log_level = match level {
+        Level::Error => log::Level::Error,
+        Level::Warning => log::Level::Warn,
+        Level::Step | Level::Info | Level::Success => log::Level::Info,
+    };
+    if !log::enabled("output", log_level) {
+        return true;
+    }
+    if !events::enabled() {
+        return false;
+    }
+    events::emit(Event::Message {
+        level,
+        message: msg.to_string(),
+    });
+    true
+}
+
 pub fn success(msg: &str) {
-    println!("{} {}", "✓".green().bold(), msg);
+    if !as_event(Level::Success, msg) {
+        println!("{} {}", "✓".green().bold(), msg);
+    }
 }
 
 pub fn info(msg: &str) {
-    println!("{} {}", "ℹ".blue().bold(), msg);
+    if !as_event(Level::Info, msg) {
+        println!("{} {}", "ℹ".blue().bold(), msg);
+    }
 }
 
 pub fn warning(msg: &str) {
-    println!("{} {}", "⚠".yellow().bold(), msg.yellow());
+    if !as_event(Level::Warning, msg) {
+        println!("{} {}", "⚠".yellow().bold(), msg.yellow());
+    }
 }
 
 #[allow(dead_code)]
 pub fn error(msg: &str) {
-    eprintln!("{} {}", "❌".red(), msg);
+    if !as_event(Level::Error, msg) {
+        eprintln!("{} {}", "❌".red(), msg);
+    }
 }
 
 pub fn step(step: &str) {
-    println!("{} {}", "→".cyan().bold(), step.bold());
+    if !as_event(Level::Step, step) {
+        println!("{} {}", "→".cyan().bold(), step.bold());
+    }
+}
+
+/// Headline announcing what a command is about to do, e.g. `🚀 Running task 'build'...`
+pub fn banner(msg: &str) {
+    if !as_event(Level::Step, msg) {
+        println!("{}", msg.cyan().bold());
+    }
+}
+
+/// Headline reporting that a command completed, e.g. `✅ Build completed successfully`
+pub fn done(msg: &str) {
+    if !as_event(Level::Success, msg) {
+        println!("{}", msg.green().bold());
+    }
+}
+
+/// Free-form text such as prompts, printed as is
+/// It goes to stderr with `--format json` so it does not end up in the event stream.
+pub fn note(msg: &str) {
+    if events::enabled() {
+        eprintln!("{}", msg);
+    } else {
+        println!("{}", msg);
+    }
+}
+
+/// Print a command line as it is executed by a task (stderr, like `task` does)
+pub fn task_command(task: &str, cmd: &str) {
+    if !log::enabled("output", log::Level::Info) {
+        return;
+    }
+    if events::enabled() {
+        events::emit(Event::Command {
+            task: task.to_string(),
+            command: cmd.to_string(),
+        });
+        return;
+    }
+    eprintln!("{}", format_task_command(task, cmd));
+}
+
+/// `task: [name] command` line announcing a command, see `task_command`
+pub fn format_task_command(task: &str, cmd: &str) -> String {
+    format!("{} {}", format!("task: [{}]", task).green(), cmd)
+}
+
+/// Colored `[task]` label used to tell apart output of concurrently running tasks
+/// The color is derived from the task name so a task keeps its color across runs
+pub fn task_prefix(task: &str) -> String {
+    const COLORS: [Color; 6] = [
+        Color::Cyan,
+        Color::Magenta,
+        Color::Yellow,
+        Color::Blue,
+        Color::Green,
+        Color::BrightRed,
+    ];
+    let index = task
+        .bytes()
+        .fold(0usize, |acc, b| acc.wrapping_add(b as usize))
+        % COLORS.len();
+    format!("[{}]", task).color(COLORS[index]).to_string()
 }
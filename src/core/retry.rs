@@ -0,0 +1,173 @@
+//! Retrying flaky steps with exponential backoff
+//!
+//! Tasks and commands opt in with `retries:` and `retry_delay:`; razd's own
+//! network steps (git clone, mise install, mise trust) follow the global
+//! `--retries`/`--retry-delay` flags. The n-th retry waits `retry_delay * 2^(n-1)`,
+//! at most `MAX_RETRY_DELAY`, of which up to half is taken off at random so
+//! concurrent retries do not hit a server in lockstep.
+
+use super::duration::HumanDuration;
+use super::{output, RazdError, Result};
+use crate::defaults;
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::time::{Duration, SystemTime};
+
+/// Longest wait between two attempts, however many retries came before
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// How often a failed step is tried again, and how long to wait in between
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RetryPolicy {
+    pub retries: u32,
+    pub delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(retries: u32, delay: Option<Duration>) -> Self {
+        Self {
+            retries,
+            delay: delay.unwrap_or(Duration::from_millis(defaults::DEFAULT_RETRY_DELAY_MS)),
+        }
+    }
+
+    /// Policy of razd's own steps, from the global `--retries` and `--retry-delay`
+    /// flags (RAZD_RETRIES, RAZD_RETRY_DELAY); no retries when unset
+    pub fn from_env() -> Self {
+        let retries = std::env::var("RAZD_RETRIES")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0);
+        let delay = std::env::var("RAZD_RETRY_DELAY")
+            .ok()
+            .and_then(|value| value.parse::<HumanDuration>().ok())
+            .map(HumanDuration::as_duration);
+        Self::new(retries, delay)
+    }
+
+    /// Total number of attempts, the first one included
+    pub fn attempts(&self) -> u32 {
+        self.retries.saturating_add(1)
+    }
+
+    /// Whether another attempt may follow attempt number `attempt` (starting at 1)
+    pub fn allows_retry(&self, attempt: u32) -> bool {
+        attempt <= self.retries
+    }
+
+    /// Wait before retry number `retry` (starting at 1), with jitter
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let full = self.max_backoff(retry);
+        full - full.mul_f64(jitter() / 2.0)
+    }
+
+    /// Wait before retry number `retry` without jitter: the delay doubled for every earlier retry
+    fn max_backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.delay.saturating_mul(factor).min(MAX_RETRY_DELAY)
+    }
+
+    /// Tell the user a step failed and when it is tried again
+    pub fn announce(&self, what: &str, attempt: u32, error: &RazdError, wait: Duration) {
+        let reason = error.to_string();
+        output::warning(&format!(
+            "{} failed (attempt {}/{}): {}; retrying in {}",
+            what,
+            attempt,
+            self.attempts(),
+            reason.lines().next().unwrap_or_default(),
+            HumanDuration(wait)
+        ));
+    }
+}
+
+/// Random number in `[0, 1)`; good enough to spread retries, not for anything else
+fn jitter() -> f64 {
+    let hash = RandomState::new().hash_one(SystemTime::now());
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Run `step` until it succeeds or `policy` runs out of retries
+/// `what` names the step in messages; an interruption is never retried.
+pub async fn retry<T, F, Fut>(policy: RetryPolicy, what: &str, mut step: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match step().await {
+            Ok(value) => return Ok(value),
+            Err(e) if e.is_retryable() && policy.allows_retry(attempt) => {
+                let wait = policy.backoff(attempt);
+                policy.announce(what, attempt, &e, wait);
+                tokio::time::sleep(wait).await;
+                attempt += 1;
+            }
+            Err(e) if attempt > 1 => return Err(RazdError::retries_exhausted(what, attempt, e)),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_limit_with_jitter() {
+        let policy = RetryPolicy::new(10, Some(Duration::from_millis(500)));
+        assert_eq!(policy.max_backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.max_backoff(2), Duration::from_secs(1));
+        assert_eq!(policy.max_backoff(4), Duration::from_secs(4));
+        assert_eq!(policy.max_backoff(9), MAX_RETRY_DELAY);
+
+        for retry in 1..=10 {
+            let wait = policy.backoff(retry);
+            let full = policy.max_backoff(retry);
+            assert!(wait <= full && wait >= full / 2, "{:?} of {:?}", wait, full);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_with_last_error() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new(2, Some(Duration::from_millis(1)));
+
+        let err = retry(policy, "flaky step", || async {
+            let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Err::<(), _>(RazdError::task_failed(format!("failure {}", call), 7))
+        })
+        .await
+        .unwrap_err();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(err.exit_code(), 7);
+        let message = err.to_string();
+        assert!(
+            message.starts_with("flaky step failed after 3 attempts"),
+            "{}",
+            message
+        );
+        assert!(message.contains("failure 3"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_retry_stops_at_first_success() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new(5, Some(Duration::from_millis(1)));
+
+        let value = retry(policy, "flaky step", || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(RazdError::command("connection reset")),
+                n => Ok(n),
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(value, 1);
+    }
+}
@@ -0,0 +1,178 @@
+//! Termination signals: SIGINT (Ctrl-C), SIGTERM and SIGHUP
+//!
+//! razd catches these signals for its whole lifetime. Code waiting for commands
+//! `listen`s for them, forwards them to the commands, waits for those to exit and
+//! runs cleanup before failing with `RazdError::Interrupted`. When nothing is
+//! listening razd exits right away, as it would without a handler, after removing
+//! the files registered with `remove_on_exit`. Either way the exit code is
+//! 128 + the signal number, as shells report it (130 for Ctrl-C, 143 for SIGTERM).
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tokio::sync::broadcast;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    /// SIGINT, sent by Ctrl-C
+    Interrupt,
+    /// SIGTERM
+    Terminate,
+    /// SIGHUP, sent when the terminal goes away
+    Hangup,
+}
+
+impl Signal {
+    pub fn number(self) -> i32 {
+        match self {
+            Signal::Hangup => 1,
+            Signal::Interrupt => 2,
+            Signal::Terminate => 15,
+        }
+    }
+
+    /// Exit code of a process terminated by this signal, as shells report it
+    pub fn exit_code(self) -> i32 {
+        128 + self.number()
+    }
+}
+
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Signal::Interrupt => "SIGINT",
+            Signal::Terminate => "SIGTERM",
+            Signal::Hangup => "SIGHUP",
+        })
+    }
+}
+
+/// Signals received by the handler installed with `install`
+static RECEIVED: OnceLock<broadcast::Sender<Signal>> = OnceLock::new();
+
+/// Temporary files to remove when a signal ends razd right away
+static TEMP_FILES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Remove `path` if a signal makes razd exit before it is `forget_on_exit`
+pub fn remove_on_exit(path: &Path) {
+    if let Ok(mut files) = TEMP_FILES.lock() {
+        files.push(path.to_path_buf());
+    }
+}
+
+pub fn forget_on_exit(path: &Path) {
+    if let Ok(mut files) = TEMP_FILES.lock() {
+        files.retain(|file| file != path);
+    }
+}
+
+/// Catch termination signals from now on; must be called within the tokio runtime
+/// Signals nobody `listen`s for make razd exit immediately.
+pub fn install() {
+    let Ok(mut incoming) = Incoming::new() else {
+        return;
+    };
+    let (sender, _) = broadcast::channel(8);
+    if RECEIVED.set(sender.clone()).is_err() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        while let Some(signal) = incoming.next().await {
+            if sender.send(signal).is_err() {
+                if let Ok(files) = TEMP_FILES.lock() {
+                    for file in files.iter() {
+                        let _ = std::fs::remove_file(file);
+                    }
+                }
+                std::process::exit(signal.exit_code());
+            }
+        }
+    });
+}
+
+/// Start listening for termination signals
+/// Without `install` (e.g. in tests) the listener never receives anything.
+pub fn listen() -> Listener {
+    Listener(RECEIVED.get().map(broadcast::Sender::subscribe))
+}
+
+/// Receives the termination signals arriving after it was created
+pub struct Listener(Option<broadcast::Receiver<Signal>>);
+
+impl Listener {
+    /// Wait for the next signal
+    pub async fn next(&mut self) -> Signal {
+        if let Some(ref mut receiver) = self.0 {
+            loop {
+                match receiver.recv().await {
+                    Ok(signal) => return signal,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }
+        std::future::pending().await
+    }
+}
+
+/// Signal streams of the operating system
+#[cfg(unix)]
+struct Incoming {
+    interrupt: tokio::signal::unix::Signal,
+    terminate: tokio::signal::unix::Signal,
+    hangup: tokio::signal::unix::Signal,
+}
+
+#[cfg(unix)]
+impl Incoming {
+    fn new() -> std::io::Result<Self> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        Ok(Self {
+            interrupt: signal(SignalKind::interrupt())?,
+            terminate: signal(SignalKind::terminate())?,
+            hangup: signal(SignalKind::hangup())?,
+        })
+    }
+
+    async fn next(&mut self) -> Option<Signal> {
+        tokio::select! {
+            Some(()) = self.interrupt.recv() => Some(Signal::Interrupt),
+            Some(()) = self.terminate.recv() => Some(Signal::Terminate),
+            Some(()) = self.hangup.recv() => Some(Signal::Hangup),
+            else => None,
+        }
+    }
+}
+
+/// Ctrl-C is the only termination signal outside of unix
+#[cfg(not(unix))]
+struct Incoming;
+
+#[cfg(not(unix))]
+impl Incoming {
+    fn new() -> std::io::Result<Self> {
+        Ok(Incoming)
+    }
+
+    async fn next(&mut self) -> Option<Signal> {
+        tokio::signal::ctrl_c()
+            .await
+            .ok()
+            .map(|_| Signal::Interrupt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_follow_shell_convention() {
+        assert_eq!(Signal::Interrupt.exit_code(), 130);
+        assert_eq!(Signal::Terminate.exit_code(), 143);
+        assert_eq!(Signal::Hangup.exit_code(), 129);
+        assert_eq!(Signal::Terminate.to_string(), "SIGTERM");
+    }
+}
@@ -0,0 +1,358 @@
+//! Run summary of `razd up`
+//!
+//! While a summary is recorded every phase of the setup is timed: the clone,
+//! the trust check, the mise sync, tool installs and each task and dependency.
+//! At the end razd prints the slowest of them:
+//!
+//! ```text
+//! Summary: 7 steps in 42.1s (5 succeeded, 1 up to date, 1 skipped)
+//!   task default            succeeded    38.2s
+//!   install all tools       succeeded    30.9s
+//!   task build              up to date   12ms
+//! ```
+//!
+//! With `--summary json` the same data is also written to
+//! `<data_dir>/summaries/<project-hash>/<timestamp>.json` (or `--summary-file`)
+//! so setup times can be tracked over many runs.
+
+use super::events::{self, Event, Status};
+use super::{log, output, RazdError, Result};
+use crate::config::file_tracker::{get_data_dir, hash_path};
+use crate::executor::logs;
+use colored::*;
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Number of steps shown in the summary table; the faster ones are only counted
+pub const MAX_SUMMARY_ROWS: usize = 10;
+
+/// Number of JSON summaries kept per project in the data directory
+pub const MAX_SUMMARIES_PER_PROJECT: usize = 50;
+
+/// Kind of step in a summary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Clone,
+    Trust,
+    Sync,
+    ToolInstall,
+    Task,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Phase::Clone => "clone",
+            Phase::Trust => "trust",
+            Phase::Sync => "sync",
+            Phase::ToolInstall => "install",
+            Phase::Task => "task",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A timed step of the run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Step {
+    pub phase: Phase,
+    pub name: String,
+    pub status: Status,
+    pub duration_ms: u64,
+}
+
+/// Everything a summary reports, as written by `--summary json`
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    /// When recording started
+    pub started: String,
+    pub project: PathBuf,
+    pub status: Status,
+    pub duration_ms: u64,
+    /// Steps in the order they finished
+    pub steps: Vec<Step>,
+}
+
+struct Recorder {
+    started: Instant,
+    started_at: SystemTime,
+    steps: Vec<Step>,
+}
+
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+/// Start timing the steps of this run; `record` does nothing before
+pub fn start() {
+    if let Ok(mut recorder) = RECORDER.lock() {
+        *recorder = Some(Recorder {
+            started: Instant::now(),
+            started_at: SystemTime::now(),
+            steps: Vec::new(),
+        });
+    }
+}
+
+/// Add a finished step when a summary is being recorded
+pub fn record(phase: Phase, name: impl Into<String>, status: Status, duration: Duration) {
+    if let Ok(mut recorder) = RECORDER.lock() {
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.steps.push(Step {
+                phase,
+                name: name.into(),
+                status,
+                duration_ms: events::millis(duration),
+            });
+        }
+    }
+}
+
+/// Stop recording and report the summary of a run that ended with `result`
+/// Prints the table (or a `summary` event with `--format json`) and writes the
+/// JSON file with `--summary json`. Nothing is reported when `start` was not called.
+pub fn finish<T>(project_dir: &Path, result: &Result<T>) {
+    let Some(recorder) = RECORDER
+        .lock()
+        .ok()
+        .and_then(|mut recorder| recorder.take())
+    else {
+        return;
+    };
+    let summary = Summary {
+        started: logs::timestamp(recorder.started_at),
+        project: project_dir.to_path_buf(),
+        status: events::status(result),
+        duration_ms: events::millis(recorder.started.elapsed()),
+        steps: recorder.steps,
+    };
+
+    if events::enabled() {
+        events::emit(Event::Summary {
+            status: summary.status,
+            duration_ms: summary.duration_ms,
+            steps: summary.steps.clone(),
+        });
+    } else if log::enabled("output", log::Level::Info)
+        && summary.steps.iter().any(|s| s.status != Status::Skipped)
+    {
+        println!();
+        for line in render(&summary) {
+            println!("{}", line);
+        }
+    }
+
+    if json_requested() {
+        match write_json(&summary) {
+            Ok(path) => output::info(&format!("Summary written to {}", path.display())),
+            Err(e) => output::warning(&format!("Failed to write summary: {}", e)),
+        }
+    }
+}
+
+/// Whether the summary is written as JSON (`--summary json` or `--summary-file`)
+fn json_requested() -> bool {
+    std::env::var("RAZD_SUMMARY").unwrap_or_default() == "json"
+        || std::env::var_os("RAZD_SUMMARY_FILE").is_some()
+}
+
+/// Label of a status in the summary table
+pub fn status_label(status: Status) -> &'static str {
+    match status {
+        Status::Succeeded => "succeeded",
+        Status::Failed => "failed",
+        Status::Skipped => "skipped",
+        Status::UpToDate => "up to date",
+    }
+}
+
+/// Steps sorted from slowest to fastest; steps that took as long keep their order
+pub fn slowest(steps: &[Step]) -> Vec<&Step> {
+    let mut sorted: Vec<&Step> = steps.iter().collect();
+    sorted.sort_by_key(|step| std::cmp::Reverse(step.duration_ms));
+    sorted
+}
+
+/// Lines of the summary table: a headline with the counts per status, then the slowest steps
+fn render(summary: &Summary) -> Vec<String> {
+    let counts: Vec<String> = [
+        Status::Succeeded,
+        Status::UpToDate,
+        Status::Skipped,
+        Status::Failed,
+    ]
+    .into_iter()
+    .filter_map(|status| {
+        let count = summary.steps.iter().filter(|s| s.status == status).count();
+        (count > 0).then(|| format!("{} {}", count, status_label(status)))
+    })
+    .collect();
+    let mut lines = vec![format!(
+        "{} {} {} in {} ({})",
+        "Summary:".bold(),
+        summary.steps.len(),
+        if summary.steps.len() == 1 {
+            "step"
+        } else {
+            "steps"
+        },
+        format_millis(summary.duration_ms),
+        counts.join(", ")
+    )];
+
+    let rows = slowest(&summary.steps);
+    let width = rows
+        .iter()
+        .take(MAX_SUMMARY_ROWS)
+        .map(|step| step.phase.to_string().len() + 1 + step.name.len())
+        .max()
+        .unwrap_or(0);
+    for step in rows.iter().take(MAX_SUMMARY_ROWS) {
+        let label = format!("{} {}", step.phase, step.name);
+        let status = format!("{:<10}", status_label(step.status));
+        let status = match step.status {
+            Status::Succeeded => status.green(),
+            Status::Failed => status.red(),
+            Status::Skipped | Status::UpToDate => status.dimmed(),
+        };
+        lines.push(format!(
+            "  {:<width$}  {}  {}",
+            label,
+            status,
+            format_millis(step.duration_ms),
+            width = width
+        ));
+    }
+    if rows.len() > MAX_SUMMARY_ROWS {
+        lines.push(format!(
+            "  … {} faster steps not shown",
+            rows.len() - MAX_SUMMARY_ROWS
+        ));
+    }
+    lines
+}
+
+fn format_millis(millis: u64) -> String {
+    super::duration::HumanDuration(Duration::from_millis(millis)).to_string()
+}
+
+/// Write the summary as pretty JSON and return where it went
+/// Without `--summary-file` old summaries beyond `MAX_SUMMARIES_PER_PROJECT` are removed.
+fn write_json(summary: &Summary) -> Result<PathBuf> {
+    let content = serde_json::to_string_pretty(summary)
+        .map_err(|e| RazdError::config(format!("Failed to serialize summary: {}", e)))?;
+
+    let path = match std::env::var_os("RAZD_SUMMARY_FILE") {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let dir = project_summaries_dir(&summary.project)?;
+            fs::create_dir_all(&dir).map_err(|e| {
+                RazdError::config(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+            prune_summaries(&dir, MAX_SUMMARIES_PER_PROJECT - 1);
+            dir.join(format!("{}.json", logs::file_timestamp(SystemTime::now())))
+        }
+    };
+    fs::write(&path, content + "\n")
+        .map_err(|e| RazdError::config(format!("Failed to write {}: {}", path.display(), e)))?;
+    Ok(path)
+}
+
+/// Directory holding the JSON summaries of a project
+pub fn project_summaries_dir(project_dir: &Path) -> Result<PathBuf> {
+    let abs_path = project_dir
+        .canonicalize()
+        .map_err(|e| RazdError::config(format!("Failed to canonicalize project path: {}", e)))?;
+
+    Ok(get_data_dir()?.join("summaries").join(hash_path(&abs_path)))
+}
+
+/// Remove the oldest summaries so at most `keep` remain
+fn prune_summaries(dir: &Path, keep: usize) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    // File names are timestamps, so they sort oldest first
+    files.sort();
+    let excess = files.len().saturating_sub(keep);
+    for path in &files[..excess] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(phase: Phase, name: &str, status: Status, duration_ms: u64) -> Step {
+        Step {
+            phase,
+            name: name.to_string(),
+            status,
+            duration_ms,
+        }
+    }
+
+    #[test]
+    fn test_slowest_sorts_by_duration_and_keeps_order_of_ties() {
+        let steps = vec![
+            step(Phase::Trust, "project", Status::Succeeded, 5),
+            step(Phase::ToolInstall, "node", Status::Succeeded, 900),
+            step(Phase::Sync, "mise.toml", Status::UpToDate, 5),
+            step(Phase::Task, "default", Status::Failed, 1200),
+        ];
+        let names: Vec<&str> = slowest(&steps)
+            .into_iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, ["default", "node", "project", "mise.toml"]);
+    }
+
+    #[test]
+    fn test_summary_serializes_steps_with_phase_and_status() {
+        let summary = Summary {
+            started: "2026-10-17T09:30:12.345Z".to_string(),
+            project: PathBuf::from("/work/app"),
+            status: Status::Succeeded,
+            duration_ms: 1500,
+            steps: vec![step(Phase::ToolInstall, "node", Status::UpToDate, 12)],
+        };
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["status"], "succeeded");
+        assert_eq!(json["duration_ms"], 1500);
+        assert_eq!(
+            json["steps"][0],
+            serde_json::json!({
+                "phase": "tool_install",
+                "name": "node",
+                "status": "up_to_date",
+                "duration_ms": 12,
+            })
+        );
+    }
+
+    #[test]
+    fn test_render_limits_rows_to_the_slowest_steps() {
+        let steps = (0..MAX_SUMMARY_ROWS as u64 + 3)
+            .map(|i| step(Phase::Task, &format!("t{}", i), Status::Succeeded, i))
+            .collect();
+        let summary = Summary {
+            started: String::new(),
+            project: PathBuf::new(),
+            status: Status::Succeeded,
+            duration_ms: 100,
+            steps,
+        };
+        let lines = render(&summary);
+        assert_eq!(lines.len(), 1 + MAX_SUMMARY_ROWS + 1);
+        assert!(lines[1].contains("task t12"));
+        assert!(lines.last().unwrap().contains("3 faster steps not shown"));
+    }
+}
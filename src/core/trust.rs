@@ -3,11 +3,15 @@
 //! This module provides functionality to track which project directories
 //! the user has explicitly trusted for execution.
 
+use crate::core::events::{self, Event, Status, TrustDecision};
+use crate::core::retry::{retry, RetryPolicy};
+use crate::core::summary::{self, Phase};
 use crate::core::{output, RazdError, Result};
 use crate::integrations::{mise, process};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 /// Status of a project's trust
 #[derive(Debug, Clone, PartialEq)]
@@ -251,7 +255,7 @@ pub fn prompt_trust(path: &Path) -> Result<TrustResponse> {
         path_display
     );
 
-    println!("{}", prompt_message);
+    output::note(&prompt_message);
 
     let items = vec!["Yes", "No", "Ignore"];
     let selection = Select::with_theme(&ColorfulTheme::default())
@@ -273,21 +277,53 @@ pub fn prompt_trust(path: &Path) -> Result<TrustResponse> {
 /// This function checks if the project is trusted, prompts the user if not,
 /// and runs `mise trust` if the project becomes trusted.
 pub async fn ensure_trusted(path: &Path, auto_yes: bool) -> Result<()> {
+    let started = Instant::now();
+    let result = check_trust(path, auto_yes).await;
+    summary::record(
+        Phase::Trust,
+        path.display().to_string(),
+        *result.as_ref().unwrap_or(&Status::Failed),
+        started.elapsed(),
+    );
+    result.map(|_| ())
+}
+
+/// Trust check of `ensure_trusted`; Skipped when nothing had to be decided
+async fn check_trust(path: &Path, auto_yes: bool) -> Result<Status> {
     // Check if project has configuration files
     if !has_razd_config(path) {
         // No config means no danger, allow execution
-        return Ok(());
+        return Ok(Status::Skipped);
     }
 
     let mut store = TrustStore::load()?;
 
+    // A dry run executes nothing, so it may inspect projects that are not trusted yet
+    if std::env::var("RAZD_DRY_RUN").unwrap_or_default() == "1" {
+        if store.get_status(path) != TrustStatus::Trusted {
+            output::info(
+                "Dry run: project is not trusted yet; nothing will be trusted or executed",
+            );
+        }
+        return Ok(Status::Skipped);
+    }
+
+    let decide = |decision| {
+        events::emit_if_enabled(|| Event::Trust {
+            path: path.to_path_buf(),
+            decision,
+        })
+    };
+
     match store.get_status(path) {
         TrustStatus::Trusted => {
             // Already trusted, proceed
-            Ok(())
+            decide(TrustDecision::Trusted);
+            Ok(Status::Succeeded)
         }
         TrustStatus::Ignored => {
             // Explicitly ignored, block execution
+            decide(TrustDecision::Ignored);
             Err(RazdError::config(format!(
                 "Project is ignored: {}\n\nThis project was previously marked as ignored.\nTo remove from ignore list, run:\n  razd trust --untrust\n  razd trust",
                 path.display()
@@ -298,18 +334,21 @@ pub async fn ensure_trusted(path: &Path, auto_yes: bool) -> Result<()> {
                 // Auto-trust with --yes flag
                 output::step("Auto-trusting project (--yes flag)");
                 store.add_trusted(path)?;
+                decide(TrustDecision::NewlyTrusted);
                 run_mise_trust_if_needed(path).await?;
-                Ok(())
+                Ok(Status::Succeeded)
             } else {
                 // Show interactive prompt
                 match prompt_trust(path)? {
                     TrustResponse::Yes => {
                         store.add_trusted(path)?;
                         output::success("Project trusted");
+                        decide(TrustDecision::NewlyTrusted);
                         run_mise_trust_if_needed(path).await?;
-                        Ok(())
+                        Ok(Status::Succeeded)
                     }
                     TrustResponse::No => {
+                        decide(TrustDecision::Declined);
                         Err(RazdError::config(format!(
                             "Project is not trusted: {}\n\nTo trust this project, run:\n  razd trust\n\nOr run with --yes to auto-trust:\n  razd --yes up",
                             path.display()
@@ -317,6 +356,7 @@ pub async fn ensure_trusted(path: &Path, auto_yes: bool) -> Result<()> {
                     }
                     TrustResponse::Ignore => {
                         store.add_ignored(path)?;
+                        decide(TrustDecision::Ignored);
                         Err(RazdError::config(format!(
                             "Project added to ignore list: {}",
                             path.display()
@@ -340,13 +380,15 @@ fn has_razd_config(dir: &Path) -> bool {
 pub async fn run_mise_trust_if_needed(path: &Path) -> Result<()> {
     if mise::has_mise_config(path) {
         // Check if mise is available
-        if process::check_command_available("mise").await {
+        if process::check_command_available("mise") {
             output::step("Running mise trust...");
             // Use non-interactive execution - mise trust just adds to trusted list
             // without showing any prompts
-            process::execute_command("mise", &["trust"], Some(path))
-                .await
-                .map_err(|e| RazdError::mise(format!("Failed to run mise trust: {}", e)))?;
+            retry(RetryPolicy::from_env(), "mise trust", || {
+                process::execute_command("mise", &["trust"], Some(path))
+            })
+            .await
+            .map_err(|e| RazdError::mise(format!("Failed to run mise trust: {}", e)))?;
         }
     }
     Ok(())
@@ -6,6 +6,18 @@
 /// has time to open and read the file before it is deleted
 pub const DEFAULT_SPAWN_DELAY_MS: u64 = 100;
 
+/// Interval in milliseconds between two scans of the watched files in `--watch` mode
+pub const DEFAULT_WATCH_INTERVAL_MS: u64 = 500;
+
+/// Time in milliseconds the watched files must stay unchanged before a task is restarted
+pub const DEFAULT_WATCH_DEBOUNCE_MS: u64 = 300;
+
+/// Time in milliseconds stopped commands get to exit after SIGTERM before they are killed
+pub const DEFAULT_STOP_GRACE_MS: u64 = 3000;
+
+/// Delay in milliseconds before the first retry of a failed step when `retry_delay` is not set
+pub const DEFAULT_RETRY_DELAY_MS: u64 = 1000;
+
 pub const DEFAULT_WORKFLOWS: &str = r#"version: '3'
 mise:
   tools:
@@ -0,0 +1,149 @@
+//! `status:` and `preconditions:` checks of a task
+//!
+//! Both are shell commands rendered with the task's vars and run quietly in the
+//! task directory with the task environment. A task whose `status` commands all
+//! succeed has nothing to do; a failing precondition blocks the task.
+
+use super::{fingerprint, template, Executor, Inherited};
+use crate::config::{Precondition, TaskConfig};
+use crate::core::{log, RazdError, Result};
+use crate::integrations::process;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Outcome of the checks of a task, as reported by `razd list --check`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskChecks {
+    /// Whether every `status` command succeeded; None when the task has none
+    pub status: Option<bool>,
+    /// Whether each precondition succeeded, in declaration order
+    pub preconditions: Vec<bool>,
+}
+
+impl TaskChecks {
+    /// A task is blocked when any precondition fails
+    pub fn blocked(&self) -> bool {
+        self.preconditions.contains(&false)
+    }
+}
+
+impl Executor {
+    /// Evaluate every check of a task without running it
+    pub async fn check_task(&self, name: &str) -> Result<TaskChecks> {
+        let task = self
+            .workflow()
+            .config
+            .tasks
+            .get(name)
+            .ok_or_else(|| super::graph::task_not_found(self.workflow(), name, None))?;
+        let vars = self
+            .task_vars(name, task, None, &Inherited::default())
+            .await?;
+        let dir = self.task_dir(task, &vars);
+        let envs = self.task_env(task, &vars, &dir)?;
+
+        let mut preconditions = Vec::new();
+        for precondition in task.preconditions.iter().flatten() {
+            let line = template::render(precondition.command(), &vars);
+            preconditions.push(process::run_shell_check(&line, Some(&dir), &envs).await?);
+        }
+
+        Ok(TaskChecks {
+            status: self.status_satisfied(task, &dir, &vars, &envs).await?,
+            preconditions,
+        })
+    }
+
+    /// Fail with the precondition's message when one of them does not hold
+    pub(super) async fn check_preconditions(
+        &self,
+        name: &str,
+        task: &TaskConfig,
+        dir: &Path,
+        vars: &HashMap<String, String>,
+        envs: &[(String, String)],
+    ) -> Result<()> {
+        for precondition in task.preconditions.iter().flatten() {
+            let line = template::render(precondition.command(), vars);
+            if !process::run_shell_check(&line, Some(dir), envs).await? {
+                return Err(RazdError::task(precondition_failure(
+                    name,
+                    precondition,
+                    &line,
+                    vars,
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a task can be skipped: its `sources` fingerprint is unchanged and its
+    /// `status` commands all succeed. Tasks declaring neither always run.
+    pub(super) async fn is_up_to_date(
+        &self,
+        name: &str,
+        task: &TaskConfig,
+        dir: &Path,
+        vars: &HashMap<String, String>,
+        envs: &[(String, String)],
+    ) -> Result<bool> {
+        if task.sources.is_none() && task.status.is_none() {
+            return Ok(false);
+        }
+        if task.sources.is_some() && !fingerprint::is_up_to_date(dir, name, task)? {
+            log::debug("executor", || {
+                format!("Task '{}' has to run: its sources changed", name)
+            });
+            return Ok(false);
+        }
+        let satisfied = self
+            .status_satisfied(task, dir, vars, envs)
+            .await?
+            .unwrap_or(true);
+        log::debug("executor", || {
+            if satisfied {
+                format!("Task '{}' is up to date", name)
+            } else {
+                format!("Task '{}' has to run: a status command failed", name)
+            }
+        });
+        Ok(satisfied)
+    }
+
+    /// Run the `status` commands; stops at the first one that fails
+    async fn status_satisfied(
+        &self,
+        task: &TaskConfig,
+        dir: &Path,
+        vars: &HashMap<String, String>,
+        envs: &[(String, String)],
+    ) -> Result<Option<bool>> {
+        let Some(ref status) = task.status else {
+            return Ok(None);
+        };
+        for line in status {
+            let line = template::render(line, vars);
+            if !process::run_shell_check(&line, Some(dir), envs).await? {
+                return Ok(Some(false));
+            }
+        }
+        Ok(Some(true))
+    }
+}
+
+/// Error message for a failed precondition: its `msg`, or the command that failed
+fn precondition_failure(
+    name: &str,
+    precondition: &Precondition,
+    line: &str,
+    vars: &HashMap<String, String>,
+) -> String {
+    match precondition.message() {
+        Some(msg) => format!(
+            "Task '{}' precondition failed: {}",
+            name,
+            template::render(msg, vars)
+        ),
+        None => format!("Task '{}' precondition failed: `{}`", name, line),
+    }
+}
@@ -0,0 +1,245 @@
+//! Incremental task skipping based on `sources`/`generates` fingerprints
+//!
+//! A task with `sources:` is up to date when the SHA-256 checksum of its source
+//! files matches the one recorded after its last successful run and every
+//! `generates:` pattern still matches at least one file. Checksums are stored in
+//! `<data_dir>/checksums/<project-hash>/`, next to the sync tracking data.
+
+use crate::config::file_tracker::{atomic_write_file, get_data_dir, hash_path};
+use crate::config::TaskConfig;
+use crate::core::{RazdError, Result};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Path of the stored checksum for a task in a project
+pub fn checksum_file_path(project_dir: &Path, task_name: &str) -> Result<PathBuf> {
+    let abs_path = project_dir
+        .canonicalize()
+        .map_err(|e| RazdError::config(format!("Failed to canonicalize project path: {}", e)))?;
+
+    Ok(get_data_dir()?
+        .join("checksums")
+        .join(hash_path(&abs_path))
+        .join(format!("{}.sha256", sanitize_task_name(task_name))))
+}
+
+/// Make a task name safe to use as a file name (namespaced tasks contain ':')
+/// Other characters are percent-escaped byte by byte, so `api:build` and `api_build`
+/// get different names.
+pub(crate) fn sanitize_task_name(task_name: &str) -> String {
+    let mut sanitized = String::with_capacity(task_name.len());
+    for byte in task_name.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.') {
+            sanitized.push(byte as char);
+        } else {
+            sanitized.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    sanitized
+}
+
+/// Expand glob patterns relative to a directory into a sorted, de-duplicated file list
+pub fn expand_globs(dir: &Path, patterns: &[String]) -> Result<Vec<PathBuf>> {
+    let mut files = BTreeSet::new();
+
+    for pattern in patterns {
+        for path in glob_pattern(dir, pattern)? {
+            if path.is_file() {
+                files.insert(path);
+            }
+        }
+    }
+
+    Ok(files.into_iter().collect())
+}
+
+/// Match a single pattern relative to `dir`
+fn glob_pattern(dir: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let full_pattern = if Path::new(pattern).is_absolute() {
+        pattern.to_string()
+    } else {
+        format!(
+            "{}/{}",
+            glob::Pattern::escape(&dir.to_string_lossy()),
+            pattern
+        )
+    };
+
+    let paths = glob::glob(&full_pattern)
+        .map_err(|e| RazdError::task(format!("Invalid glob pattern '{}': {}", pattern, e)))?;
+
+    Ok(paths.filter_map(|entry| entry.ok()).collect())
+}
+
+/// Compute the checksum of all files matched by the source patterns
+/// Both the relative path and the content of every file contribute to the hash
+pub fn compute_sources_checksum(dir: &Path, patterns: &[String]) -> Result<String> {
+    let mut hasher = Sha256::new();
+
+    for path in expand_globs(dir, patterns)? {
+        let relative = path.strip_prefix(dir).unwrap_or(&path);
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update([0]);
+
+        let content = fs::read(&path).map_err(|e| {
+            RazdError::task(format!("Failed to read source {}: {}", path.display(), e))
+        })?;
+        hasher.update(&content);
+        hasher.update([0]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Check that every `generates` pattern matches at least one existing file
+pub fn generates_exist(dir: &Path, patterns: &[String]) -> Result<bool> {
+    for pattern in patterns {
+        let matched = glob_pattern(dir, pattern)?;
+        if !matched.iter().any(|path| path.exists()) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Source patterns of a task, if it declares any
+fn task_sources(task: &TaskConfig) -> Option<&Vec<String>> {
+    task.sources.as_ref().filter(|sources| !sources.is_empty())
+}
+
+/// Check whether a task's sources and generated files are unchanged since its last run
+/// Tasks without `sources` are never up to date
+pub fn is_up_to_date(project_dir: &Path, task_name: &str, task: &TaskConfig) -> Result<bool> {
+    let Some(sources) = task_sources(task) else {
+        return Ok(false);
+    };
+
+    let checksum_path = checksum_file_path(project_dir, task_name)?;
+    let stored = match fs::read_to_string(&checksum_path) {
+        Ok(content) => content.trim().to_string(),
+        Err(_) => return Ok(false),
+    };
+
+    if stored != compute_sources_checksum(project_dir, sources)? {
+        return Ok(false);
+    }
+
+    match task.generates {
+        Some(ref generates) => generates_exist(project_dir, generates),
+        None => Ok(true),
+    }
+}
+
+/// Record the current source checksum after a successful run
+pub fn record_checksum(project_dir: &Path, task_name: &str, task: &TaskConfig) -> Result<()> {
+    let Some(sources) = task_sources(task) else {
+        return Ok(());
+    };
+
+    let checksum = compute_sources_checksum(project_dir, sources)?;
+    let checksum_path = checksum_file_path(project_dir, task_name)?;
+
+    if let Some(parent) = checksum_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            RazdError::config(format!("Failed to create checksum directory: {}", e))
+        })?;
+    }
+
+    atomic_write_file(&checksum_path, &checksum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn task_with(sources: &[&str], generates: &[&str]) -> TaskConfig {
+        TaskConfig {
+            sources: Some(sources.iter().map(|s| s.to_string()).collect()),
+            generates: if generates.is_empty() {
+                None
+            } else {
+                Some(generates.iter().map(|s| s.to_string()).collect())
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sanitize_task_name() {
+        assert_eq!(sanitize_task_name("api:build"), "api%3Abuild");
+        assert_eq!(sanitize_task_name("api_build"), "api_build");
+        assert_eq!(sanitize_task_name("a/b%"), "a%2Fb%25");
+        assert_eq!(sanitize_task_name("build-all"), "build-all");
+    }
+
+    #[test]
+    fn test_expand_globs_sorted_and_unique() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("src")).unwrap();
+        fs::write(temp_dir.path().join("src/b.rs"), "b").unwrap();
+        fs::write(temp_dir.path().join("src/a.rs"), "a").unwrap();
+
+        let patterns = vec!["src/*.rs".to_string(), "src/a.rs".to_string()];
+        let files = expand_globs(temp_dir.path(), &patterns).unwrap();
+
+        assert_eq!(
+            files,
+            vec![
+                temp_dir.path().join("src/a.rs"),
+                temp_dir.path().join("src/b.rs")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_checksum_changes_with_content() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("input.txt"), "one").unwrap();
+        let patterns = vec!["*.txt".to_string()];
+
+        let first = compute_sources_checksum(temp_dir.path(), &patterns).unwrap();
+        fs::write(temp_dir.path().join("input.txt"), "two").unwrap();
+        let second = compute_sources_checksum(temp_dir.path(), &patterns).unwrap();
+
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_task_without_sources_is_never_up_to_date() {
+        let temp_dir = TempDir::new().unwrap();
+        let task = TaskConfig::default();
+        assert!(!is_up_to_date(temp_dir.path(), "build", &task).unwrap());
+    }
+
+    #[test]
+    fn test_up_to_date_after_record() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("input.txt"), "one").unwrap();
+        fs::write(temp_dir.path().join("output.bin"), "out").unwrap();
+        let task = task_with(&["*.txt"], &["*.bin"]);
+
+        assert!(!is_up_to_date(temp_dir.path(), "build", &task).unwrap());
+        record_checksum(temp_dir.path(), "build", &task).unwrap();
+        assert!(is_up_to_date(temp_dir.path(), "build", &task).unwrap());
+
+        // Changing a source invalidates the fingerprint
+        fs::write(temp_dir.path().join("input.txt"), "two").unwrap();
+        assert!(!is_up_to_date(temp_dir.path(), "build", &task).unwrap());
+    }
+
+    #[test]
+    fn test_missing_generated_file_is_not_up_to_date() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("input.txt"), "one").unwrap();
+        fs::write(temp_dir.path().join("output.bin"), "out").unwrap();
+        let task = task_with(&["*.txt"], &["*.bin"]);
+
+        record_checksum(temp_dir.path(), "build", &task).unwrap();
+        fs::remove_file(temp_dir.path().join("output.bin")).unwrap();
+
+        assert!(!is_up_to_date(temp_dir.path(), "build", &task).unwrap());
+    }
+}
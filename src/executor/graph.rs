@@ -0,0 +1,267 @@
+//! Task dependency graph built from `deps` and `task:` references
+
+use crate::config::{Command, Dependency, TaskConfig, Workflow};
+use crate::core::{RazdError, Result};
+use serde_yaml::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Names of the tasks a task points at, in declaration order (deps first, then task refs)
+pub fn task_edges(task: &TaskConfig) -> Vec<&str> {
+    let deps = task.deps.iter().flatten().map(dependency_name);
+    let refs = task.cmds.iter().filter_map(|cmd| match cmd {
+        Command::TaskRef { task, .. } => Some(task.as_str()),
+        Command::Defer { defer } => defer.task(),
+        _ => None,
+    });
+    deps.chain(refs).collect()
+}
+
+/// Name of the task a dependency points at
+pub fn dependency_name(dep: &Dependency) -> &str {
+    match dep {
+        Dependency::Simple(name) => name,
+        Dependency::Complex { task, .. } => task,
+    }
+}
+
+/// Variables passed along with a dependency
+pub fn dependency_vars(dep: &Dependency) -> Option<&HashMap<String, Value>> {
+    match dep {
+        Dependency::Simple(_) => None,
+        Dependency::Complex { vars, .. } => vars.as_ref(),
+    }
+}
+
+/// Key identifying one task invocation: the task name plus its call variables
+/// Two paths reaching the same task with the same variables share a key
+pub fn invocation_key(name: &str, vars: Option<&HashMap<String, Value>>) -> String {
+    match vars {
+        Some(vars) if !vars.is_empty() => {
+            let sorted: BTreeMap<_, _> = vars.iter().collect();
+            format!("{}{:?}", name, sorted)
+        }
+        _ => name.to_string(),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum VisitState {
+    InProgress,
+    Done,
+}
+
+/// Validate the graph reachable from `root`: every referenced task must exist
+/// and the graph must be acyclic. Cycles are reported with the full path.
+pub fn validate(workflow: &Workflow, root: &str) -> Result<()> {
+    let mut states = HashMap::new();
+    let mut path = Vec::new();
+    visit(workflow, root, None, &mut states, &mut path)
+}
+
+fn visit<'a>(
+    workflow: &'a Workflow,
+    name: &'a str,
+    caller: Option<&str>,
+    states: &mut HashMap<&'a str, VisitState>,
+    path: &mut Vec<&'a str>,
+) -> Result<()> {
+    match states.get(name) {
+        Some(VisitState::Done) => return Ok(()),
+        Some(VisitState::InProgress) => {
+            let start = path.iter().position(|n| *n == name).unwrap_or(0);
+            let mut cycle: Vec<&str> = path[start..].to_vec();
+            cycle.push(name);
+            return Err(RazdError::task(format!(
+                "Task dependency cycle detected: {} (defined in {})",
+                cycle.join(" -> "),
+                workflow.source_display()
+            )));
+        }
+        None => {}
+    }
+
+    let task = workflow
+        .config
+        .tasks
+        .get(name)
+        .ok_or_else(|| task_not_found(workflow, name, caller))?;
+
+    states.insert(name, VisitState::InProgress);
+    path.push(name);
+
+    for edge in task_edges(task) {
+        visit(workflow, edge, Some(name), states, path)?;
+    }
+
+    path.pop();
+    states.insert(name, VisitState::Done);
+    Ok(())
+}
+
+/// Names of all tasks reachable from `roots` (including the roots), in discovery order
+/// Unknown names are skipped; `validate` reports them
+pub fn reachable<'a>(workflow: &'a Workflow, roots: &'a [String]) -> Vec<&'a str> {
+    let mut seen: Vec<&str> = Vec::new();
+    let mut queue: Vec<&str> = roots.iter().map(String::as_str).collect();
+    queue.reverse();
+
+    while let Some(name) = queue.pop() {
+        if seen.contains(&name) {
+            continue;
+        }
+        let Some(task) = workflow.config.tasks.get(name) else {
+            continue;
+        };
+        seen.push(name);
+        queue.extend(task_edges(task).into_iter().rev());
+    }
+
+    seen
+}
+
+/// Build a "task not found" error that points at the Razdfile
+pub fn task_not_found(workflow: &Workflow, name: &str, caller: Option<&str>) -> RazdError {
+    match caller {
+        Some(caller) => RazdError::task(format!(
+            "Task '{}' referenced by '{}' not found in {}",
+            name,
+            caller,
+            workflow.source_display()
+        )),
+        None => RazdError::task(format!(
+            "Task '{}' not found in {}",
+            name,
+            workflow.source_display()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RazdfileConfig;
+
+    fn workflow(yaml: &str) -> Workflow {
+        let config: RazdfileConfig = serde_yaml::from_str(yaml).unwrap();
+        Workflow {
+            config,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_task_edges_include_deps_and_refs() {
+        let wf = workflow(
+            r#"
+tasks:
+  default:
+    deps: [lint, {task: test}]
+    cmds:
+      - echo hi
+      - task: build
+  lint: {cmds: []}
+  test: {cmds: []}
+  build: {cmds: []}
+"#,
+        );
+        let task = wf.config.tasks.get("default").unwrap();
+        assert_eq!(task_edges(task), vec!["lint", "test", "build"]);
+    }
+
+    #[test]
+    fn test_validate_accepts_diamond() {
+        let wf = workflow(
+            r#"
+tasks:
+  default:
+    deps: [api, web]
+    cmds: []
+  api:
+    deps: [codegen]
+    cmds: []
+  web:
+    deps: [codegen]
+    cmds: []
+  codegen:
+    cmds: []
+"#,
+        );
+        assert!(validate(&wf, "default").is_ok());
+    }
+
+    #[test]
+    fn test_validate_reports_cycle_path() {
+        let wf = workflow(
+            r#"
+tasks:
+  default:
+    deps: [a]
+    cmds: []
+  a:
+    deps: [b]
+    cmds: []
+  b:
+    cmds:
+      - task: a
+"#,
+        );
+        let err = validate(&wf, "default").unwrap_err().to_string();
+        assert!(err.contains("a -> b -> a"), "unexpected error: {}", err);
+        assert!(!err.contains("default ->"));
+    }
+
+    #[test]
+    fn test_validate_reports_missing_dependency() {
+        let wf = workflow(
+            r#"
+tasks:
+  default:
+    deps: [missing]
+    cmds: []
+"#,
+        );
+        let err = validate(&wf, "default").unwrap_err().to_string();
+        assert!(err.contains("Task 'missing' referenced by 'default' not found"));
+    }
+
+    #[test]
+    fn test_reachable_follows_deps_and_refs() {
+        let wf = workflow(
+            r#"
+tasks:
+  default:
+    deps: [api]
+    cmds:
+      - task: docs
+  api:
+    deps: [codegen]
+    cmds: []
+  docs: {cmds: []}
+  codegen: {cmds: []}
+  unrelated: {cmds: []}
+"#,
+        );
+        let roots = vec!["default".to_string()];
+        assert_eq!(
+            reachable(&wf, &roots),
+            vec!["default", "api", "codegen", "docs"]
+        );
+    }
+
+    #[test]
+    fn test_invocation_key_ignores_var_order() {
+        let mut a = HashMap::new();
+        a.insert("X".to_string(), Value::from("1"));
+        a.insert("Y".to_string(), Value::from("2"));
+        let mut b = HashMap::new();
+        b.insert("Y".to_string(), Value::from("2"));
+        b.insert("X".to_string(), Value::from("1"));
+
+        assert_eq!(
+            invocation_key("build", Some(&a)),
+            invocation_key("build", Some(&b))
+        );
+        assert_eq!(invocation_key("build", None), "build");
+        assert_ne!(invocation_key("build", Some(&a)), "build");
+    }
+}
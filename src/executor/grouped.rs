@@ -0,0 +1,48 @@
+//! Grouped output: the output of a task run is buffered and printed as one
+//! block when the task finishes, so concurrent tasks never mix their lines.
+
+use crate::integrations::process::OutputStream;
+use std::sync::Mutex;
+
+/// Held while a block is printed so blocks of different tasks stay whole
+static PRINTING: Mutex<()> = Mutex::new(());
+
+/// Output lines of one task run, in the order they were produced
+#[derive(Default)]
+pub struct OutputGroup {
+    lines: Mutex<Vec<(OutputStream, String)>>,
+}
+
+impl OutputGroup {
+    pub fn push(&self, stream: OutputStream, line: &str) {
+        if let Ok(mut lines) = self.lines.lock() {
+            lines.push((stream, line.to_string()));
+        }
+    }
+
+    /// Print the buffered lines between the `begin` and `end` markers
+    /// Lines keep their stream; a task without output prints nothing
+    pub fn print(&self, begin: Option<&str>, end: Option<&str>) {
+        let lines = match self.lines.lock() {
+            Ok(mut lines) => std::mem::take(&mut *lines),
+            Err(_) => return,
+        };
+        if lines.is_empty() {
+            return;
+        }
+
+        let _printing = PRINTING.lock();
+        if let Some(begin) = begin {
+            println!("{}", begin);
+        }
+        for (stream, line) in lines {
+            match stream {
+                OutputStream::Stdout => println!("{}", line),
+                OutputStream::Stderr => eprintln!("{}", line),
+            }
+        }
+        if let Some(end) = end {
+            println!("{}", end);
+        }
+    }
+}
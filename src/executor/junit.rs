@@ -0,0 +1,310 @@
+//! JUnit XML report of task runs (`--junit <path>`)
+//!
+//! Every task that runs, dependencies and `task:` references included, becomes
+//! a `<testcase>` with its duration and the output of its own commands, so CI
+//! systems show a failing lint, test or build task like a failing test:
+//!
+//! ```xml
+//! <testsuites name="razd" tests="2" failures="1" skipped="0" time="4.210">
+//!   <testsuite name="app" tests="2" failures="1" errors="0" skipped="0" time="4.210" timestamp="2026-10-17T09:30:12.345Z">
+//!     <testcase name="lint" classname="app" time="1.002">
+//!       <system-out>ok</system-out>
+//!     </testcase>
+//!     <testcase name="test" classname="app" time="3.208">
+//!       <failure message="Task 'test' failed: `cargo test` exited with code 101" type="task_failed">...</failure>
+//!     </testcase>
+//!   </testsuite>
+//! </testsuites>
+//! ```
+//!
+//! Tasks not run because of `platforms:` or because they are up to date are `<skipped/>`.
+
+use super::platform;
+use crate::core::events::Status;
+use crate::core::{RazdError, Result};
+use crate::executor::logs;
+use crate::integrations::process::OutputStream;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// Output of a task's own commands, kept for its testcase
+#[derive(Debug, Default)]
+pub struct Capture {
+    stdout: Mutex<String>,
+    stderr: Mutex<String>,
+}
+
+impl Capture {
+    pub fn push(&self, stream: OutputStream, line: &str) {
+        let buffer = match stream {
+            OutputStream::Stdout => &self.stdout,
+            OutputStream::Stderr => &self.stderr,
+        };
+        if let Ok(mut buffer) = buffer.lock() {
+            buffer.push_str(line);
+            buffer.push('\n');
+        }
+    }
+
+    fn take(&self, stream: OutputStream) -> String {
+        let buffer = match stream {
+            OutputStream::Stdout => &self.stdout,
+            OutputStream::Stderr => &self.stderr,
+        };
+        buffer
+            .lock()
+            .map(|mut buffer| std::mem::take(&mut *buffer))
+            .unwrap_or_default()
+    }
+}
+
+/// How a testcase ended
+#[derive(Debug, Clone, PartialEq)]
+enum Outcome {
+    Passed,
+    Failed { message: String, kind: &'static str },
+    Skipped { message: String },
+}
+
+#[derive(Debug, Clone)]
+struct TestCase {
+    name: String,
+    duration: Duration,
+    outcome: Outcome,
+    stdout: String,
+    stderr: String,
+}
+
+/// Testcases of one executor run, in the order the tasks finished
+#[derive(Debug)]
+pub struct Report {
+    started: SystemTime,
+    cases: Mutex<Vec<TestCase>>,
+}
+
+impl Default for Report {
+    fn default() -> Self {
+        Self {
+            started: SystemTime::now(),
+            cases: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl Report {
+    /// Add the testcase of a finished task
+    pub fn add(
+        &self,
+        task_name: &str,
+        result: &Result<Status>,
+        duration: Duration,
+        capture: Option<&Capture>,
+    ) {
+        let outcome = match result {
+            Ok(Status::Succeeded) => Outcome::Passed,
+            Ok(Status::Skipped) => Outcome::Skipped {
+                message: format!("not available on {}", platform::current_os()),
+            },
+            Ok(Status::UpToDate) => Outcome::Skipped {
+                message: "up to date".to_string(),
+            },
+            Ok(Status::Failed) => Outcome::Failed {
+                message: format!("Task '{}' failed", task_name),
+                kind: "task_failed",
+            },
+            Err(e) => Outcome::Failed {
+                message: e.to_string(),
+                kind: e.kind(),
+            },
+        };
+        let case = TestCase {
+            name: task_name.to_string(),
+            duration,
+            outcome,
+            stdout: capture.map_or_else(String::new, |c| c.take(OutputStream::Stdout)),
+            stderr: capture.map_or_else(String::new, |c| c.take(OutputStream::Stderr)),
+        };
+        if let Ok(mut cases) = self.cases.lock() {
+            cases.push(case);
+        }
+    }
+
+    /// The report as a JUnit XML document with one testsuite named `suite`
+    pub fn to_xml(&self, suite: &str) -> String {
+        let cases = self
+            .cases
+            .lock()
+            .map(|cases| cases.clone())
+            .unwrap_or_default();
+        let failures = cases
+            .iter()
+            .filter(|c| matches!(c.outcome, Outcome::Failed { .. }))
+            .count();
+        let skipped = cases
+            .iter()
+            .filter(|c| matches!(c.outcome, Outcome::Skipped { .. }))
+            .count();
+        let time = seconds(self.started.elapsed().unwrap_or_default());
+        let suite = escape(suite);
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            "<testsuites name=\"razd\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">",
+            cases.len(),
+            failures,
+            skipped,
+            time
+        );
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\" time=\"{}\" timestamp=\"{}\">",
+            suite,
+            cases.len(),
+            failures,
+            skipped,
+            time,
+            logs::timestamp(self.started)
+        );
+        for case in &cases {
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+                escape(&case.name),
+                suite,
+                seconds(case.duration)
+            );
+            if case.outcome == Outcome::Passed && case.stdout.is_empty() && case.stderr.is_empty() {
+                xml.push_str("/>\n");
+                continue;
+            }
+            xml.push_str(">\n");
+            match case.outcome {
+                Outcome::Passed => {}
+                Outcome::Failed { ref message, kind } => {
+                    let headline = message.lines().next().unwrap_or_default();
+                    let _ = writeln!(
+                        xml,
+                        "      <failure message=\"{}\" type=\"{}\">{}</failure>",
+                        escape(headline),
+                        kind,
+                        escape(message)
+                    );
+                }
+                Outcome::Skipped { ref message } => {
+                    let _ = writeln!(xml, "      <skipped message=\"{}\"/>", escape(message));
+                }
+            }
+            if !case.stdout.is_empty() {
+                let _ = writeln!(
+                    xml,
+                    "      <system-out>{}</system-out>",
+                    escape(&case.stdout)
+                );
+            }
+            if !case.stderr.is_empty() {
+                let _ = writeln!(
+                    xml,
+                    "      <system-err>{}</system-err>",
+                    escape(&case.stderr)
+                );
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+
+    /// Write the report to `path`, replacing the report of an earlier run
+    pub fn write(&self, path: &Path, suite: &str) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| {
+                RazdError::config(format!("Failed to create {}: {}", parent.display(), e))
+            })?;
+        }
+        fs::write(path, self.to_xml(suite)).map_err(|e| {
+            RazdError::config(format!(
+                "Failed to write JUnit report {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/// Seconds with millisecond precision, as JUnit `time` attributes expect
+fn seconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64())
+}
+
+/// Escape text for XML attributes and content
+/// Control characters XML 1.0 cannot hold, such as the ESC of color codes, are dropped.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_marks_failed_and_skipped_tasks() {
+        let report = Report::default();
+        let capture = Capture::default();
+        capture.push(OutputStream::Stdout, "checking <all> files");
+        capture.push(OutputStream::Stderr, "\u{1b}[31mwarning\u{1b}[0m");
+        report.add(
+            "lint",
+            &Ok(Status::Succeeded),
+            Duration::from_millis(1002),
+            Some(&capture),
+        );
+        report.add(
+            "test",
+            &Err(RazdError::task_failed(
+                "Task 'test' failed: exit code 1\nmore",
+                1,
+            )),
+            Duration::from_millis(5),
+            None,
+        );
+        report.add("build", &Ok(Status::UpToDate), Duration::ZERO, None);
+
+        let xml = report.to_xml("app & co");
+        assert!(xml.contains(
+            "<testsuite name=\"app &amp; co\" tests=\"3\" failures=\"1\" errors=\"0\" skipped=\"1\""
+        ));
+        assert!(xml.contains("<testcase name=\"lint\" classname=\"app &amp; co\" time=\"1.002\">"));
+        assert!(xml.contains("<system-out>checking &lt;all&gt; files\n</system-out>"));
+        assert!(xml.contains("<system-err>[31mwarning[0m\n</system-err>"));
+        assert!(xml.contains(
+            "failed: exit code 1\" type=\"task_failed\">Task &apos;test&apos; failed: exit code 1\nmore</failure>"
+        ));
+        assert!(xml.contains("<skipped message=\"up to date\"/>"));
+    }
+
+    #[test]
+    fn test_passed_task_without_output_is_an_empty_testcase() {
+        let report = Report::default();
+        report.add("setup", &Ok(Status::Succeeded), Duration::ZERO, None);
+        assert!(report
+            .to_xml("app")
+            .contains("<testcase name=\"setup\" classname=\"app\" time=\"0.000\"/>"));
+    }
+}
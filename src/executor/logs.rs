@@ -0,0 +1,286 @@
+//! Per-task log files
+//!
+//! Every task run tees the output of its commands into
+//! `<data_dir>/logs/<project-hash>/<task>/<timestamp>.log`, next to the sync
+//! tracking data. Each line carries a UTC timestamp and a stream marker:
+//!
+//! ```text
+//! 2026-10-17T09:30:12.345Z [cmd] cargo build
+//! 2026-10-17T09:30:13.001Z [err]    Compiling razd v0.7.0
+//! 2026-10-17T09:30:20.120Z [exit] 0
+//! ```
+//!
+//! Commands writing straight to a terminal keep it instead, so their log only
+//! holds the `[cmd]` and `[exit]` lines. Only the most recent runs of every task
+//! are kept.
+
+use super::fingerprint::sanitize_task_name;
+use crate::config::file_tracker::{get_data_dir, hash_path};
+use crate::core::{RazdError, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of runs kept per task; older log files are removed when a new run starts
+pub const MAX_RUNS_PER_TASK: usize = 20;
+
+/// What a log line records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Marker {
+    /// A command line as it is started
+    Command,
+    Stdout,
+    Stderr,
+    /// Exit code of the command that just finished
+    Exit,
+    /// A failed attempt that is tried again
+    Retry,
+}
+
+impl Marker {
+    fn label(self) -> &'static str {
+        match self {
+            Marker::Command => "cmd",
+            Marker::Stdout => "out",
+            Marker::Stderr => "err",
+            Marker::Exit => "exit",
+            Marker::Retry => "retry",
+        }
+    }
+}
+
+/// Directory holding the logs of every task of a project
+pub fn project_logs_dir(project_dir: &Path) -> Result<PathBuf> {
+    let abs_path = project_dir
+        .canonicalize()
+        .map_err(|e| RazdError::config(format!("Failed to canonicalize project path: {}", e)))?;
+
+    Ok(get_data_dir()?.join("logs").join(hash_path(&abs_path)))
+}
+
+/// Directory holding the runs of one task
+pub fn task_logs_dir(logs_dir: &Path, task_name: &str) -> PathBuf {
+    logs_dir.join(sanitize_task_name(task_name))
+}
+
+/// Log files of a task, oldest run first; empty when the task never ran
+pub fn list_runs(logs_dir: &Path, task_name: &str) -> Result<Vec<PathBuf>> {
+    let dir = task_logs_dir(logs_dir, task_name);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(RazdError::config(format!(
+                "Failed to read log directory {}: {}",
+                dir.display(),
+                e
+            )))
+        }
+    };
+
+    let mut runs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .collect();
+    runs.sort_by_cached_key(|path| run_order(path));
+    Ok(runs)
+}
+
+/// Sort key of a log file: its timestamp, then the counter added when several
+/// runs started within the same millisecond (`<timestamp>-<n>.log`)
+fn run_order(path: &Path) -> (String, u32) {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    match stem.split_once('-') {
+        Some((stamp, n)) => (stamp.to_string(), n.parse().unwrap_or(0)),
+        None => (stem, 0),
+    }
+}
+
+/// Log file of a single task run; lines are written as soon as they arrive
+pub struct TaskLog {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl TaskLog {
+    /// Start the log of a new run of `task_name` and drop the oldest runs beyond the limit
+    pub fn create(logs_dir: &Path, task_name: &str, project_dir: &Path) -> Result<Self> {
+        let dir = task_logs_dir(logs_dir, task_name);
+        fs::create_dir_all(&dir).map_err(|e| {
+            RazdError::config(format!(
+                "Failed to create log directory {}: {}",
+                dir.display(),
+                e
+            ))
+        })?;
+
+        let now = SystemTime::now();
+        let stamp = file_timestamp(now);
+        let mut attempt = 0;
+        let (path, mut file) = loop {
+            // Runs of the same task can start within the same millisecond
+            let name = match attempt {
+                0 => format!("{}.log", stamp),
+                n => format!("{}-{}.log", stamp, n),
+            };
+            let path = dir.join(name);
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => break (path, file),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+                Err(e) => {
+                    return Err(RazdError::config(format!(
+                        "Failed to create log file {}: {}",
+                        path.display(),
+                        e
+                    )))
+                }
+            }
+        };
+
+        let _ = writeln!(
+            file,
+            "# razd task '{}' in {}\n# started {}",
+            task_name,
+            project_dir.display(),
+            timestamp(now)
+        );
+        prune_runs(logs_dir, task_name, MAX_RUNS_PER_TASK);
+
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a timestamped line; write errors are ignored so logging never fails a task
+    pub fn write(&self, marker: Marker, line: &str) {
+        let entry = format!(
+            "{} [{}] {}\n",
+            timestamp(SystemTime::now()),
+            marker.label(),
+            line
+        );
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.write_all(entry.as_bytes());
+        }
+    }
+}
+
+/// Remove the oldest runs of a task so at most `keep` remain
+fn prune_runs(logs_dir: &Path, task_name: &str, keep: usize) {
+    let Ok(runs) = list_runs(logs_dir, task_name) else {
+        return;
+    };
+    let excess = runs.len().saturating_sub(keep);
+    for path in &runs[..excess] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// RFC 3339 UTC timestamp with milliseconds, e.g. `2026-10-17T09:30:12.345Z`
+pub fn timestamp(time: SystemTime) -> String {
+    let (date, clock, millis) = utc_parts(time);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        date.0, date.1, date.2, clock.0, clock.1, clock.2, millis
+    )
+}
+
+/// Compact timestamp usable in file names, e.g. `20261017T093012.345Z`
+pub fn file_timestamp(time: SystemTime) -> String {
+    let (date, clock, millis) = utc_parts(time);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}.{:03}Z",
+        date.0, date.1, date.2, clock.0, clock.1, clock.2, millis
+    )
+}
+
+type Date = (i64, u32, u32);
+type Clock = (u32, u32, u32);
+
+/// Split a time into UTC calendar date, time of day and milliseconds
+fn utc_parts(time: SystemTime) -> (Date, Clock, u32) {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs() as i64;
+    let days = secs.div_euclid(86_400);
+    let of_day = secs.rem_euclid(86_400) as u32;
+    (
+        civil_from_days(days),
+        (of_day / 3600, of_day / 60 % 60, of_day % 60),
+        since_epoch.subsec_millis(),
+    )
+}
+
+/// Convert days since 1970-01-01 into a (year, month, day) date
+/// Howard Hinnant's `civil_from_days` algorithm for the proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> Date {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_timestamp_formats_utc() {
+        assert_eq!(timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+
+        // 2024-02-29T13:45:30.250Z, a leap day
+        let time = UNIX_EPOCH + Duration::from_millis(1_709_214_330_250);
+        assert_eq!(timestamp(time), "2024-02-29T13:45:30.250Z");
+        assert_eq!(file_timestamp(time), "20240229T134530.250Z");
+    }
+
+    #[test]
+    fn test_task_log_writes_markers_and_prunes_old_runs() {
+        let temp_dir = TempDir::new().unwrap();
+        let logs_dir = temp_dir.path().join("logs");
+
+        let log = TaskLog::create(&logs_dir, "api:build", temp_dir.path()).unwrap();
+        log.write(Marker::Command, "cargo build");
+        log.write(Marker::Stderr, "warning: unused");
+        log.write(Marker::Exit, "0");
+
+        let content = fs::read_to_string(log.path()).unwrap();
+        assert!(content.starts_with("# razd task 'api:build'"));
+        assert!(content.contains("Z [cmd] cargo build\n"));
+        assert!(content.contains("Z [err] warning: unused\n"));
+        assert!(content.contains("Z [exit] 0\n"));
+        assert!(log.path().starts_with(logs_dir.join("api%3Abuild")));
+
+        for _ in 0..MAX_RUNS_PER_TASK + 2 {
+            TaskLog::create(&logs_dir, "api:build", temp_dir.path()).unwrap();
+        }
+        let runs = list_runs(&logs_dir, "api:build").unwrap();
+        assert_eq!(runs.len(), MAX_RUNS_PER_TASK);
+        assert!(!runs.contains(&log.path().to_path_buf()));
+
+        // A task named like the escaped one keeps its own logs
+        TaskLog::create(&logs_dir, "api_build", temp_dir.path()).unwrap();
+        assert_eq!(list_runs(&logs_dir, "api_build").unwrap().len(), 1);
+        assert_eq!(
+            list_runs(&logs_dir, "api:build").unwrap().len(),
+            MAX_RUNS_PER_TASK
+        );
+    }
+}
@@ -0,0 +1,2157 @@
+//! Native task executor
+//!
+//! Interprets Razdfile tasks (shell commands, task references and dependencies)
+//! directly instead of handing a temporary Taskfile to the external `task` binary.
+
+pub mod checks;
+pub mod fingerprint;
+pub mod graph;
+pub mod grouped;
+pub mod junit;
+pub mod logs;
+pub mod plan;
+pub mod platform;
+pub mod template;
+pub mod vars;
+pub mod watch;
+
+use crate::config::{
+    dotenv, Command, Deferred, Dependency, Dotenv, Output, OutputMode, TaskConfig, Workflow,
+};
+use crate::core::duration::HumanDuration;
+use crate::core::events::{self, Event, Status, Stream};
+use crate::core::retry::RetryPolicy;
+use crate::core::signals::{self, Signal};
+use crate::core::summary::{self, Phase};
+use crate::core::{log, output, RazdError, Result};
+use crate::defaults;
+use crate::integrations::process;
+use grouped::OutputGroup;
+use indexmap::IndexMap;
+use logs::{Marker, TaskLog};
+use serde_yaml::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{Notify, OnceCell, Semaphore};
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use vars::{ShellVarCache, VarLayers};
+
+/// Options controlling a single executor run
+#[derive(Debug, Clone, Default)]
+pub struct ExecutorOptions {
+    /// Maximum number of commands running at the same time (None = unlimited)
+    pub jobs: Option<usize>,
+    /// Run tasks even when their `sources` fingerprint is up to date
+    pub force: bool,
+    /// Run the top-level tasks of `run_all` concurrently
+    pub parallel: bool,
+    /// Keep running the remaining top-level tasks after one fails
+    pub continue_on_error: bool,
+    /// How command output is shown: passed through, tagged with the task name or grouped per task
+    pub output: Output,
+    /// Start every command in its own process group so `stop` also reaches its children,
+    /// including commands reading the terminal (which otherwise share razd's group)
+    pub process_groups: bool,
+    /// `KEY=value` variables from the command line; they override every other source
+    pub vars: Vec<(String, String)>,
+    /// Project log directory receiving one file per task run (see `logs`); None disables logs
+    pub log_dir: Option<PathBuf>,
+    /// Time limit of tasks that do not set `timeout:` themselves
+    pub timeout: Option<Duration>,
+    /// Time stopped commands get between SIGTERM and SIGKILL (None = `DEFAULT_STOP_GRACE_MS`)
+    pub stop_grace: Option<Duration>,
+    /// File receiving a JUnit report of the tasks that ran (see `junit`); None disables it
+    pub junit: Option<PathBuf>,
+}
+
+/// Executes tasks from a resolved workflow
+///
+/// Cloning is cheap: clones share the workflow, the job limit and the record of
+/// dependencies that already ran during this invocation.
+#[derive(Clone)]
+pub struct Executor {
+    inner: Arc<ExecutorState>,
+}
+
+struct ExecutorState {
+    workflow: Workflow,
+    working_dir: PathBuf,
+    options: ExecutorOptions,
+    /// Limits concurrently running commands to `--jobs`
+    job_slots: Semaphore,
+    /// Dependencies started during this invocation, keyed by task name and vars.
+    /// The cell holds whether the dependency succeeded.
+    deps_run: Mutex<HashMap<String, Arc<OnceCell<bool>>>>,
+    /// Process ids of the commands currently running, with whether they lead their own process group
+    running: Mutex<HashMap<u32, bool>>,
+    /// Set by `stop` and `interrupt`; only cleanup (`defer:`) commands are started afterwards
+    stopped: AtomicBool,
+    /// Set by `stop(true)`; no new commands are started at all
+    killed: AtomicBool,
+    /// Wakes commands waiting to be retried when `stopped` or `killed` is set
+    halted: Notify,
+    /// Output of `sh:` vars evaluated during this invocation
+    shell_vars: ShellVarCache,
+    /// Missing optional dotenv files that were already reported
+    dotenv_warned: Mutex<HashSet<PathBuf>>,
+    /// Testcases of the tasks run so far, with `--junit`
+    report: Option<junit::Report>,
+}
+
+/// Number of stderr lines kept in the error of a command that may be retried
+const FAILURE_STDERR_LINES: usize = 20;
+
+type TaskFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+/// State of one task run: its time limit and where its output goes besides the terminal
+#[derive(Default)]
+struct TaskRun {
+    /// Time limit of the run, which also covers the tasks it references
+    deadline: Option<Deadline>,
+    /// Whether the task runs as cleanup of another task, see `Inherited`
+    cleanup: bool,
+    /// `defer:` commands met so far, run last to first when the task's commands are done
+    deferred: Vec<Deferred>,
+    /// Log file, opened with the first shell command so tasks that only call others leave none
+    log: Option<Arc<TaskLog>>,
+    /// Output buffered until the task finishes, in grouped mode
+    group: Option<Arc<OutputGroup>>,
+    /// Whether one of the task's own commands failed
+    failed: bool,
+    /// Whether the task may be retried, so failing commands keep the end of their stderr
+    retried: bool,
+    /// Output kept for the task's testcase in the JUnit report
+    capture: Option<Arc<junit::Capture>>,
+}
+
+impl TaskRun {
+    /// Announce a command, in its group when output is grouped
+    fn command(&self, task_name: &str, line: &str) {
+        match self.group {
+            Some(ref group) => group.push(
+                process::OutputStream::Stderr,
+                &output::format_task_command(task_name, line),
+            ),
+            None => output::task_command(task_name, line),
+        }
+    }
+
+    /// Receiver of the output lines of a command: the log, the group, the stderr tail,
+    /// the JUnit capture and the event stream, if any
+    fn sink(
+        &self,
+        task_name: &str,
+        stderr_tail: Option<Arc<Mutex<VecDeque<String>>>>,
+    ) -> Option<process::LineSink> {
+        let events_task = events::enabled().then(|| task_name.to_string());
+        if self.log.is_none()
+            && self.group.is_none()
+            && stderr_tail.is_none()
+            && self.capture.is_none()
+            && events_task.is_none()
+        {
+            return None;
+        }
+        let log = self.log.clone();
+        let group = self.group.clone();
+        let capture = self.capture.clone();
+        Some(Arc::new(move |stream, line: &str| {
+            if let (process::OutputStream::Stderr, Some(tail)) = (stream, stderr_tail.as_ref()) {
+                if let Ok(mut tail) = tail.lock() {
+                    if tail.len() == FAILURE_STDERR_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line.to_string());
+                }
+            }
+            if let Some(ref log) = log {
+                let marker = match stream {
+                    process::OutputStream::Stdout => Marker::Stdout,
+                    process::OutputStream::Stderr => Marker::Stderr,
+                };
+                log.write(marker, line);
+            }
+            if let Some(ref group) = group {
+                group.push(stream, line);
+            }
+            if let Some(ref capture) = capture {
+                capture.push(stream, line);
+            }
+            if let Some(ref task) = events_task {
+                events::emit(Event::Output {
+                    task: task.clone(),
+                    stream: match stream {
+                        process::OutputStream::Stdout => Stream::Stdout,
+                        process::OutputStream::Stderr => Stream::Stderr,
+                    },
+                    line: line.to_string(),
+                });
+            }
+        }))
+    }
+}
+
+/// Per-command settings collected from the different command forms
+struct CommandSpec<'a> {
+    line: &'a str,
+    silent: bool,
+    ignore_error: bool,
+    set: Option<&'a Vec<String>>,
+    shopt: Option<&'a Vec<String>>,
+    timeout: Option<Duration>,
+    /// A `defer:` command: it runs after the task's time limit and after `stop`
+    deferred: bool,
+    retry: RetryPolicy,
+}
+
+/// Failure of a command itself, as opposed to razd failing to run it
+struct Failure {
+    error: RazdError,
+    /// One-line description for the warning shown when the failure is ignored
+    summary: String,
+}
+
+/// What a task run through a `task:` reference inherits from its caller
+#[derive(Debug, Clone, Default)]
+struct Inherited {
+    /// Time limit of the calling task
+    deadline: Option<Deadline>,
+    /// Called from `defer:`; its commands still run after `stop`
+    cleanup: bool,
+}
+
+/// Time limit of a task or a command
+/// A task's limit also covers the tasks it calls through `task:` references.
+#[derive(Debug, Clone)]
+struct Deadline {
+    task: String,
+    started: Instant,
+    limit: Duration,
+}
+
+impl Deadline {
+    fn start(task: &str, limit: Duration) -> Self {
+        Self {
+            task: task.to_string(),
+            started: Instant::now(),
+            limit,
+        }
+    }
+
+    fn at(&self) -> Instant {
+        self.started + self.limit
+    }
+
+    /// Whichever of two optional limits expires first
+    fn earliest(a: Option<Deadline>, b: Option<Deadline>) -> Option<Deadline> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(if a.at() <= b.at() { a } else { b }),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+impl Executor {
+    pub fn new(workflow: Workflow, working_dir: PathBuf, options: ExecutorOptions) -> Self {
+        // Long-running dependencies (dev servers) must not starve each other,
+        // so there is no limit unless one is requested
+        let jobs = options
+            .jobs
+            .map(|n| n.clamp(1, Semaphore::MAX_PERMITS))
+            .unwrap_or(Semaphore::MAX_PERMITS);
+        let report = options.junit.as_ref().map(|_| junit::Report::default());
+        Self {
+            inner: Arc::new(ExecutorState {
+                workflow,
+                working_dir,
+                options,
+                job_slots: Semaphore::new(jobs),
+                deps_run: Mutex::new(HashMap::new()),
+                running: Mutex::new(HashMap::new()),
+                stopped: AtomicBool::new(false),
+                killed: AtomicBool::new(false),
+                halted: Notify::new(),
+                shell_vars: ShellVarCache::default(),
+                dotenv_warned: Mutex::new(HashSet::new()),
+                report,
+            }),
+        }
+    }
+
+    fn workflow(&self) -> &Workflow {
+        &self.inner.workflow
+    }
+
+    /// Run several top-level tasks, in order or concurrently with `parallel`
+    /// Arguments after `--` are exposed as `{{.CLI_ARGS}}` to the last task only.
+    /// Without `continue_on_error` the first failure is returned; with it every
+    /// failure is reported once all tasks have finished.
+    pub async fn run_all(&self, task_names: &[String], cli_args: &[String]) -> Result<()> {
+        for name in task_names {
+            graph::validate(self.workflow(), name)?;
+        }
+
+        let invocations = invocations(task_names, cli_args);
+        let options = &self.inner.options;
+        let mut failures = Vec::new();
+
+        if options.parallel {
+            let mut running = JoinSet::new();
+            for (name, vars) in invocations {
+                running.spawn(self.run_task(name, vars, Inherited::default()));
+            }
+            while let Some(joined) = running.join_next().await {
+                let result = joined
+                    .map_err(|e| RazdError::task(format!("Task did not complete: {}", e)))
+                    .and_then(|result| result);
+                if let Err(e) = result {
+                    failures.push(e);
+                }
+            }
+        } else {
+            for (name, vars) in invocations {
+                if let Err(e) = self.run_task(name, vars, Inherited::default()).await {
+                    failures.push(e);
+                    if !options.continue_on_error {
+                        break;
+                    }
+                }
+            }
+        }
+
+        combine_failures(failures, task_names.len(), options.continue_on_error)
+    }
+
+    /// Write the JUnit report of the tasks run so far, with `--junit`
+    /// A report that cannot be written is only a warning, so it never hides the outcome of the tasks.
+    pub fn write_report(&self) {
+        let (Some(report), Some(path)) = (&self.inner.report, &self.inner.options.junit) else {
+            return;
+        };
+        let suite = self
+            .inner
+            .working_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "razd".to_string());
+        if let Err(e) = report.write(path, &suite) {
+            output::warning(&e.to_string());
+        }
+    }
+
+    /// `run_all`, stopping on SIGINT, SIGTERM or SIGHUP
+    /// The signal is passed on to the running commands, which get the grace period to
+    /// exit (a second signal cuts it short) before they are killed. Deferred commands
+    /// still run, then the run fails with `RazdError::Interrupted`.
+    pub async fn run_all_interruptible(
+        &self,
+        task_names: &[String],
+        cli_args: &[String],
+    ) -> Result<()> {
+        let mut signals = signals::listen();
+        let run = self.run_all(task_names, cli_args);
+        tokio::pin!(run);
+
+        let signal = tokio::select! {
+            result = &mut run => return result,
+            signal = signals.next() => signal,
+        };
+        output::warning(&format!(
+            "Received {}, stopping running commands...",
+            signal
+        ));
+        self.interrupt(signal);
+
+        tokio::select! {
+            _ = &mut run => return Err(RazdError::interrupted(signal)),
+            _ = tokio::time::sleep(self.stop_grace()) => {}
+            _ = signals.next() => {}
+        }
+        self.stop(true);
+        let _ = run.await;
+        Err(RazdError::interrupted(signal))
+    }
+
+    /// Run a single task as an owned, spawnable future
+    fn run_task(
+        &self,
+        name: String,
+        call_vars: Option<HashMap<String, Value>>,
+        inherited: Inherited,
+    ) -> TaskFuture {
+        let this = self.clone();
+        Box::pin(async move {
+            this.execute_task(&name, call_vars.as_ref(), inherited)
+                .await
+        })
+    }
+
+    /// Run a task between `task_started` and `task_finished` events
+    async fn execute_task(
+        &self,
+        name: &str,
+        call_vars: Option<&HashMap<String, Value>>,
+        inherited: Inherited,
+    ) -> Result<()> {
+        events::emit_if_enabled(|| Event::TaskStarted {
+            task: name.to_string(),
+        });
+        let started = Instant::now();
+        let capture = self
+            .inner
+            .report
+            .as_ref()
+            .map(|_| Arc::new(junit::Capture::default()));
+        let result = self
+            .execute_task_steps(name, call_vars, inherited, capture.clone())
+            .await;
+        if let Some(ref report) = self.inner.report {
+            report.add(name, &result, started.elapsed(), capture.as_deref());
+        }
+        summary::record(
+            Phase::Task,
+            name,
+            *result.as_ref().unwrap_or(&Status::Failed),
+            started.elapsed(),
+        );
+        events::emit_if_enabled(|| Event::TaskFinished {
+            task: name.to_string(),
+            status: *result.as_ref().unwrap_or(&Status::Failed),
+            duration_ms: events::millis(started.elapsed()),
+            exit_code: result.as_ref().err().map_or(0, RazdError::exit_code),
+        });
+        result.map(|_| ())
+    }
+
+    /// Run a task: its dependencies, checks and commands
+    /// Returns whether the task ran or why it did not
+    async fn execute_task_steps(
+        &self,
+        name: &str,
+        call_vars: Option<&HashMap<String, Value>>,
+        inherited: Inherited,
+        capture: Option<Arc<junit::Capture>>,
+    ) -> Result<Status> {
+        let task = self
+            .workflow()
+            .config
+            .tasks
+            .get(name)
+            .ok_or_else(|| graph::task_not_found(self.workflow(), name, None))?;
+
+        if !platform::matches_current(task.platforms.as_ref()) {
+            output::info(&format!(
+                "Skipping task '{}' (not available on {})",
+                name,
+                platform::current_os()
+            ));
+            return Ok(Status::Skipped);
+        }
+
+        let vars = self.task_vars(name, task, call_vars, &inherited).await?;
+        let task_dir = self.task_dir(task, &vars);
+        let envs = self.task_env(task, &vars, &task_dir)?;
+
+        if let Some(ref deps) = task.deps {
+            self.run_deps(name, deps, &vars).await?;
+        }
+
+        self.check_preconditions(name, task, &task_dir, &vars, &envs)
+            .await?;
+
+        if !self.inner.options.force
+            && self
+                .is_up_to_date(name, task, &task_dir, &vars, &envs)
+                .await?
+        {
+            output::info(&format!("Task '{}' is up to date", name));
+            return Ok(Status::UpToDate);
+        }
+
+        let retry = RetryPolicy::new(
+            task.retries.unwrap_or(0),
+            task.retry_delay.map(|delay| delay.as_duration()),
+        );
+        let mut run = TaskRun {
+            cleanup: inherited.cleanup,
+            // Events carry the task name already, so their output is never grouped
+            group: (self.inner.options.output.mode() == OutputMode::Grouped && !events::enabled())
+                .then(|| Arc::new(OutputGroup::default())),
+            retried: retry.retries > 0,
+            capture,
+            ..Default::default()
+        };
+        let mut attempt = 1;
+        let result = loop {
+            // The limit starts with the task's own commands; dependencies have their own
+            let own_limit = task
+                .timeout
+                .map(|timeout| timeout.as_duration())
+                .or(self.inner.options.timeout)
+                .map(|timeout| Deadline::start(name, timeout));
+            run.deadline = Deadline::earliest(own_limit, inherited.deadline.clone());
+            run.failed = false;
+
+            let result = self
+                .run_commands(name, task, &task_dir, &vars, &envs, &mut run)
+                .await;
+            let error = match result {
+                Err(e) if e.is_retryable() && retry.allows_retry(attempt) => e,
+                Err(e) if attempt > 1 => {
+                    break Err(RazdError::retries_exhausted(
+                        format!("Task '{}'", name),
+                        attempt,
+                        e,
+                    ))
+                }
+                result => break result,
+            };
+            if self.halted(run.cleanup) {
+                break Err(error);
+            }
+
+            let wait = retry.backoff(attempt);
+            if let Some(ref log) = run.log {
+                log.write(
+                    Marker::Retry,
+                    &format!(
+                        "task attempt {}/{} failed, retrying in {}",
+                        attempt,
+                        retry.attempts(),
+                        HumanDuration(wait)
+                    ),
+                );
+            }
+            retry.announce(&format!("Task '{}'", name), attempt, &error, wait);
+            // Stopping while waiting reports the failure that led to the wait
+            if !self.pause(run.cleanup, wait).await {
+                break Err(error);
+            }
+            attempt += 1;
+        };
+
+        if let Some(ref group) = run.group {
+            self.print_group(name, group, &vars);
+        }
+        if let (Some(log), true) = (&run.log, run.failed) {
+            output::info(&format!(
+                "Output of task '{}' saved to {}",
+                name,
+                log.path().display()
+            ));
+        }
+        result?;
+
+        fingerprint::record_checksum(&task_dir, name, task)?;
+        Ok(Status::Succeeded)
+    }
+
+    /// Run the commands of a task in order, including the tasks it references,
+    /// then its deferred commands
+    async fn run_commands(
+        &self,
+        name: &str,
+        task: &TaskConfig,
+        task_dir: &Path,
+        vars: &HashMap<String, String>,
+        envs: &[(String, String)],
+        run: &mut TaskRun,
+    ) -> Result<()> {
+        let result = self
+            .run_own_commands(name, task, task_dir, vars, envs, run)
+            .await;
+
+        // Deferred commands run whatever happened; the task's own failure is the one reported
+        let mut cleanup = Ok(());
+        while let Some(deferred) = run.deferred.pop() {
+            let silent = task.silent.unwrap_or(false);
+            let deferred_result = match deferred {
+                Deferred::String(ref line) => {
+                    let spec = CommandSpec {
+                        line,
+                        silent,
+                        ignore_error: false,
+                        set: None,
+                        shopt: None,
+                        timeout: None,
+                        deferred: true,
+                        retry: RetryPolicy::default(),
+                    };
+                    if run.log.is_none() {
+                        run.log = self.open_log(name);
+                    }
+                    self.run_command(name, task_dir, &spec, vars, envs, run)
+                        .await
+                }
+                Deferred::TaskRef {
+                    task: ref_name,
+                    vars: ref_vars,
+                    ..
+                } => {
+                    let call_vars = vars::render_call_vars(ref_vars.as_ref(), vars);
+                    let inherited = Inherited {
+                        deadline: None,
+                        cleanup: true,
+                    };
+                    self.run_task(ref_name, call_vars, inherited).await
+                }
+            };
+            if let Err(e) = deferred_result {
+                if result.is_ok() && cleanup.is_ok() {
+                    cleanup = Err(e);
+                } else {
+                    output::warning(&format!(
+                        "Deferred command of task '{}' failed: {}",
+                        name, e
+                    ));
+                }
+            }
+        }
+
+        result.and(cleanup)
+    }
+
+    /// Run the commands of a task in order, collecting the deferred ones
+    async fn run_own_commands(
+        &self,
+        name: &str,
+        task: &TaskConfig,
+        task_dir: &Path,
+        vars: &HashMap<String, String>,
+        envs: &[(String, String)],
+        run: &mut TaskRun,
+    ) -> Result<()> {
+        let task_silent = task.silent.unwrap_or(false);
+
+        for cmd in &task.cmds {
+            let spec = match cmd {
+                Command::String(line) => CommandSpec {
+                    line,
+                    silent: task_silent,
+                    ignore_error: false,
+                    set: None,
+                    shopt: None,
+                    timeout: None,
+                    deferred: false,
+                    retry: RetryPolicy::default(),
+                },
+                Command::TaskRef {
+                    task: ref_name,
+                    vars: ref_vars,
+                    ..
+                } => {
+                    let call_vars = vars::render_call_vars(ref_vars.as_ref(), vars);
+                    let inherited = Inherited {
+                        deadline: run.deadline.clone(),
+                        cleanup: run.cleanup,
+                    };
+                    self.run_task(ref_name.clone(), call_vars, inherited)
+                        .await?;
+                    continue;
+                }
+                Command::Defer { defer } => {
+                    run.deferred.push(defer.clone());
+                    continue;
+                }
+                Command::Complex {
+                    cmd,
+                    silent,
+                    platforms,
+                    ignore_error,
+                    set,
+                    shopt,
+                    timeout,
+                    retries,
+                    retry_delay,
+                } => {
+                    if !platform::matches_current(platforms.as_ref()) {
+                        continue;
+                    }
+                    CommandSpec {
+                        line: cmd,
+                        silent: silent.unwrap_or(task_silent),
+                        ignore_error: ignore_error.unwrap_or(false),
+                        set: set.as_ref(),
+                        shopt: shopt.as_ref(),
+                        timeout: timeout.map(|timeout| timeout.as_duration()),
+                        deferred: false,
+                        retry: RetryPolicy::new(
+                            retries.unwrap_or(0),
+                            retry_delay.map(|delay| delay.as_duration()),
+                        ),
+                    }
+                }
+            };
+
+            if run.log.is_none() {
+                run.log = self.open_log(name);
+            }
+            let result = self
+                .run_command(name, task_dir, &spec, vars, envs, run)
+                .await;
+            run.failed = result.is_err();
+            result?;
+        }
+
+        Ok(())
+    }
+
+    /// Print the grouped output of a task between its `output:` markers
+    fn print_group(&self, name: &str, group: &OutputGroup, vars: &HashMap<String, String>) {
+        let markers = self.inner.options.output.markers();
+        let mut marker_vars = vars.clone();
+        marker_vars.insert("TASK".to_string(), name.to_string());
+        let render =
+            |marker: Option<&String>| marker.map(|marker| template::render(marker, &marker_vars));
+        group.print(
+            render(markers.and_then(|m| m.begin.as_ref())).as_deref(),
+            render(markers.and_then(|m| m.end.as_ref())).as_deref(),
+        );
+    }
+
+    /// Run the dependencies of a task concurrently and wait for all of them
+    /// Returns the first failure after every dependency has finished
+    async fn run_deps(
+        &self,
+        task_name: &str,
+        deps: &[Dependency],
+        vars: &HashMap<String, String>,
+    ) -> Result<()> {
+        let mut running = JoinSet::new();
+
+        for dep in deps {
+            let this = self.clone();
+            let dep_name = graph::dependency_name(dep).to_string();
+            let dep_vars = vars::render_call_vars(graph::dependency_vars(dep), vars);
+            log::debug("executor", || {
+                format!("Starting dependency '{}' of task '{}'", dep_name, task_name)
+            });
+            running.spawn(async move { this.run_dep_once(dep_name, dep_vars).await });
+        }
+
+        let mut first_error = None;
+        while let Some(joined) = running.join_next().await {
+            let result = joined
+                .map_err(|e| {
+                    RazdError::task(format!(
+                        "Dependency of task '{}' did not complete: {}",
+                        task_name, e
+                    ))
+                })
+                .and_then(|result| result);
+
+            if let Err(e) = result {
+                first_error.get_or_insert(e);
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Run a dependency unless the same invocation already ran (or is running)
+    async fn run_dep_once(&self, name: String, vars: Option<HashMap<String, Value>>) -> Result<()> {
+        let key = graph::invocation_key(&name, vars.as_ref());
+        let cell = {
+            let mut deps_run = self
+                .inner
+                .deps_run
+                .lock()
+                .map_err(|_| RazdError::task("Dependency tracking state is poisoned"))?;
+            deps_run.entry(key).or_default().clone()
+        };
+
+        // Only the first caller runs the task; it keeps the detailed error for itself
+        let mut own_result = None;
+        let succeeded = *cell
+            .get_or_init(|| async {
+                let result = self
+                    .run_task(name.clone(), vars.clone(), Inherited::default())
+                    .await;
+                let succeeded = result.is_ok();
+                own_result = Some(result);
+                succeeded
+            })
+            .await;
+
+        match own_result {
+            Some(result) => result,
+            None if succeeded => {
+                log::debug("executor", || {
+                    format!("Dependency '{}' already ran in this invocation", name)
+                });
+                Ok(())
+            }
+            None => Err(RazdError::task(format!("Dependency '{}' failed", name))),
+        }
+    }
+
+    /// Run one shell command line of a task, again after failures as far as its `retries:` allow
+    async fn run_command(
+        &self,
+        task_name: &str,
+        dir: &Path,
+        spec: &CommandSpec<'_>,
+        vars: &HashMap<String, String>,
+        envs: &[(String, String)],
+        run: &TaskRun,
+    ) -> Result<()> {
+        let line = template::render(spec.line, vars);
+        let cleanup = spec.deferred || run.cleanup;
+
+        let mut attempt = 1;
+        let failure = loop {
+            let Some(failure) = self
+                .run_attempt(task_name, dir, spec, &line, envs, run)
+                .await?
+            else {
+                return Ok(());
+            };
+            // A command that failed because razd is stopping is not retried
+            if !spec.retry.allows_retry(attempt) || self.halted(cleanup) {
+                break failure;
+            }
+            let wait = spec.retry.backoff(attempt);
+            if let Some(ref log) = run.log {
+                log.write(
+                    Marker::Retry,
+                    &format!(
+                        "attempt {}/{} failed, retrying in {}",
+                        attempt,
+                        spec.retry.attempts(),
+                        HumanDuration(wait)
+                    ),
+                );
+            }
+            spec.retry.announce(
+                &format!("Task '{}': `{}`", task_name, line),
+                attempt,
+                &failure.error,
+                wait,
+            );
+            if !self.pause(cleanup, wait).await {
+                break failure;
+            }
+            attempt += 1;
+        };
+
+        if spec.ignore_error {
+            output::warning(&format!("{} (ignored)", failure.summary));
+            return Ok(());
+        }
+        match attempt {
+            1 => Err(failure.error),
+            _ => Err(RazdError::retries_exhausted(
+                format!("Task '{}': `{}`", task_name, line),
+                attempt,
+                failure.error,
+            )),
+        }
+    }
+
+    /// Run one attempt of a shell command line
+    /// Returns the failure of the command itself (exit code or own time limit), which
+    /// `retries:` and `ignore_error:` cover; other errors stop the task right away.
+    async fn run_attempt(
+        &self,
+        task_name: &str,
+        dir: &Path,
+        spec: &CommandSpec<'_>,
+        line: &str,
+        envs: &[(String, String)],
+        run: &TaskRun,
+    ) -> Result<Option<Failure>> {
+        let _slot = self
+            .inner
+            .job_slots
+            .acquire()
+            .await
+            .map_err(|e| RazdError::task(format!("Failed to acquire job slot: {}", e)))?;
+
+        if !spec.silent {
+            run.command(task_name, line);
+        }
+
+        let log = run.log.as_ref();
+        if let Some(log) = log {
+            log.write(Marker::Command, line);
+        }
+
+        let script = build_script(line, spec.set, spec.shopt);
+        let options = &self.inner.options;
+        let command_deadline = spec
+            .timeout
+            .map(|timeout| Deadline::start(task_name, timeout));
+        // Deferred commands also clean up after a task that ran out of time
+        let task_deadline = run.deadline.as_ref().filter(|_| !spec.deferred);
+        // Whether the command's own limit is the one that applies, which `ignore_error` covers
+        let own_limit = command_deadline
+            .as_ref()
+            .is_some_and(|own| task_deadline.is_none_or(|task| own.at() <= task.at()));
+        let deadline = Deadline::earliest(command_deadline, task_deadline.cloned());
+        let cleanup = spec.deferred || run.cleanup;
+        // With `--format json` output only reaches the terminal as events
+        let relay = match options.output.mode() {
+            _ if events::enabled() => process::Relay::Quiet,
+            OutputMode::Interleaved => process::Relay::Passthrough,
+            OutputMode::Prefixed => process::Relay::Prefixed(output::task_prefix(task_name)),
+            OutputMode::Grouped => process::Relay::Quiet,
+        };
+        // Commands get their own process group so signals reach their children too. One
+        // reading the terminal stays in razd's group (where the terminal's Ctrl-C reaches it
+        // directly), since reading from a background group would suspend it; unless it has
+        // a time limit, which must be able to stop it with its children.
+        let reads_terminal = relay == process::Relay::Passthrough && process::stdin_is_terminal();
+        let groups = options.process_groups || deadline.is_some() || !reads_terminal;
+        // The end of stderr goes into the error of a command that may be retried
+        let stderr_tail =
+            (spec.retry.retries > 0 || run.retried).then(|| Arc::new(Mutex::new(VecDeque::new())));
+        // Commands writing to a terminal keep it, so they keep their colors and progress
+        // output; their log then holds the commands and exit codes only
+        let tee_log = log.is_some() && !process::stdout_is_terminal();
+        // Output is piped when it has to be tagged, buffered, teed into the log or kept
+        let (pid, wait): (Option<u32>, process::WaitFuture<'_>) = if relay
+            != process::Relay::Passthrough
+            || tee_log
+            || stderr_tail.is_some()
+            || run.capture.is_some()
+        {
+            // Only a command whose output goes straight to the terminal may read from it
+            let inherit_stdin = relay == process::Relay::Passthrough;
+            let child = self.spawn_tracked(task_name, cleanup, |running| {
+                let child =
+                    process::spawn_shell_piped(&script, Some(dir), envs, groups, inherit_stdin)?;
+                running.extend(child.id().map(|pid| (pid, groups)));
+                Ok(child)
+            })?;
+            let pid = child.id();
+            (
+                pid,
+                Box::pin(process::wait_with_relay(
+                    child,
+                    relay,
+                    run.sink(task_name, stderr_tail.clone()),
+                    task_name,
+                )),
+            )
+        } else {
+            let child = self.spawn_tracked(task_name, cleanup, |running| {
+                let child = process::spawn_shell_interactive(&script, Some(dir), envs, groups)?;
+                running.insert(child.id(), groups);
+                Ok(child)
+            })?;
+            let pid = child.id();
+            (
+                Some(pid),
+                Box::pin(process::wait_for_status(child, task_name)),
+            )
+        };
+        let status = process::wait_until(
+            wait,
+            pid,
+            deadline.as_ref().map(Deadline::at),
+            self.stop_grace(),
+        )
+        .await;
+        if let (Some(pid), Ok(mut running)) = (pid, self.inner.running.lock()) {
+            running.remove(&pid);
+        }
+
+        let Some(status) = status else {
+            if let Some(log) = log {
+                log.write(Marker::Exit, "timeout");
+            }
+            // Deadlines are only set when a wait can expire
+            let deadline = deadline.expect("a command can only time out with a deadline");
+            let error = RazdError::timed_out(
+                deadline.task.as_str(),
+                line,
+                deadline.started.elapsed(),
+                deadline.limit,
+            );
+            if own_limit {
+                return Ok(Some(Failure {
+                    summary: error.to_string(),
+                    error,
+                }));
+            }
+            return Err(error);
+        };
+        let status = status?;
+        let code = status
+            .code()
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "signal".to_string());
+        if let Some(log) = log {
+            log.write(Marker::Exit, &code);
+        }
+
+        if status.success() {
+            return Ok(None);
+        }
+
+        let mut message = format!(
+            "Task '{}' failed: `{}` exited with code {} (defined in {})",
+            task_name,
+            line,
+            code,
+            self.defined_in(task_name)
+        );
+        if let Some(tail) = stderr_tail.as_ref().and_then(|tail| tail.lock().ok()) {
+            if !tail.is_empty() {
+                message.push_str("\nLast lines of stderr:");
+                for tail_line in tail.iter() {
+                    message.push_str("\n  ");
+                    message.push_str(tail_line);
+                }
+            }
+        }
+        Ok(Some(Failure {
+            summary: format!("Task '{}': command exited with code {}", task_name, code),
+            error: RazdError::task_failed(message, status.code().unwrap_or(1)),
+        }))
+    }
+
+    /// Wait before a retry; false when the executor was stopped in the meantime
+    async fn pause(&self, cleanup: bool, wait: Duration) -> bool {
+        let until = Instant::now() + wait;
+        loop {
+            let halted = self.inner.halted.notified();
+            tokio::pin!(halted);
+            halted.as_mut().enable();
+            if self.halted(cleanup) {
+                return false;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep_until(until) => return true,
+                _ = halted => {}
+            }
+        }
+    }
+
+    /// Whether new commands are refused: all after `stop(true)`, all but cleanup after
+    /// `stop(false)` and `interrupt`
+    fn halted(&self, cleanup: bool) -> bool {
+        self.inner.killed.load(Ordering::SeqCst)
+            || (self.inner.stopped.load(Ordering::SeqCst) && !cleanup)
+    }
+
+    /// Start the log file of a task run; failing to create it only warns
+    fn open_log(&self, task_name: &str) -> Option<Arc<TaskLog>> {
+        let log_dir = self.inner.options.log_dir.as_ref()?;
+        match TaskLog::create(log_dir, task_name, &self.inner.working_dir) {
+            Ok(log) => Some(Arc::new(log)),
+            Err(e) => {
+                output::warning(&format!("Not logging task '{}': {}", task_name, e));
+                None
+            }
+        }
+    }
+
+    /// Spawn a command and record its process id, unless the executor was stopped
+    /// `cleanup` commands are still started after `stop(false)` and `interrupt`.
+    /// The running set stays locked while spawning so `stop` cannot miss the command
+    fn spawn_tracked<C>(
+        &self,
+        task_name: &str,
+        cleanup: bool,
+        spawn: impl FnOnce(&mut HashMap<u32, bool>) -> Result<C>,
+    ) -> Result<C> {
+        let mut running = self
+            .inner
+            .running
+            .lock()
+            .map_err(|_| RazdError::task("Process tracking state is poisoned"))?;
+        if self.halted(cleanup) {
+            return Err(RazdError::stopped(task_name));
+        }
+        spawn(&mut running)
+    }
+
+    /// Time stopped commands get to exit after SIGTERM before they are killed
+    pub fn stop_grace(&self) -> Duration {
+        self.inner
+            .options
+            .stop_grace
+            .unwrap_or(Duration::from_millis(defaults::DEFAULT_STOP_GRACE_MS))
+    }
+
+    /// Stop every running command and prevent new ones from starting
+    /// Commands get SIGTERM first and deferred cleanup commands still run;
+    /// `force` sends SIGKILL instead and does not start anything anymore
+    pub fn stop(&self, force: bool) {
+        self.inner.stopped.store(true, Ordering::SeqCst);
+        if force {
+            self.inner.killed.store(true, Ordering::SeqCst);
+        }
+        self.inner.halted.notify_waiters();
+        if let Ok(running) = self.inner.running.lock() {
+            for (pid, group) in running.iter() {
+                process::terminate_process(*pid, *group, force);
+            }
+        }
+    }
+
+    /// Pass a signal razd received on to every running command and start no new ones
+    /// but deferred cleanup commands
+    pub fn interrupt(&self, signal: Signal) {
+        self.inner.stopped.store(true, Ordering::SeqCst);
+        self.inner.halted.notify_waiters();
+        if let Ok(running) = self.inner.running.lock() {
+            for (pid, group) in running.iter() {
+                process::forward_signal(*pid, *group, signal);
+            }
+        }
+    }
+
+    /// File a task was defined in: the included Razdfile or the workflow source
+    fn defined_in(&self, task_name: &str) -> String {
+        self.workflow()
+            .config
+            .tasks
+            .get(task_name)
+            .and_then(|task| task.source.as_ref())
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| self.workflow().source_display())
+    }
+
+    /// Collect variables visible to a task, running its `sh:` vars; see `vars` for the
+    /// precedence order. Evaluation is abandoned, killing the command it waits for, when the
+    /// executor is stopped or the limit of the calling task passes.
+    async fn task_vars(
+        &self,
+        name: &str,
+        task: &TaskConfig,
+        call_vars: Option<&HashMap<String, Value>>,
+        inherited: &Inherited,
+    ) -> Result<HashMap<String, String>> {
+        let layers = self.var_layers(task, call_vars);
+        let evaluation = vars::evaluate(&layers, &self.inner.shell_vars, &self.inner.working_dir);
+        tokio::pin!(evaluation);
+        let deadline = inherited.deadline.as_ref();
+        loop {
+            let halted = self.inner.halted.notified();
+            tokio::pin!(halted);
+            halted.as_mut().enable();
+            if self.halted(inherited.cleanup) {
+                return Err(RazdError::stopped(name));
+            }
+            tokio::select! {
+                vars = &mut evaluation => return vars,
+                _ = halted => {}
+                _ = tokio::time::sleep_until(deadline.map_or_else(Instant::now, Deadline::at)),
+                    if deadline.is_some() =>
+                {
+                    let deadline = deadline.expect("only waited for with a deadline");
+                    return Err(RazdError::timed_out(
+                        deadline.task.as_str(),
+                        "evaluating vars",
+                        deadline.started.elapsed(),
+                        deadline.limit,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Collect variables visible to a task without running anything, `sh:` vars shown
+    /// as `$(command)`
+    fn described_task_vars(
+        &self,
+        task: &TaskConfig,
+        call_vars: Option<&HashMap<String, Value>>,
+    ) -> HashMap<String, String> {
+        vars::describe(&self.var_layers(task, call_vars))
+    }
+
+    fn var_layers<'a>(
+        &'a self,
+        task: &'a TaskConfig,
+        call_vars: Option<&'a HashMap<String, Value>>,
+    ) -> VarLayers<'a> {
+        VarLayers {
+            global: self.workflow().config.vars.as_ref(),
+            task: task.vars.as_ref(),
+            call: call_vars,
+            cli: &self.inner.options.vars,
+        }
+    }
+
+    /// Directory a task runs in, see `task_dir`
+    fn task_dir(&self, task: &TaskConfig, vars: &HashMap<String, String>) -> PathBuf {
+        task_dir(&self.inner.working_dir, task, vars)
+    }
+
+    /// Collect environment variables for a task, later sources overriding earlier ones:
+    /// global dotenv files, global env, task dotenv files, task env
+    /// Global dotenv paths are relative to the project directory, task ones to the task directory.
+    fn task_env(
+        &self,
+        task: &TaskConfig,
+        vars: &HashMap<String, String>,
+        task_dir: &Path,
+    ) -> Result<Vec<(String, String)>> {
+        let config = &self.workflow().config;
+        let mut env = IndexMap::new();
+
+        self.load_dotenv(
+            config.dotenv.as_ref(),
+            &self.inner.working_dir,
+            vars,
+            &mut env,
+        )?;
+        extend_env(&mut env, config.env.as_ref(), vars);
+        self.load_dotenv(task.dotenv.as_ref(), task_dir, vars, &mut env)?;
+        extend_env(&mut env, task.env.as_ref(), vars);
+
+        Ok(env.into_iter().collect())
+    }
+
+    /// Add the entries of dotenv files to `env`, in order
+    /// A missing file is an error unless it is marked `optional`, which only warns once per run
+    fn load_dotenv(
+        &self,
+        files: Option<&Vec<Dotenv>>,
+        base: &Path,
+        vars: &HashMap<String, String>,
+        env: &mut IndexMap<String, String>,
+    ) -> Result<()> {
+        for file in files.into_iter().flatten() {
+            let path = base.join(template::render(file.file(), vars));
+            match dotenv::load(&path, env)? {
+                Some(entries) => env.extend(entries),
+                None if file.optional() => {
+                    let first_time = self
+                        .inner
+                        .dotenv_warned
+                        .lock()
+                        .map(|mut warned| warned.insert(path.clone()))
+                        .unwrap_or(false);
+                    if first_time {
+                        output::warning(&format!(
+                            "Optional dotenv file not found: {}",
+                            path.display()
+                        ));
+                    }
+                }
+                None => {
+                    return Err(RazdError::config(format!(
+                        "Dotenv file not found: {} (use `optional: true` if it may be missing)",
+                        path.display()
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Add rendered `env:` entries to an environment, overriding existing keys
+fn extend_env(
+    env: &mut IndexMap<String, String>,
+    entries: Option<&IndexMap<String, Value>>,
+    vars: &HashMap<String, String>,
+) {
+    for (key, value) in entries.into_iter().flatten() {
+        if let Some(raw) = template::value_to_string(value) {
+            env.insert(key.clone(), template::render(&raw, vars));
+        }
+    }
+}
+
+/// Directory a task runs in, with placeholders in `dir:` rendered
+pub fn task_dir(working_dir: &Path, task: &TaskConfig, vars: &HashMap<String, String>) -> PathBuf {
+    match task.dir {
+        Some(ref dir) => working_dir.join(template::render(dir, vars)),
+        None => working_dir.to_path_buf(),
+    }
+}
+
+/// Pair every top-level task with its call vars; only the last task gets `CLI_ARGS`
+fn invocations(
+    task_names: &[String],
+    cli_args: &[String],
+) -> Vec<(String, Option<HashMap<String, Value>>)> {
+    let last = task_names.len().saturating_sub(1);
+    task_names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let vars = (index == last && !cli_args.is_empty()).then(|| {
+                let mut vars = HashMap::new();
+                vars.insert("CLI_ARGS".to_string(), Value::from(cli_args.join(" ")));
+                vars
+            });
+            (name.clone(), vars)
+        })
+        .collect()
+}
+
+/// Turn the failures of a multi-task run into a single error
+/// The exit code is always the one of the first failure
+fn combine_failures(failures: Vec<RazdError>, total: usize, report_all: bool) -> Result<()> {
+    let mut failures = failures.into_iter();
+    let Some(first) = failures.next() else {
+        return Ok(());
+    };
+    let rest: Vec<RazdError> = failures.collect();
+    if !report_all || rest.is_empty() {
+        return Err(first);
+    }
+
+    let exit_code = first.exit_code();
+    let count = rest.len() + 1;
+    let details: Vec<String> = std::iter::once(first)
+        .chain(rest)
+        .map(|e| match e {
+            RazdError::Task(message) | RazdError::TaskFailed { message, .. } => {
+                format!("  - {}", message)
+            }
+            other => format!("  - {}", other),
+        })
+        .collect();
+    Err(RazdError::task_failed(
+        format!(
+            "{} of {} tasks failed:\n{}",
+            count,
+            total,
+            details.join("\n")
+        ),
+        exit_code,
+    ))
+}
+
+/// Prefix a command line with `set`/`shopt` options from the complex command syntax
+fn build_script(line: &str, set: Option<&Vec<String>>, shopt: Option<&Vec<String>>) -> String {
+    let mut script = String::new();
+
+    for opt in set.into_iter().flatten() {
+        if opt.len() == 1 {
+            script.push_str(&format!("set -{}\n", opt));
+        } else {
+            script.push_str(&format!("set -o {}\n", opt));
+        }
+    }
+
+    for opt in shopt.into_iter().flatten() {
+        script.push_str(&format!("shopt -s {}\n", opt));
+    }
+
+    script.push_str(line);
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RazdfileConfig;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn executor_for(yaml: &str, dir: &TempDir) -> Executor {
+        let config: RazdfileConfig = serde_yaml::from_str(yaml).unwrap();
+        let workflow = Workflow {
+            config,
+            source: Some(dir.path().join("Razdfile.yml")),
+        };
+        Executor::new(
+            workflow,
+            dir.path().to_path_buf(),
+            ExecutorOptions::default(),
+        )
+    }
+
+    #[test]
+    fn test_build_script_with_options() {
+        let set = vec!["e".to_string(), "pipefail".to_string()];
+        let shopt = vec!["globstar".to_string()];
+        let script = build_script("echo hi", Some(&set), Some(&shopt));
+        assert_eq!(
+            script,
+            "set -e\nset -o pipefail\nshopt -s globstar\necho hi"
+        );
+    }
+
+    #[test]
+    fn test_build_script_plain() {
+        assert_eq!(build_script("echo hi", None, None), "echo hi");
+    }
+
+    #[test]
+    fn test_task_vars_precedence() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+vars:
+  NAME: global
+  GREETING: "hello {{.NAME}}"
+tasks:
+  greet:
+    vars:
+      NAME: task
+    cmds:
+      - echo hi
+"#,
+            &temp_dir,
+        );
+
+        let task = executor.workflow().config.tasks.get("greet").unwrap();
+        let vars = executor.described_task_vars(task, None);
+        assert_eq!(vars.get("NAME").unwrap(), "task");
+        assert_eq!(vars.get("GREETING").unwrap(), "hello global");
+        assert_eq!(vars.get("CLI_ARGS").unwrap(), "");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_executes_deps_and_task_refs_in_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  default:
+    deps: [prepare]
+    cmds:
+      - echo main >> log.txt
+      - task: finish
+  prepare:
+    cmds:
+      - echo prepare >> log.txt
+  finish:
+    cmds:
+      - echo finish >> log.txt
+"#,
+            &temp_dir,
+        );
+
+        executor
+            .run_all(&["default".to_string()], &[])
+            .await
+            .unwrap();
+
+        let log = fs::read_to_string(temp_dir.path().join("log.txt")).unwrap();
+        assert_eq!(log, "prepare\nmain\nfinish\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_applies_set_and_shopt_options() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  default:
+    cmds:
+      - cmd: false | true || echo pipefail >> log.txt
+        set: [e, pipefail]
+      - cmd: mkdir -p a/b && touch a/b/c.txt && echo **/c.txt >> log.txt
+        shopt: [globstar]
+"#,
+            &temp_dir,
+        );
+
+        executor
+            .run_all(&["default".to_string()], &[])
+            .await
+            .unwrap();
+        let log = fs::read_to_string(temp_dir.path().join("log.txt")).unwrap();
+        assert_eq!(log, "pipefail\na/b/c.txt\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_reports_failing_command() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  broken:
+    cmds:
+      - exit 3
+"#,
+            &temp_dir,
+        );
+
+        let err = executor
+            .run_all(&["broken".to_string()], &[])
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("Task 'broken' failed"), "{}", err);
+        assert!(err.contains("exited with code 3"));
+        assert!(err.contains("Razdfile.yml"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_ignores_errors_when_requested() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  lenient:
+    cmds:
+      - cmd: exit 1
+        ignore_error: true
+      - echo after >> log.txt
+"#,
+            &temp_dir,
+        );
+
+        executor
+            .run_all(&["lenient".to_string()], &[])
+            .await
+            .unwrap();
+        assert!(temp_dir.path().join("log.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_deps_concurrently() {
+        let temp_dir = TempDir::new().unwrap();
+        // Each dependency waits for the other one's marker file, so the task
+        // only succeeds when both dependencies run at the same time
+        let executor = executor_for(
+            r#"
+tasks:
+  default:
+    deps: [left, right]
+    cmds: []
+  left:
+    cmds:
+      - touch left.flag; i=0; while [ ! -f right.flag ]; do i=$((i+1)); [ $i -gt 100 ] && exit 1; sleep 0.05; done
+  right:
+    cmds:
+      - touch right.flag; i=0; while [ ! -f left.flag ]; do i=$((i+1)); [ $i -gt 100 ] && exit 1; sleep 0.05; done
+"#,
+            &temp_dir,
+        );
+
+        executor
+            .run_all(&["default".to_string()], &[])
+            .await
+            .unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_shared_dependency_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  default:
+    deps: [api, web]
+    cmds: []
+  api:
+    deps: [codegen]
+    cmds: []
+  web:
+    deps: [codegen]
+    cmds: []
+  codegen:
+    cmds:
+      - echo codegen >> log.txt
+"#,
+            &temp_dir,
+        );
+
+        executor
+            .run_all(&["default".to_string()], &[])
+            .await
+            .unwrap();
+
+        let log = fs::read_to_string(temp_dir.path().join("log.txt")).unwrap();
+        assert_eq!(log, "codegen\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_reports_failed_dependency() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  default:
+    deps: [ok, broken]
+    cmds:
+      - echo main >> log.txt
+  ok:
+    cmds:
+      - "true"
+  broken:
+    cmds:
+      - exit 2
+"#,
+            &temp_dir,
+        );
+
+        let err = executor
+            .run_all(&["default".to_string()], &[])
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Task 'broken' failed"));
+        assert!(!temp_dir.path().join("log.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_skips_up_to_date_task() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("input.txt"), "one").unwrap();
+        let yaml = r#"
+tasks:
+  build:
+    sources: ["*.txt"]
+    generates: ["out.bin"]
+    cmds:
+      - echo build >> log.txt && touch out.bin
+"#;
+
+        executor_for(yaml, &temp_dir)
+            .run_all(&["build".to_string()], &[])
+            .await
+            .unwrap();
+        executor_for(yaml, &temp_dir)
+            .run_all(&["build".to_string()], &[])
+            .await
+            .unwrap();
+        let log = fs::read_to_string(temp_dir.path().join("log.txt")).unwrap();
+        assert_eq!(log, "build\n");
+
+        // --force bypasses the check
+        let config: RazdfileConfig = serde_yaml::from_str(yaml).unwrap();
+        let workflow = Workflow {
+            config,
+            source: None,
+        };
+        let options = ExecutorOptions {
+            force: true,
+            ..Default::default()
+        };
+        Executor::new(workflow, temp_dir.path().to_path_buf(), options)
+            .run_all(&["build".to_string()], &[])
+            .await
+            .unwrap();
+        let log = fs::read_to_string(temp_dir.path().join("log.txt")).unwrap();
+        assert_eq!(log, "build\nbuild\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_all_passes_cli_args_to_last_task() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  first:
+    cmds:
+      - echo "first [{{.CLI_ARGS}}]" >> log.txt
+  second:
+    cmds:
+      - echo "second [{{.CLI_ARGS}}]" >> log.txt
+"#,
+            &temp_dir,
+        );
+
+        let tasks = vec!["first".to_string(), "second".to_string()];
+        let args = vec!["-v".to_string(), "x".to_string()];
+        executor.run_all(&tasks, &args).await.unwrap();
+
+        let log = fs::read_to_string(temp_dir.path().join("log.txt")).unwrap();
+        assert_eq!(log, "first []\nsecond [-v x]\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_all_stops_at_first_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  broken:
+    cmds:
+      - exit 4
+  after:
+    cmds:
+      - echo after >> log.txt
+"#,
+            &temp_dir,
+        );
+
+        let tasks = vec!["broken".to_string(), "after".to_string()];
+        let err = executor.run_all(&tasks, &[]).await.unwrap_err();
+        assert_eq!(err.exit_code(), 4);
+        assert!(!temp_dir.path().join("log.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_all_continue_on_error_reports_every_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let config: RazdfileConfig = serde_yaml::from_str(
+            r#"
+tasks:
+  lint:
+    cmds:
+      - exit 2
+  test:
+    cmds:
+      - exit 3
+  build:
+    cmds:
+      - echo build >> log.txt
+"#,
+        )
+        .unwrap();
+        let workflow = Workflow {
+            config,
+            source: None,
+        };
+        let options = ExecutorOptions {
+            parallel: true,
+            continue_on_error: true,
+            output: Output::Mode(OutputMode::Prefixed),
+            ..Default::default()
+        };
+        let executor = Executor::new(workflow, temp_dir.path().to_path_buf(), options);
+
+        let tasks = vec!["lint".to_string(), "test".to_string(), "build".to_string()];
+        let err = executor.run_all(&tasks, &[]).await.unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("2 of 3 tasks failed"), "{}", message);
+        assert!(message.contains("Task 'lint' failed"));
+        assert!(message.contains("Task 'test' failed"));
+        assert!(temp_dir.path().join("log.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_layers_dotenv_files_and_env() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("svc")).unwrap();
+        fs::write(
+            temp_dir.path().join(".env"),
+            "A=dotenv\nB=dotenv\nC=dotenv\nD=dotenv\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("svc/.env.local"),
+            "C=task-dotenv\nD=task-dotenv\n",
+        )
+        .unwrap();
+        let executor = executor_for(
+            r#"
+dotenv: [.env]
+env:
+  B: global-env
+  C: global-env
+tasks:
+  show:
+    dir: svc
+    dotenv:
+      - .env.local
+      - file: .env.missing
+        optional: true
+    env:
+      D: task-env
+    cmds:
+      - echo "$A $B $C $D" > ../env.txt
+"#,
+            &temp_dir,
+        );
+
+        executor.run_all(&["show".to_string()], &[]).await.unwrap();
+
+        let env = fs::read_to_string(temp_dir.path().join("env.txt")).unwrap();
+        assert_eq!(env, "dotenv global-env task-dotenv task-env\n");
+    }
+
+    #[tokio::test]
+    async fn test_run_reports_missing_dotenv_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  show:
+    dotenv: [.env]
+    cmds:
+      - echo never
+"#,
+            &temp_dir,
+        );
+
+        let err = executor
+            .run_all(&["show".to_string()], &[])
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Dotenv file not found"), "{}", err);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_skips_tasks_whose_status_succeeds() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  generate:
+    status:
+      - test -f generated.txt
+    cmds:
+      - echo run >> runs.txt
+      - touch generated.txt
+"#,
+            &temp_dir,
+        );
+
+        executor
+            .run_all(&["generate".to_string()], &[])
+            .await
+            .unwrap();
+        executor
+            .run_all(&["generate".to_string()], &[])
+            .await
+            .unwrap();
+        let runs = fs::read_to_string(temp_dir.path().join("runs.txt")).unwrap();
+        assert_eq!(runs.lines().count(), 1);
+
+        let checks = executor.check_task("generate").await.unwrap();
+        assert_eq!(checks.status, Some(true));
+        assert!(!checks.blocked());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_fails_on_precondition_with_message() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+vars:
+  CONFIG: settings.json
+tasks:
+  deploy:
+    preconditions:
+      - test -d .
+      - sh: test -f {{.CONFIG}}
+        msg: "{{.CONFIG}} is missing, run setup first"
+    cmds:
+      - touch deployed
+"#,
+            &temp_dir,
+        );
+
+        let err = executor
+            .run_all(&["deploy".to_string()], &[])
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("precondition failed: settings.json is missing, run setup first"),
+            "{}",
+            err
+        );
+        assert!(!temp_dir.path().join("deployed").exists());
+
+        let checks = executor.check_task("deploy").await.unwrap();
+        assert_eq!(checks.preconditions, vec![true, false]);
+        assert!(checks.blocked());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_stops_task_after_timeout() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  serve:
+    timeout: 300ms
+    cmds:
+      - task: wait
+      - touch finished
+  wait:
+    cmds:
+      - sleep 5; touch slept
+"#,
+            &temp_dir,
+        );
+
+        let started = std::time::Instant::now();
+        let err = executor
+            .run_all(&["serve".to_string()], &[])
+            .await
+            .unwrap_err();
+        assert!(started.elapsed() < std::time::Duration::from_secs(4));
+        assert_eq!(err.exit_code(), 124);
+        let message = err.to_string();
+        assert!(
+            message.contains("Task 'serve' timed out after"),
+            "{}",
+            message
+        );
+        assert!(message.contains("(timeout 300ms)"), "{}", message);
+        assert!(!temp_dir.path().join("slept").exists());
+        assert!(!temp_dir.path().join("finished").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_ignores_command_timeout_when_requested() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  lenient:
+    cmds:
+      - cmd: sleep 5
+        timeout: 200ms
+        ignore_error: true
+      - echo after >> log.txt
+"#,
+            &temp_dir,
+        );
+
+        executor
+            .run_all(&["lenient".to_string()], &[])
+            .await
+            .unwrap();
+        assert!(temp_dir.path().join("log.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_retries_flaky_command_until_it_succeeds() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  fetch:
+    cmds:
+      - cmd: echo try >> attempts.txt; test $(wc -l < attempts.txt) -ge 3
+        retries: 3
+        retry_delay: 10ms
+      - touch fetched
+"#,
+            &temp_dir,
+        );
+
+        executor.run_all(&["fetch".to_string()], &[]).await.unwrap();
+        let attempts = fs::read_to_string(temp_dir.path().join("attempts.txt")).unwrap();
+        assert_eq!(attempts.lines().count(), 3);
+        assert!(temp_dir.path().join("fetched").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_exhausted_retries_keep_last_stderr() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  fetch:
+    cmds:
+      - cmd: echo try >> attempts.txt; echo "attempt $(wc -l < attempts.txt) failed" >&2; exit 3
+        retries: 2
+        retry_delay: 10ms
+"#,
+            &temp_dir,
+        );
+
+        let err = executor
+            .run_all(&["fetch".to_string()], &[])
+            .await
+            .unwrap_err();
+        assert_eq!(err.exit_code(), 3);
+        let message = err.to_string();
+        assert!(message.contains("failed after 3 attempts"), "{}", message);
+        assert!(message.contains("attempt 3 failed"), "{}", message);
+        assert!(!message.contains("attempt 2 failed"), "{}", message);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_retries_whole_task() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  setup:
+    retries: 1
+    retry_delay: 10ms
+    cmds:
+      - echo step >> steps.txt
+      - test -f ready || (touch ready; exit 1)
+"#,
+            &temp_dir,
+        );
+
+        executor.run_all(&["setup".to_string()], &[]).await.unwrap();
+        let steps = fs::read_to_string(temp_dir.path().join("steps.txt")).unwrap();
+        assert_eq!(steps.lines().count(), 2);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_deferred_commands_last_to_first_after_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  release:
+    cmds:
+      - defer: echo first >> cleanup.txt
+      - defer:
+          task: unlock
+          vars:
+            NAME: release
+      - echo work >> cleanup.txt
+      - exit 3
+      - echo unreachable >> cleanup.txt
+  unlock:
+    cmds:
+      - echo unlock {{.NAME}} >> cleanup.txt
+"#,
+            &temp_dir,
+        );
+
+        let err = executor
+            .run_all(&["release".to_string()], &[])
+            .await
+            .unwrap_err();
+        assert_eq!(err.exit_code(), 3);
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("cleanup.txt")).unwrap(),
+            "work\nunlock release\nfirst\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_interrupt_forwards_signal_and_runs_deferred_commands() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  serve:
+    cmds:
+      - defer: touch cleaned
+      - trap 'touch terminated; exit 1' TERM; sleep 5 & wait
+      - touch continued
+"#,
+            &temp_dir,
+        );
+
+        let run = {
+            let executor = executor.clone();
+            tokio::spawn(async move { executor.run_all(&["serve".to_string()], &[]).await })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        executor.interrupt(Signal::Terminate);
+
+        assert!(run.await.unwrap().is_err());
+        assert!(temp_dir.path().join("terminated").exists());
+        assert!(temp_dir.path().join("cleaned").exists());
+        assert!(!temp_dir.path().join("continued").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_writes_task_log() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_dir = temp_dir.path().join("logs");
+        let config: RazdfileConfig = serde_yaml::from_str(
+            r#"
+tasks:
+  build:
+    cmds:
+      - echo compiled; echo careful >&2
+      - task: notify
+  notify:
+    cmds:
+      - exit 2
+"#,
+        )
+        .unwrap();
+        let executor = Executor::new(
+            Workflow {
+                config,
+                source: None,
+            },
+            temp_dir.path().to_path_buf(),
+            ExecutorOptions {
+                log_dir: Some(log_dir.clone()),
+                ..Default::default()
+            },
+        );
+
+        assert!(executor.run_all(&["build".to_string()], &[]).await.is_err());
+
+        let build_runs = logs::list_runs(&log_dir, "build").unwrap();
+        assert_eq!(build_runs.len(), 1);
+        let build_log = fs::read_to_string(&build_runs[0]).unwrap();
+        assert!(build_log.contains("[cmd] echo compiled; echo careful >&2\n"));
+        assert!(build_log.contains("[out] compiled\n"));
+        assert!(build_log.contains("[err] careful\n"));
+        assert!(build_log.contains("[exit] 0\n"));
+
+        let notify_runs = logs::list_runs(&log_dir, "notify").unwrap();
+        let notify_log = fs::read_to_string(&notify_runs[0]).unwrap();
+        assert!(notify_log.contains("[exit] 2\n"));
+    }
+
+    #[tokio::test]
+    async fn test_run_detects_task_ref_cycle() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  a:
+    cmds:
+      - task: b
+  b:
+    cmds:
+      - task: a
+"#,
+            &temp_dir,
+        );
+
+        let err = executor
+            .run_all(&["a".to_string()], &[])
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("a -> b -> a"));
+    }
+
+    #[tokio::test]
+    async fn test_run_unknown_task_ref() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = executor_for(
+            r#"
+tasks:
+  a:
+    cmds:
+      - task: missing
+"#,
+            &temp_dir,
+        );
+
+        let err = executor
+            .run_all(&["a".to_string()], &[])
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Task 'missing' referenced by 'a' not found"));
+    }
+}
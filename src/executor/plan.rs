@@ -0,0 +1,427 @@
+//! Execution plans for `--dry-run`
+//!
+//! The plan walks the task graph the same way a run does (platform filters,
+//! dependencies, task references, vars and env after templating, up-to-date
+//! checks) but only records the commands instead of spawning them.
+
+use super::vars;
+use super::{fingerprint, graph, invocations, platform, template, Executor};
+use crate::config::{Command, Deferred};
+use crate::core::events::{self, Event};
+use crate::core::Result;
+use colored::*;
+use serde::Serialize;
+use serde_yaml::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// One entry of an execution plan, in the order a sequential run would reach it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum PlanStep {
+    /// A task starts running its commands
+    /// `resumed` marks a task continuing after a `task:` reference it called
+    Task {
+        name: String,
+        dir: PathBuf,
+        vars: Vec<(String, String)>,
+        env: Vec<(String, String)>,
+        /// Precondition and `status` commands, which a dry run does not evaluate
+        preconditions: Vec<String>,
+        status: Vec<String>,
+        resumed: bool,
+    },
+    /// A shell command line after templating
+    /// `deferred` marks a `defer:` command, run once the task's other commands are done
+    Command {
+        task: String,
+        line: String,
+        ignore_error: bool,
+        deferred: bool,
+    },
+    /// A task that would not run its commands, and why
+    Skipped { name: String, reason: String },
+}
+
+/// Walks the task graph while collecting plan steps
+struct Planner<'a> {
+    executor: &'a Executor,
+    steps: Vec<PlanStep>,
+    /// Dependency invocations already planned; like a run, each runs only once
+    deps_seen: HashSet<String>,
+}
+
+impl Executor {
+    /// Resolve everything `run_all` would execute without spawning anything
+    /// Dependencies are listed before the task that needs them in declaration
+    /// order, although a real run starts them concurrently.
+    pub fn plan(&self, task_names: &[String], cli_args: &[String]) -> Result<Vec<PlanStep>> {
+        for name in task_names {
+            graph::validate(self.workflow(), name)?;
+        }
+
+        let mut planner = Planner {
+            executor: self,
+            steps: Vec::new(),
+            deps_seen: HashSet::new(),
+        };
+        for (name, vars) in invocations(task_names, cli_args) {
+            planner.plan_task(&name, vars.as_ref())?;
+        }
+        Ok(planner.steps)
+    }
+}
+
+impl Planner<'_> {
+    fn plan_task(&mut self, name: &str, call_vars: Option<&HashMap<String, Value>>) -> Result<()> {
+        let executor = self.executor;
+        let workflow = executor.workflow();
+        let task = workflow
+            .config
+            .tasks
+            .get(name)
+            .ok_or_else(|| graph::task_not_found(workflow, name, None))?;
+
+        if !platform::matches_current(task.platforms.as_ref()) {
+            self.steps.push(PlanStep::Skipped {
+                name: name.to_string(),
+                reason: format!("not available on {}", platform::current_os()),
+            });
+            return Ok(());
+        }
+
+        // `sh:` vars are shown as `$(command)` since a dry run must not spawn anything
+        let vars = executor.described_task_vars(task, call_vars);
+        let dir = executor.task_dir(task, &vars);
+        let env = executor.task_env(task, &vars, &dir)?;
+
+        for dep in task.deps.iter().flatten() {
+            let dep_name = graph::dependency_name(dep);
+            let dep_vars = vars::render_call_vars(graph::dependency_vars(dep), &vars);
+            if self
+                .deps_seen
+                .insert(graph::invocation_key(dep_name, dep_vars.as_ref()))
+            {
+                self.plan_task(dep_name, dep_vars.as_ref())?;
+            }
+        }
+
+        // `status` commands are not run, so only tasks without them can be known to be up to date
+        if !executor.inner.options.force
+            && task.status.is_none()
+            && fingerprint::is_up_to_date(&dir, name, task)?
+        {
+            self.steps.push(PlanStep::Skipped {
+                name: name.to_string(),
+                reason: "up to date".to_string(),
+            });
+            return Ok(());
+        }
+
+        // Only show the vars set on this task or by its caller; global vars are the same everywhere
+        let mut declared: Vec<(String, String)> = Vec::new();
+        let declared_keys = task
+            .vars
+            .iter()
+            .flat_map(|vars| vars.keys())
+            .chain(call_vars.into_iter().flat_map(|vars| vars.keys()));
+        for key in declared_keys {
+            if declared.iter().any(|(existing, _)| existing == key) {
+                continue;
+            }
+            if let Some(value) = vars.get(key) {
+                declared.push((key.clone(), value.clone()));
+            }
+        }
+
+        let preconditions: Vec<String> = task
+            .preconditions
+            .iter()
+            .flatten()
+            .map(|precondition| template::render(precondition.command(), &vars))
+            .collect();
+        let status: Vec<String> = task
+            .status
+            .iter()
+            .flatten()
+            .map(|line| template::render(line, &vars))
+            .collect();
+        let header = |resumed| PlanStep::Task {
+            name: name.to_string(),
+            dir: dir.clone(),
+            vars: declared.clone(),
+            env: env.clone(),
+            preconditions: preconditions.clone(),
+            status: status.clone(),
+            resumed,
+        };
+        self.steps.push(header(false));
+
+        let mut resume = false;
+        let mut deferred = Vec::new();
+        for cmd in &task.cmds {
+            let (line, ignore_error) = match cmd {
+                Command::String(line) => (line, false),
+                Command::TaskRef {
+                    task: ref_name,
+                    vars: ref_vars,
+                    ..
+                } => {
+                    let call_vars = vars::render_call_vars(ref_vars.as_ref(), &vars);
+                    self.plan_task(ref_name, call_vars.as_ref())?;
+                    resume = true;
+                    continue;
+                }
+                Command::Complex {
+                    cmd,
+                    platforms,
+                    ignore_error,
+                    ..
+                } => {
+                    if !platform::matches_current(platforms.as_ref()) {
+                        continue;
+                    }
+                    (cmd, ignore_error.unwrap_or(false))
+                }
+                Command::Defer { defer } => {
+                    deferred.push(defer);
+                    continue;
+                }
+            };
+
+            if resume {
+                self.steps.push(header(true));
+                resume = false;
+            }
+            self.steps.push(PlanStep::Command {
+                task: name.to_string(),
+                line: template::render(line, &vars),
+                ignore_error,
+                deferred: false,
+            });
+        }
+
+        // Like a run, deferred commands come last, the last one declared first
+        for defer in deferred.into_iter().rev() {
+            match defer {
+                Deferred::String(line) => {
+                    if resume {
+                        self.steps.push(header(true));
+                        resume = false;
+                    }
+                    self.steps.push(PlanStep::Command {
+                        task: name.to_string(),
+                        line: template::render(line, &vars),
+                        ignore_error: false,
+                        deferred: true,
+                    });
+                }
+                Deferred::TaskRef {
+                    task: ref_name,
+                    vars: ref_vars,
+                    ..
+                } => {
+                    let call_vars = vars::render_call_vars(ref_vars.as_ref(), &vars);
+                    self.plan_task(ref_name, call_vars.as_ref())?;
+                    resume = true;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Print a plan; directories inside `working_dir` are shown relative to it
+/// With `--format json` the plan is a single `plan` event instead
+pub fn print_plan(steps: &[PlanStep], working_dir: &Path) {
+    if events::enabled() {
+        events::emit(Event::Plan {
+            steps: steps.to_vec(),
+        });
+        return;
+    }
+    if steps.is_empty() {
+        println!("  (nothing to run)");
+        return;
+    }
+
+    for step in steps {
+        match step {
+            PlanStep::Task {
+                name,
+                dir,
+                vars,
+                env,
+                preconditions,
+                status,
+                resumed,
+            } => {
+                if *resumed {
+                    println!(
+                        "{} {}",
+                        "task:".green(),
+                        format!("[{}] (continued)", name).green()
+                    );
+                    continue;
+                }
+                println!(
+                    "{} {}",
+                    "task:".green(),
+                    format!("[{}]", name).green().bold()
+                );
+                if dir != working_dir {
+                    let shown = dir.strip_prefix(working_dir).unwrap_or(dir);
+                    println!("    {} {}", "dir:".dimmed(), shown.display());
+                }
+                for (key, value) in vars {
+                    println!("    {} {}={}", "var:".dimmed(), key, value);
+                }
+                for (key, value) in env {
+                    println!("    {} {}={}", "env:".dimmed(), key, value);
+                }
+                for line in preconditions {
+                    println!("    {} {}", "requires:".dimmed(), line);
+                }
+                for line in status {
+                    println!("    {} {}", "skipped if:".dimmed(), line);
+                }
+            }
+            PlanStep::Command {
+                line,
+                ignore_error,
+                deferred,
+                ..
+            } => {
+                let mut lines = line.lines();
+                let first = lines.next().unwrap_or_default();
+                let note = if *ignore_error {
+                    " (errors ignored)".dimmed().to_string()
+                } else if *deferred {
+                    " (deferred)".dimmed().to_string()
+                } else {
+                    String::new()
+                };
+                println!("    $ {}{}", first, note);
+                for rest in lines {
+                    println!("      {}", rest);
+                }
+            }
+            PlanStep::Skipped { name, reason } => {
+                println!(
+                    "{} {}",
+                    "task:".yellow(),
+                    format!("[{}] skipped: {}", name, reason).yellow()
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{RazdfileConfig, Workflow};
+    use crate::executor::ExecutorOptions;
+    use tempfile::TempDir;
+
+    fn plan_for(yaml: &str, dir: &TempDir, tasks: &[&str], cli_args: &[&str]) -> Vec<PlanStep> {
+        let config: RazdfileConfig = serde_yaml::from_str(yaml).unwrap();
+        let workflow = Workflow {
+            config,
+            source: None,
+        };
+        let tasks: Vec<String> = tasks.iter().map(|t| t.to_string()).collect();
+        let cli_args: Vec<String> = cli_args.iter().map(|a| a.to_string()).collect();
+        Executor::new(
+            workflow,
+            dir.path().to_path_buf(),
+            ExecutorOptions::default(),
+        )
+        .plan(&tasks, &cli_args)
+        .unwrap()
+    }
+
+    fn commands(steps: &[PlanStep]) -> Vec<(&str, &str)> {
+        steps
+            .iter()
+            .filter_map(|step| match step {
+                PlanStep::Command { task, line, .. } => Some((task.as_str(), line.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_orders_deps_refs_and_renders_templates() {
+        let temp_dir = TempDir::new().unwrap();
+        let steps = plan_for(
+            r#"
+vars:
+  TARGET: app
+tasks:
+  setup:
+    cmds: [echo setup]
+  build:
+    deps: [setup]
+    env:
+      OUT: "dist/{{.TARGET}}"
+    cmds:
+      - echo build {{.TARGET}} {{.CLI_ARGS}}
+      - task: notify
+        vars: {MSG: built}
+      - touch done
+  notify:
+    deps: [setup]
+    cmds: ["echo {{.MSG}}"]
+"#,
+            &temp_dir,
+            &["build"],
+            &["--release"],
+        );
+
+        assert_eq!(
+            commands(&steps),
+            vec![
+                ("setup", "echo setup"),
+                ("build", "echo build app --release"),
+                ("notify", "echo built"),
+                ("build", "touch done"),
+            ]
+        );
+        assert!(steps.contains(&PlanStep::Task {
+            name: "build".to_string(),
+            dir: temp_dir.path().to_path_buf(),
+            vars: vec![("CLI_ARGS".to_string(), "--release".to_string())],
+            env: vec![("OUT".to_string(), "dist/app".to_string())],
+            preconditions: Vec::new(),
+            status: Vec::new(),
+            resumed: true,
+        }));
+        assert!(!temp_dir.path().join("done").exists());
+    }
+
+    #[test]
+    fn test_plan_reports_skipped_tasks() {
+        let temp_dir = TempDir::new().unwrap();
+        let steps = plan_for(
+            r#"
+tasks:
+  other-os:
+    platforms: [plan9]
+    cmds: [echo never]
+"#,
+            &temp_dir,
+            &["other-os"],
+            &[],
+        );
+
+        assert_eq!(
+            steps,
+            vec![PlanStep::Skipped {
+                name: "other-os".to_string(),
+                reason: format!("not available on {}", platform::current_os()),
+            }]
+        );
+    }
+}
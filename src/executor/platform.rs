@@ -0,0 +1,77 @@
+//! Platform filters for tasks and commands
+//!
+//! Razdfile uses Taskfile's platform names (`linux`, `darwin`, `windows`,
+//! optionally suffixed with an architecture such as `linux/amd64`).
+
+/// Current operating system using Taskfile (GOOS) naming
+pub fn current_os() -> &'static str {
+    match std::env::consts::OS {
+        "macos" => "darwin",
+        other => other,
+    }
+}
+
+/// Current architecture using Taskfile (GOARCH) naming
+pub fn current_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "x86" => "386",
+        "aarch64" => "arm64",
+        other => other,
+    }
+}
+
+/// Check whether a single platform entry matches the given os/arch
+fn entry_matches(entry: &str, os: &str, arch: &str) -> bool {
+    match entry.split_once('/') {
+        Some((entry_os, entry_arch)) => entry_os == os && entry_arch == arch,
+        None => entry == os || entry == arch,
+    }
+}
+
+/// Check whether a platforms filter allows running on the current machine
+/// An absent or empty filter always matches
+pub fn matches_current(platforms: Option<&Vec<String>>) -> bool {
+    match platforms {
+        Some(list) if !list.is_empty() => list
+            .iter()
+            .any(|entry| entry_matches(entry.trim(), current_os(), current_arch())),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_matches_os_only() {
+        assert!(entry_matches("linux", "linux", "amd64"));
+        assert!(!entry_matches("windows", "linux", "amd64"));
+    }
+
+    #[test]
+    fn test_entry_matches_arch_only() {
+        assert!(entry_matches("arm64", "darwin", "arm64"));
+        assert!(!entry_matches("arm64", "darwin", "amd64"));
+    }
+
+    #[test]
+    fn test_entry_matches_os_and_arch() {
+        assert!(entry_matches("linux/amd64", "linux", "amd64"));
+        assert!(!entry_matches("linux/arm64", "linux", "amd64"));
+        assert!(!entry_matches("darwin/amd64", "linux", "amd64"));
+    }
+
+    #[test]
+    fn test_matches_current_without_filter() {
+        assert!(matches_current(None));
+        assert!(matches_current(Some(&vec![])));
+    }
+
+    #[test]
+    fn test_matches_current_with_filter() {
+        assert!(matches_current(Some(&vec![current_os().to_string()])));
+        assert!(!matches_current(Some(&vec!["plan9".to_string()])));
+    }
+}
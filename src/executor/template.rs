@@ -0,0 +1,119 @@
+//! Minimal `{{.VAR}}` placeholder rendering for task commands
+
+use serde_yaml::Value;
+use std::collections::HashMap;
+
+/// Convert a scalar YAML value to the string used in commands and environment
+/// Returns None for mappings and sequences
+pub fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Null => Some(String::new()),
+        _ => None,
+    }
+}
+
+/// Render `{{.NAME}}` placeholders using the given variables
+/// Unknown variables render as an empty string, matching Taskfile behavior
+pub fn render(input: &str, vars: &HashMap<String, String>) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+
+        let Some(end) = after_open.find("}}") else {
+            // Unterminated placeholder: keep the remainder verbatim
+            output.push_str(&rest[start..]);
+            return output;
+        };
+
+        let expr = after_open[..end].trim();
+        match expr.strip_prefix('.') {
+            Some(name) if is_identifier(name) => {
+                if let Some(value) = vars.get(name) {
+                    output.push_str(value);
+                }
+            }
+            _ => {
+                // Not a simple variable reference: leave it untouched
+                output.push_str(&rest[start..start + 2 + end + 2]);
+            }
+        }
+
+        rest = &after_open[end + 2..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Check that a name is a plain variable identifier
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_render_simple_variable() {
+        let rendered = render("echo {{.NAME}}", &vars(&[("NAME", "razd")]));
+        assert_eq!(rendered, "echo razd");
+    }
+
+    #[test]
+    fn test_render_with_whitespace() {
+        let rendered = render("echo {{ .NAME }}!", &vars(&[("NAME", "razd")]));
+        assert_eq!(rendered, "echo razd!");
+    }
+
+    #[test]
+    fn test_render_unknown_variable_is_empty() {
+        let rendered = render("echo [{{.MISSING}}]", &vars(&[]));
+        assert_eq!(rendered, "echo []");
+    }
+
+    #[test]
+    fn test_render_keeps_non_variable_expressions() {
+        let rendered = render("echo {{if .X}}yes{{end}}", &vars(&[("X", "1")]));
+        assert_eq!(rendered, "echo {{if .X}}yes{{end}}");
+    }
+
+    #[test]
+    fn test_render_unterminated_placeholder() {
+        let rendered = render("echo {{.NAME", &vars(&[("NAME", "razd")]));
+        assert_eq!(rendered, "echo {{.NAME");
+    }
+
+    #[test]
+    fn test_value_to_string() {
+        assert_eq!(
+            value_to_string(&Value::String("a".to_string())),
+            Some("a".to_string())
+        );
+        assert_eq!(
+            value_to_string(&Value::Bool(true)),
+            Some("true".to_string())
+        );
+        assert_eq!(
+            value_to_string(&serde_yaml::from_str("42").unwrap()),
+            Some("42".to_string())
+        );
+        assert_eq!(
+            value_to_string(&serde_yaml::from_str("{a: 1}").unwrap()),
+            None
+        );
+    }
+}
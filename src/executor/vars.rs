@@ -0,0 +1,278 @@
+//! Task variable resolution
+//!
+//! Sources are applied in a fixed precedence order, from highest to lowest:
+//! CLI `KEY=value` arguments, vars passed by the caller (`task:` references and
+//! `deps`), task vars, global vars, and finally the process environment.
+//! A value may refer to any variable of a lower layer or declared before it.
+
+use super::template;
+use crate::core::{RazdError, Result};
+use crate::integrations::{process, tools};
+use indexmap::IndexMap;
+use serde_yaml::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+
+/// Directory and command of a `sh:` var
+type ShellVar = (PathBuf, String);
+
+/// Output of `sh:` vars, so every command runs at most once per run
+#[derive(Default)]
+pub struct ShellVarCache {
+    outputs: Mutex<HashMap<ShellVar, Arc<OnceCell<String>>>>,
+}
+
+impl ShellVarCache {
+    /// Run a dynamic var command in `dir`, or return its cached output
+    /// The output is trimmed of trailing newlines like `$(...)` in a shell.
+    /// Tasks evaluating the same command wait for the first one; others run in parallel.
+    pub async fn evaluate(&self, name: &str, command: &str, dir: &Path) -> Result<String> {
+        let cell = self
+            .outputs
+            .lock()
+            .map_err(|_| RazdError::task("Dynamic variable cache is poisoned"))?
+            .entry((dir.to_path_buf(), command.to_string()))
+            .or_default()
+            .clone();
+        cell.get_or_try_init(|| run_shell_var(name, command, dir))
+            .await
+            .cloned()
+    }
+}
+
+/// Run the command of a `sh:` var and return its output
+/// The command is killed when the evaluation is abandoned, e.g. on a stop or timeout.
+async fn run_shell_var(name: &str, command: &str, dir: &Path) -> Result<String> {
+    let (program, args) = process::shell_invocation(command);
+    process::log_spawn(program, &args, Some(dir), &[]);
+    let output = tokio::process::Command::new(tools::program_path(program))
+        .args(&args)
+        .current_dir(dir)
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::inherit())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| RazdError::task(format!("Failed to evaluate variable '{}': {}", name, e)))?;
+    if !output.status.success() {
+        let code = output
+            .status
+            .code()
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "signal".to_string());
+        return Err(RazdError::task(format!(
+            "Variable '{}' failed: `{}` exited with code {}",
+            name, command, code
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches(['\n', '\r'])
+        .to_string())
+}
+
+/// The declared layers of variables for one task invocation
+pub struct VarLayers<'a> {
+    pub global: Option<&'a IndexMap<String, Value>>,
+    pub task: Option<&'a IndexMap<String, Value>>,
+    pub call: Option<&'a HashMap<String, Value>>,
+    pub cli: &'a [(String, String)],
+}
+
+impl VarLayers<'_> {
+    /// The process environment, `CLI_ARGS` and the CLI vars, which no declared var overrides
+    /// Environment variables that are not valid UTF-8 are left out.
+    fn fixed(&self) -> HashMap<String, String> {
+        let mut vars: HashMap<String, String> = std::env::vars_os()
+            .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+            .collect();
+        vars.insert("CLI_ARGS".to_string(), String::new());
+        vars.extend(self.cli.iter().cloned());
+        vars
+    }
+
+    /// Declared vars in the order they are resolved, without those a higher layer sets
+    /// Caller vars come first; global and task vars then refer to them.
+    fn declared(&self) -> Vec<(&String, &Value)> {
+        let cli: HashSet<&String> = self.cli.iter().map(|(key, _)| key).collect();
+        let call = self.call.into_iter().flatten();
+        let global = self.global.into_iter().flatten();
+        let task = self.task.into_iter().flatten();
+        call.filter(|(key, _)| !cli.contains(key))
+            .chain(global.chain(task).filter(|(key, _)| {
+                !cli.contains(key) && !self.call.is_some_and(|call| call.contains_key(*key))
+            }))
+            .collect()
+    }
+}
+
+/// Resolve every variable visible to a task invocation without running anything
+/// `sh:` vars show up as `$(command)`, for dry runs and listings.
+pub fn describe(layers: &VarLayers) -> HashMap<String, String> {
+    let mut vars = layers.fixed();
+    for (key, value) in layers.declared() {
+        let resolved = match shell_command(value, &vars) {
+            Some(command) => Some(format!("$({})", command)),
+            None => render_value(value, &vars),
+        };
+        if let Some(resolved) = resolved {
+            vars.insert(key.clone(), resolved);
+        }
+    }
+    vars
+}
+
+/// Resolve every variable visible to a task invocation, running `sh:` vars in `dir`
+/// once per run
+pub async fn evaluate(
+    layers: &VarLayers<'_>,
+    cache: &ShellVarCache,
+    dir: &Path,
+) -> Result<HashMap<String, String>> {
+    let mut vars = layers.fixed();
+    for (key, value) in layers.declared() {
+        let resolved = match shell_command(value, &vars) {
+            Some(command) => Some(cache.evaluate(key, &command, dir).await?),
+            None => render_value(value, &vars),
+        };
+        if let Some(resolved) = resolved {
+            vars.insert(key.clone(), resolved);
+        }
+    }
+    Ok(vars)
+}
+
+/// Render the vars a task passes to another task in the caller's context
+/// `sh:` vars are passed on unevaluated and run in the context of the callee
+pub fn render_call_vars(
+    call: Option<&HashMap<String, Value>>,
+    vars: &HashMap<String, String>,
+) -> Option<HashMap<String, Value>> {
+    call.map(|call| {
+        call.iter()
+            .map(|(key, value)| {
+                let rendered = match template::value_to_string(value) {
+                    Some(raw) => Value::String(template::render(&raw, vars)),
+                    None => value.clone(),
+                };
+                (key.clone(), rendered)
+            })
+            .collect()
+    })
+}
+
+/// Parse a `KEY=value` command line argument into a variable
+/// Returns None for anything else, such as task names
+pub fn parse_cli_var(arg: &str) -> Option<(String, String)> {
+    let (key, value) = arg.split_once('=')?;
+    let mut chars = key.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    if !valid_start || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+    Some((key.to_string(), value.to_string()))
+}
+
+/// Render a plain declared value; None for mappings
+fn render_value(value: &Value, vars: &HashMap<String, String>) -> Option<String> {
+    template::value_to_string(value).map(|raw| template::render(&raw, vars))
+}
+
+/// Rendered command of a `sh:` var; None for anything else
+fn shell_command(value: &Value, vars: &HashMap<String, String>) -> Option<String> {
+    let command = value.get("sh").and_then(Value::as_str)?;
+    Some(template::render(command, vars))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn map(yaml: &str) -> IndexMap<String, Value> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_precedence() {
+        let global = map("NAME: global\nGREETING: 'hello {{.NAME}}'\nLEVEL: global");
+        let task = map("NAME: task\nLEVEL: task");
+        let call: HashMap<String, Value> = serde_yaml::from_str("LEVEL: call").unwrap();
+        let cli = vec![("NAME".to_string(), "cli".to_string())];
+
+        let vars = describe(&VarLayers {
+            global: Some(&global),
+            task: Some(&task),
+            call: Some(&call),
+            cli: &cli,
+        });
+
+        assert_eq!(vars.get("NAME").unwrap(), "cli");
+        assert_eq!(vars.get("GREETING").unwrap(), "hello cli");
+        assert_eq!(vars.get("LEVEL").unwrap(), "call");
+        assert_eq!(vars.get("CLI_ARGS").unwrap(), "");
+    }
+
+    #[test]
+    fn test_resolve_falls_back_to_environment() {
+        std::env::set_var("RAZD_TEST_VARS_FALLBACK", "from-env");
+        std::env::set_var("RAZD_TEST_VARS_SHADOWED", "from-env");
+        let global = map("RAZD_TEST_VARS_SHADOWED: declared\nUSES: '{{.RAZD_TEST_VARS_FALLBACK}}'");
+
+        let vars = describe(&VarLayers {
+            global: Some(&global),
+            task: None,
+            call: None,
+            cli: &[],
+        });
+
+        assert_eq!(vars.get("USES").unwrap(), "from-env");
+        assert_eq!(vars.get("RAZD_TEST_VARS_SHADOWED").unwrap(), "declared");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_sh_vars_are_evaluated_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ShellVarCache::default();
+        let global = map("COUNT: {sh: 'echo x >> calls.txt; wc -l < calls.txt | tr -d \" \"'}");
+        let layers = VarLayers {
+            global: Some(&global),
+            task: None,
+            call: None,
+            cli: &[],
+        };
+
+        let (first, second) = tokio::join!(
+            evaluate(&layers, &cache, temp_dir.path()),
+            evaluate(&layers, &cache, temp_dir.path())
+        );
+        assert_eq!(first.unwrap().get("COUNT").unwrap(), "1");
+        assert_eq!(second.unwrap().get("COUNT").unwrap(), "1");
+        let third = evaluate(&layers, &cache, temp_dir.path()).await.unwrap();
+        assert_eq!(third.get("COUNT").unwrap(), "1");
+
+        let described = describe(&layers);
+        assert!(described.get("COUNT").unwrap().starts_with("$(echo x"));
+    }
+
+    #[test]
+    fn test_parse_cli_var() {
+        assert_eq!(
+            parse_cli_var("VERSION=1.2.3"),
+            Some(("VERSION".to_string(), "1.2.3".to_string()))
+        );
+        assert_eq!(
+            parse_cli_var("_X=a=b"),
+            Some(("_X".to_string(), "a=b".to_string()))
+        );
+        assert_eq!(parse_cli_var("build"), None);
+        assert_eq!(parse_cli_var("api:build"), None);
+        assert_eq!(parse_cli_var("1X=2"), None);
+        assert_eq!(parse_cli_var("--flag=1"), None);
+    }
+}
@@ -0,0 +1,340 @@
+//! Watch mode: rerun tasks when their input files change
+//!
+//! Changes are detected by polling file modification times and sizes, so watch
+//! mode works the same on every platform and inside containers where inotify
+//! is limited or unavailable.
+
+use super::{graph, Executor, ExecutorOptions};
+use crate::config::Workflow;
+use crate::core::signals::{self, Signal};
+use crate::core::{output, RazdError, Result};
+use crate::defaults;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+
+/// Modification time and size of every watched file
+type Snapshot = BTreeMap<PathBuf, (Option<SystemTime>, u64)>;
+
+/// Polls a set of files for changes
+pub struct FileWatcher {
+    root: PathBuf,
+    /// Absolute glob patterns; None watches the whole project directory
+    patterns: Option<Vec<String>>,
+    /// `.gitignore` patterns, matched against paths relative to `root`
+    ignored: Vec<glob::Pattern>,
+    snapshot: Snapshot,
+    interval: Duration,
+    debounce: Duration,
+}
+
+impl FileWatcher {
+    /// Start watching `patterns`, or every file below `root` when there are none
+    pub fn new(root: &Path, patterns: Option<Vec<String>>) -> Result<Self> {
+        let mut watcher = Self {
+            root: root.to_path_buf(),
+            patterns,
+            ignored: load_gitignore(root),
+            snapshot: Snapshot::new(),
+            interval: Duration::from_millis(defaults::DEFAULT_WATCH_INTERVAL_MS),
+            debounce: Duration::from_millis(defaults::DEFAULT_WATCH_DEBOUNCE_MS),
+        };
+        watcher.snapshot = watcher.scan()?;
+        Ok(watcher)
+    }
+
+    /// Number of files currently watched
+    pub fn file_count(&self) -> usize {
+        self.snapshot.len()
+    }
+
+    /// Take a snapshot of the watched files
+    fn scan(&self) -> Result<Snapshot> {
+        let mut files = Vec::new();
+        match self.patterns {
+            Some(ref patterns) => {
+                for pattern in patterns {
+                    let paths = glob::glob(pattern).map_err(|e| {
+                        RazdError::task(format!("Invalid glob pattern '{}': {}", pattern, e))
+                    })?;
+                    files.extend(paths.filter_map(|entry| entry.ok()));
+                }
+            }
+            None => self.walk(&self.root, &mut files),
+        }
+
+        Ok(files
+            .into_iter()
+            .filter_map(|path| {
+                let metadata = fs::metadata(&path).ok()?;
+                metadata
+                    .is_file()
+                    .then(|| (path, (metadata.modified().ok(), metadata.len())))
+            })
+            .collect())
+    }
+
+    /// Collect files below `dir`, skipping `.git` and ignored paths
+    fn walk(&self, dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if entry.file_name() == ".git" || self.is_ignored(&path) {
+                continue;
+            }
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => self.walk(&path, files),
+                Ok(_) => files.push(path),
+                Err(_) => {}
+            }
+        }
+    }
+
+    /// Check a path against the `.gitignore` patterns
+    /// A pattern matches the relative path or any single component of it
+    fn is_ignored(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        self.ignored.iter().any(|pattern| {
+            pattern.matches_path(relative)
+                || relative
+                    .components()
+                    .any(|c| pattern.matches(&c.as_os_str().to_string_lossy()))
+        })
+    }
+
+    /// Wait until the watched files change and then stay unchanged for the debounce period
+    pub async fn wait_for_change(&mut self) -> Result<()> {
+        loop {
+            tokio::time::sleep(self.interval).await;
+            let mut current = self.scan()?;
+            if current == self.snapshot {
+                continue;
+            }
+
+            // Let bursts of writes (formatters, git checkouts) settle first
+            loop {
+                tokio::time::sleep(self.debounce).await;
+                let next = self.scan()?;
+                if next == current {
+                    break;
+                }
+                current = next;
+            }
+
+            self.snapshot = current;
+            return Ok(());
+        }
+    }
+}
+
+/// Read `.gitignore` in `root` into glob patterns
+/// Negations and comments are skipped; leading and trailing slashes are ignored
+fn load_gitignore(root: &Path) -> Vec<glob::Pattern> {
+    let Ok(content) = fs::read_to_string(root.join(".gitignore")) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('!'))
+        .filter_map(|line| glob::Pattern::new(line.trim_matches('/')).ok())
+        .collect()
+}
+
+/// Absolute glob patterns of the `sources` of every task reachable from `task_names`
+/// Returns None when no task declares sources
+pub fn watch_patterns(
+    workflow: &Workflow,
+    working_dir: &Path,
+    task_names: &[String],
+) -> Option<Vec<String>> {
+    let patterns: Vec<String> = graph::reachable(workflow, task_names)
+        .into_iter()
+        .filter_map(|name| workflow.config.tasks.get(name))
+        .flat_map(|task| {
+            let dir = task.resolve_dir(working_dir);
+            let prefix = glob::Pattern::escape(&dir.to_string_lossy());
+            task.sources
+                .iter()
+                .flatten()
+                .map(move |source| format!("{}/{}", prefix, source))
+        })
+        .collect();
+
+    (!patterns.is_empty()).then_some(patterns)
+}
+
+/// Run tasks and rerun them whenever their inputs change, until Ctrl-C
+/// A change while the tasks are still running stops their commands and restarts them
+pub async fn run_watch(
+    workflow: Workflow,
+    working_dir: PathBuf,
+    options: ExecutorOptions,
+    task_names: Vec<String>,
+    cli_args: Vec<String>,
+) -> Result<()> {
+    for name in &task_names {
+        graph::validate(&workflow, name)?;
+    }
+
+    let patterns = watch_patterns(&workflow, &working_dir, &task_names);
+    let watching_sources = patterns.is_some();
+    let mut watcher = FileWatcher::new(&working_dir, patterns)?;
+    output::info(&format!(
+        "Watching {} {} for changes (Ctrl-C to stop)",
+        watcher.file_count(),
+        if watching_sources {
+            "source files"
+        } else {
+            "project files"
+        }
+    ));
+
+    let options = ExecutorOptions {
+        process_groups: true,
+        ..options
+    };
+
+    let mut signals = signals::listen();
+    loop {
+        let executor = Executor::new(workflow.clone(), working_dir.clone(), options.clone());
+        let mut run = {
+            let executor = executor.clone();
+            let task_names = task_names.clone();
+            let cli_args = cli_args.clone();
+            tokio::spawn(async move { executor.run_all(&task_names, &cli_args).await })
+        };
+
+        tokio::select! {
+            joined = &mut run => {
+                report_run(joined);
+                executor.write_report();
+                output::info("Waiting for changes...");
+                tokio::select! {
+                    changed = watcher.wait_for_change() => changed?,
+                    signal = signals.next() => return Err(RazdError::interrupted(signal)),
+                }
+            }
+            changed = watcher.wait_for_change() => {
+                changed?;
+                output::info("Change detected, stopping running commands...");
+                stop_run(&executor, &mut run, None).await;
+            }
+            signal = signals.next() => {
+                stop_run(&executor, &mut run, Some(signal)).await;
+                executor.write_report();
+                return Err(RazdError::interrupted(signal));
+            }
+        }
+
+        output::step(&format!("Restarting {}", task_names.join(", ")));
+    }
+}
+
+/// Print the outcome of one watch iteration without leaving watch mode
+fn report_run(joined: std::result::Result<Result<()>, tokio::task::JoinError>) {
+    match joined {
+        Ok(Ok(())) => output::success("Run finished"),
+        Ok(Err(e)) => output::error(&e.to_string()),
+        Err(e) => output::error(&format!("Run did not complete: {}", e)),
+    }
+}
+
+/// Stop the commands of a run and wait for it to finish, killing them after a grace period
+/// A signal razd received is passed on to the commands instead of SIGTERM
+async fn stop_run(executor: &Executor, run: &mut JoinHandle<Result<()>>, signal: Option<Signal>) {
+    match signal {
+        Some(signal) => executor.interrupt(signal),
+        None => executor.stop(false),
+    }
+    if tokio::time::timeout(executor.stop_grace(), &mut *run)
+        .await
+        .is_err()
+    {
+        executor.stop(true);
+        let _ = run.await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RazdfileConfig;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scan_skips_git_and_ignored_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("target/debug")).unwrap();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join(".git/HEAD"), "ref").unwrap();
+        fs::write(root.join("target/debug/app"), "bin").unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(root.join("debug.log"), "log").unwrap();
+        fs::write(root.join(".gitignore"), "# build output\n/target/\n*.log\n").unwrap();
+
+        let watcher = FileWatcher::new(root, None).unwrap();
+        let files: Vec<_> = watcher.snapshot.keys().cloned().collect();
+        assert_eq!(
+            files,
+            vec![root.join(".gitignore"), root.join("src/main.rs")]
+        );
+    }
+
+    #[test]
+    fn test_watch_patterns_collects_reachable_sources() {
+        let config: RazdfileConfig = serde_yaml::from_str(
+            r#"
+tasks:
+  build:
+    deps: [codegen]
+    sources: ["src/**/*.rs"]
+    cmds: []
+  codegen:
+    dir: api
+    sources: ["schema.graphql"]
+    cmds: []
+  dev:
+    cmds: []
+"#,
+        )
+        .unwrap();
+        let workflow = Workflow {
+            config,
+            source: None,
+        };
+        let root = Path::new("/project");
+
+        let patterns = watch_patterns(&workflow, root, &["build".to_string()]).unwrap();
+        assert_eq!(
+            patterns,
+            vec!["/project/src/**/*.rs", "/project/api/schema.graphql"]
+        );
+        assert!(watch_patterns(&workflow, root, &["dev".to_string()]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_change_detects_modification() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("input.txt");
+        fs::write(&file, "one").unwrap();
+
+        let mut watcher = FileWatcher::new(temp_dir.path(), None).unwrap();
+        watcher.interval = Duration::from_millis(10);
+        watcher.debounce = Duration::from_millis(10);
+
+        fs::write(&file, "changed content").unwrap();
+        tokio::time::timeout(Duration::from_secs(5), watcher.wait_for_change())
+            .await
+            .expect("change was not detected")
+            .unwrap();
+    }
+}
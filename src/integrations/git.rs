@@ -1,6 +1,10 @@
+use crate::core::events::{self, Event};
+use crate::core::retry::{retry, RetryPolicy};
+use crate::core::summary::{self, Phase};
 use crate::core::{output, RazdError, Result};
 use crate::integrations::process;
 use std::path::PathBuf;
+use std::time::Instant;
 
 /// Extract repository name from git URL
 pub fn extract_repo_name(url: &str) -> &str {
@@ -16,7 +20,7 @@ pub fn extract_repo_name(url: &str) -> &str {
 /// Clone a git repository
 pub async fn clone_repository(url: &str, target_dir: Option<&str>) -> Result<PathBuf> {
     // Check if git is available
-    if !process::check_command_available("git").await {
+    if !process::check_command_available("git") {
         return Err(RazdError::missing_tool(
             "git",
             "https://git-scm.com/downloads",
@@ -41,9 +45,29 @@ pub async fn clone_repository(url: &str, target_dir: Option<&str>) -> Result<Pat
 
     output::step(&format!("Cloning {} into {}", url, repo_name));
 
-    process::execute_command("git", &["clone", url, &repo_name], None)
-        .await
-        .map_err(|e| RazdError::git(format!("Failed to clone repository: {}", e)))?;
+    events::emit_if_enabled(|| Event::CloneStarted {
+        url: url.to_string(),
+        path: target_path.clone(),
+    });
+    let started = Instant::now();
+    let clone_args = ["clone", url, &repo_name];
+    let result = retry(RetryPolicy::from_env(), "git clone", || {
+        process::execute_command("git", &clone_args, None)
+    })
+    .await;
+    events::emit_if_enabled(|| Event::CloneFinished {
+        url: url.to_string(),
+        path: target_path.clone(),
+        status: events::status(&result),
+        duration_ms: events::millis(started.elapsed()),
+    });
+    summary::record(
+        Phase::Clone,
+        url,
+        events::status(&result),
+        started.elapsed(),
+    );
+    result.map_err(|e| RazdError::git(format!("Failed to clone repository: {}", e)))?;
 
     output::success(&format!("Successfully cloned repository to {}", repo_name));
 
@@ -1,12 +1,16 @@
-use crate::config::RazdfileConfig;
+use crate::config::{RazdfileConfig, ToolConfig};
+use crate::core::events::{self, Event};
+use crate::core::retry::{retry, RetryPolicy};
+use crate::core::summary::{self, Phase};
 use crate::core::{output, RazdError, Result};
-use crate::integrations::process;
+use crate::integrations::{process, tools};
 use std::path::Path;
+use std::time::Instant;
 
 /// Check if mise configuration exists in the directory
 pub fn has_mise_config(dir: &Path) -> bool {
     // Check for Razdfile.yml with mise section first
-    if let Ok(Some(razdfile)) = RazdfileConfig::load_from_path(dir.join("Razdfile.yml")) {
+    if let Ok(Some(razdfile)) = RazdfileConfig::load_with_includes(dir.join("Razdfile.yml")) {
         if razdfile.mise.is_some() {
             return true;
         }
@@ -18,11 +22,91 @@ pub fn has_mise_config(dir: &Path) -> bool {
         || dir.join(".tool-versions").exists()
 }
 
+/// Tools `mise install` would install, as `name@version`
+/// Reads the Razdfile `mise:` section first, then mise.toml, then .tool-versions
+pub fn planned_tools(dir: &Path) -> Vec<String> {
+    if let Ok(Some(razdfile)) = RazdfileConfig::load_with_includes(dir.join("Razdfile.yml")) {
+        if let Some(tools) = razdfile.mise.and_then(|mise| mise.tools) {
+            return tools
+                .iter()
+                .map(|(name, tool)| match tool {
+                    ToolConfig::Simple(version) | ToolConfig::Complex { version, .. } => {
+                        format!("{}@{}", name, version)
+                    }
+                })
+                .collect();
+        }
+    }
+
+    for file in ["mise.toml", ".mise.toml"] {
+        let Ok(content) = std::fs::read_to_string(dir.join(file)) else {
+            continue;
+        };
+        let Ok(doc) = content.parse::<toml::Table>() else {
+            continue;
+        };
+        if let Some(tools) = doc.get("tools").and_then(|tools| tools.as_table()) {
+            return tools
+                .iter()
+                .map(|(name, value)| {
+                    let version = match value {
+                        toml::Value::String(version) => Some(version.as_str()),
+                        toml::Value::Table(table) => {
+                            table.get("version").and_then(|version| version.as_str())
+                        }
+                        _ => None,
+                    };
+                    format!("{}@{}", name, version.unwrap_or("latest"))
+                })
+                .collect();
+        }
+    }
+
+    std::fs::read_to_string(dir.join(".tool-versions"))
+        .map(|content| {
+            content
+                .lines()
+                .map(|line| line.split('#').next().unwrap_or_default().trim())
+                .filter_map(|line| {
+                    let mut parts = line.split_whitespace();
+                    let name = parts.next()?;
+                    Some(format!("{}@{}", name, parts.next().unwrap_or("latest")))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Run a tool installation between `tool_install_started` and `tool_install_finished` events
+/// `tool` is None when every configured tool is installed
+async fn install_reporting<F, Fut>(tool: Option<&str>, install: F) -> Result<()>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<()>>,
+{
+    let tool = tool.map(String::from);
+    events::emit_if_enabled(|| Event::ToolInstallStarted { tool: tool.clone() });
+    let started = Instant::now();
+    let result = install().await;
+    summary::record(
+        Phase::ToolInstall,
+        tool.as_deref().unwrap_or("all tools"),
+        events::status(&result),
+        started.elapsed(),
+    );
+    events::emit_if_enabled(|| Event::ToolInstallFinished {
+        tool,
+        status: events::status(&result),
+        duration_ms: events::millis(started.elapsed()),
+    });
+    result
+}
+
 /// Install tools using mise
 /// Note: razd trust guard ensures mise is already trusted before this is called
 pub async fn install_tools(working_dir: &Path) -> Result<()> {
-    // Check if mise is available
-    if !process::check_command_available("mise").await {
+    // Check if mise is available (a dry run does not need it)
+    if !process::dry_run() && !process::check_command_available("mise") {
         return Err(RazdError::missing_tool(
             "mise",
             "https://mise.jdx.dev/getting-started.html",
@@ -35,13 +119,28 @@ pub async fn install_tools(working_dir: &Path) -> Result<()> {
         return Ok(());
     }
 
+    if process::dry_run() {
+        output::info(&format!(
+            "Dry run: would run `mise install` in {}",
+            working_dir.display()
+        ));
+        for tool in planned_tools(working_dir) {
+            output::info(&format!("  {}", tool));
+        }
+        return Ok(());
+    }
+
     // Note: trust_config is no longer called here - razd trust guard handles it
 
     output::step("Installing development tools with mise");
 
-    process::execute_command_interactive("mise", &["install"], Some(working_dir))
-        .await
-        .map_err(|e| RazdError::mise(format!("Failed to install tools: {}", e)))?;
+    install_reporting(None, || {
+        retry(RetryPolicy::from_env(), "mise install", || {
+            process::execute_command_interactive("mise", &["install"], Some(working_dir))
+        })
+    })
+    .await
+    .map_err(|e| RazdError::mise(format!("Failed to install tools: {}", e)))?;
 
     output::success("Successfully installed development tools");
 
@@ -51,8 +150,20 @@ pub async fn install_tools(working_dir: &Path) -> Result<()> {
 /// Install a specific tool using mise
 /// Note: razd trust guard ensures mise is already trusted before this is called
 pub async fn install_specific_tool(tool: &str, version: &str, working_dir: &Path) -> Result<()> {
+    let tool_spec = format!("{}@{}", tool, version);
+
+    // A dry run spawns nothing, so it does not need mise
+    if process::dry_run() {
+        output::info(&format!(
+            "Dry run: would run `mise install {}` in {}",
+            tool_spec,
+            working_dir.display()
+        ));
+        return Ok(());
+    }
+
     // Check if mise is available
-    if !process::check_command_available("mise").await {
+    if !process::check_command_available("mise") {
         return Err(RazdError::missing_tool(
             "mise",
             "https://mise.jdx.dev/getting-started.html",
@@ -64,18 +175,24 @@ pub async fn install_specific_tool(tool: &str, version: &str, working_dir: &Path
     output::step(&format!("Installing {} via mise...", tool));
 
     // Install the tool
-    let tool_spec = format!("{}@{}", tool, version);
     let install_args = vec!["install", &tool_spec];
 
-    process::execute_command_interactive("mise", &install_args, Some(working_dir))
-        .await
-        .map_err(|e| {
-            RazdError::mise(format!(
-                "Failed to install {}: {}\n\
+    install_reporting(Some(tool), || {
+        retry(RetryPolicy::from_env(), "mise install", || {
+            process::execute_command_interactive("mise", &install_args, Some(working_dir))
+        })
+    })
+    .await
+    .map_err(|e| {
+        RazdError::mise(format!(
+            "Failed to install {}: {}\n\
                  Please install {} manually: https://taskfile.dev/installation/",
-                tool, e, tool
-            ))
-        })?;
+            tool, e, tool
+        ))
+    })?;
+
+    // It was looked up as missing before the install
+    tools::forget(tool);
 
     // Use the tool to make it available in current environment
     output::step(&format!(
@@ -97,8 +214,16 @@ pub async fn install_specific_tool(tool: &str, version: &str, working_dir: &Path
 
 /// Ensure a tool is available, installing it via mise if necessary
 pub async fn ensure_tool_available(tool: &str, version: &str, working_dir: &Path) -> Result<()> {
+    if process::dry_run() {
+        output::info(&format!(
+            "Dry run: would install {}@{} with mise if it is missing",
+            tool, version
+        ));
+        return Ok(());
+    }
+
     // Fast path: check if tool is already available
-    if process::check_command_available(tool).await {
+    if process::check_command_available(tool) {
         return Ok(());
     }
 
@@ -132,6 +257,27 @@ mod tests {
         assert!(has_mise_config(temp_dir.path()));
     }
 
+    #[test]
+    fn test_planned_tools_from_razdfile_and_mise_toml() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("mise.toml"),
+            "[tools]\nnode = \"22\"\npython = { version = \"3.12\" }\n",
+        )
+        .unwrap();
+        assert_eq!(
+            planned_tools(temp_dir.path()),
+            vec!["node@22", "python@3.12"]
+        );
+
+        std::fs::write(
+            temp_dir.path().join("Razdfile.yml"),
+            "mise:\n  tools:\n    go: '1.23'\ntasks: {}\n",
+        )
+        .unwrap();
+        assert_eq!(planned_tools(temp_dir.path()), vec!["go@1.23"]);
+    }
+
     #[test]
     fn test_has_mise_config_with_neither() {
         let temp_dir = TempDir::new().unwrap();
@@ -2,3 +2,4 @@ pub mod git;
 pub mod mise;
 pub mod process;
 pub mod taskfile;
+pub mod tools;
@@ -1,23 +1,100 @@
+use crate::core::duration::HumanDuration;
+use crate::core::signals::{self, Signal};
+use crate::core::{events, log};
 use crate::core::{output, RazdError, Result};
+use crate::defaults;
+use crate::integrations::tools;
+use std::future::Future;
 use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio::process::Command;
 
+/// Check whether commands are only described instead of spawned
+/// Enabled with the global `--dry-run` flag (RAZD_DRY_RUN=1)
+pub fn dry_run() -> bool {
+    std::env::var("RAZD_DRY_RUN").unwrap_or_default() == "1"
+}
+
+/// Time stopped commands get between SIGTERM and SIGKILL
+/// Set with the global `--grace-period` flag (RAZD_GRACE_PERIOD)
+pub fn grace_period() -> Duration {
+    std::env::var("RAZD_GRACE_PERIOD")
+        .ok()
+        .and_then(|value| value.parse::<HumanDuration>().ok())
+        .map(HumanDuration::as_duration)
+        .unwrap_or(Duration::from_millis(defaults::DEFAULT_STOP_GRACE_MS))
+}
+
+/// Whether razd reads its input from a terminal
+pub fn stdin_is_terminal() -> bool {
+    use std::io::IsTerminal;
+    std::io::stdin().is_terminal()
+}
+
+/// Whether razd's stdout is a terminal
+pub fn stdout_is_terminal() -> bool {
+    use std::io::IsTerminal;
+    std::io::stdout().is_terminal()
+}
+
+/// Stdout of commands that write to the terminal directly
+/// With `--format json` stdout carries the event stream, so their output goes to stderr.
+pub fn terminal_stdout() -> std::process::Stdio {
+    if events::enabled() {
+        std::io::stderr().into()
+    } else {
+        std::process::Stdio::inherit()
+    }
+}
+
+/// Log the full argv and working directory of a process about to be spawned
+/// Only the names of task-specific environment variables are logged, at trace level.
+pub fn log_spawn<S: AsRef<str>>(
+    program: &str,
+    args: &[S],
+    working_dir: Option<&Path>,
+    envs: &[(String, String)],
+) {
+    log::debug("process", || {
+        let argv: Vec<&str> = std::iter::once(program)
+            .chain(args.iter().map(AsRef::as_ref))
+            .collect();
+        let cwd = working_dir
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::current_dir().ok())
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default();
+        format!("Spawning {:?} in {}", argv, cwd)
+    });
+    if !envs.is_empty() {
+        log::trace("process", || {
+            let names: Vec<&str> = envs.iter().map(|(key, _)| key.as_str()).collect();
+            format!("With environment variables {}", names.join(", "))
+        });
+    }
+}
+
 /// Spawn a command and return the child process handle
 pub async fn spawn_command(
     program: &str,
     args: &[&str],
     working_dir: Option<&Path>,
 ) -> Result<tokio::process::Child> {
-    let mut cmd = Command::new(program);
+    let mut cmd = Command::new(tools::program_path(program));
     cmd.args(args);
 
     // Inherit current environment to ensure tools are found
-    cmd.env_clear().envs(std::env::vars());
+    cmd.env_clear().envs(std::env::vars_os());
+    cmd.stdout(terminal_stdout());
 
     if let Some(dir) = working_dir {
         cmd.current_dir(dir);
     }
 
+    log_spawn(program, args, working_dir, &[]);
     cmd.spawn()
         .map_err(|e| RazdError::config(format!("Failed to spawn {}: {}", program, e)))
 }
@@ -28,30 +105,430 @@ pub fn spawn_command_interactive(
     args: &[&str],
     working_dir: Option<&Path>,
 ) -> Result<std::process::Child> {
-    let mut cmd = std::process::Command::new(program);
+    let mut cmd = std::process::Command::new(tools::program_path(program));
     cmd.args(args);
 
     // Inherit current environment and stdio for interactive execution
-    cmd.env_clear().envs(std::env::vars());
+    cmd.env_clear().envs(std::env::vars_os());
     cmd.stdin(std::process::Stdio::inherit());
-    cmd.stdout(std::process::Stdio::inherit());
+    cmd.stdout(terminal_stdout());
     cmd.stderr(std::process::Stdio::inherit());
 
     if let Some(dir) = working_dir {
         cmd.current_dir(dir);
     }
 
+    log_spawn(program, args, working_dir, &[]);
+    cmd.spawn()
+        .map_err(|e| RazdError::config(format!("Failed to spawn {}: {}", program, e)))
+}
+
+/// Build the shell program and arguments used to run a task command line
+/// Task commands were written for go-task's bash-compatible interpreter, so they run
+/// with bash, or sh where there is no bash (`set -o pipefail` and `shopt` need bash).
+/// On Windows without either, `cmd /C` is the last resort; tasks then go through
+/// go-task instead (see `taskfile::use_external_task`).
+pub fn shell_invocation(script: &str) -> (&'static str, Vec<String>) {
+    match posix_shell() {
+        Some(shell) => (shell, vec!["-c".to_string(), script.to_string()]),
+        None => ("cmd", vec!["/C".to_string(), script.to_string()]),
+    }
+}
+
+/// bash or sh, whichever is found first; on unix sh is assumed to exist
+pub fn posix_shell() -> Option<&'static str> {
+    match ["bash", "sh"]
+        .into_iter()
+        .find(|shell| tools::resolve(shell).is_some())
+    {
+        Some(shell) => Some(shell),
+        None if cfg!(windows) => None,
+        None => Some("sh"),
+    }
+}
+
+/// Start a command in its own process group so it can be stopped together with its children
+/// Only has an effect on unix
+fn isolate_process_group(cmd: &mut std::process::Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+/// Ask a process (and its process group on unix) to stop, or kill it when `force` is set
+pub fn terminate_process_group(pid: u32, force: bool) {
+    #[cfg(unix)]
+    {
+        let signal = if force { libc::SIGKILL } else { libc::SIGTERM };
+        // A negative pid addresses the whole process group led by `pid`
+        unsafe {
+            libc::kill(-(pid as libc::pid_t), signal);
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let pid = pid.to_string();
+        let mut args = vec!["/PID", pid.as_str(), "/T"];
+        if force {
+            args.push("/F");
+        }
+        let _ = std::process::Command::new("taskkill")
+            .args(&args)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status();
+    }
+}
+
+/// Stop a command started by razd: its whole process group when it leads one
+pub fn terminate_process(pid: u32, group: bool, force: bool) {
+    if group {
+        return terminate_process_group(pid, force);
+    }
+    #[cfg(unix)]
+    {
+        let signal = if force { libc::SIGKILL } else { libc::SIGTERM };
+        unsafe {
+            libc::kill(pid as libc::pid_t, signal);
+        }
+    }
+    // taskkill stops the whole process tree either way
+    #[cfg(not(unix))]
+    terminate_process_group(pid, force);
+}
+
+/// Pass a termination signal razd received on to a command it started
+/// A command leading its own process group gets it for the whole group. A command
+/// in razd's process group already got Ctrl-C from the terminal razd reads from,
+/// so SIGINT is not sent a second time.
+pub fn forward_signal(pid: u32, group: bool, signal: Signal) {
+    if !group && signal == Signal::Interrupt && stdin_is_terminal() {
+        return;
+    }
+    #[cfg(unix)]
+    {
+        let target = if group {
+            -(pid as libc::pid_t)
+        } else {
+            pid as libc::pid_t
+        };
+        unsafe {
+            libc::kill(target, signal.number());
+        }
+    }
+    // Ctrl-C reaches every process attached to the console
+    #[cfg(not(unix))]
+    let _ = pid;
+}
+
+/// Wait for a command in razd's process group, passing termination signals on to it
+/// After a signal the command gets the grace period to exit before it is killed,
+/// then the wait fails with `RazdError::Interrupted`.
+async fn wait_forwarding_signals<T>(
+    wait: impl Future<Output = Result<T>>,
+    pid: Option<u32>,
+) -> Result<T> {
+    let mut signals = signals::listen();
+    tokio::pin!(wait);
+    let signal = tokio::select! {
+        result = &mut wait => return result,
+        signal = signals.next() => signal,
+    };
+
+    if let Some(pid) = pid {
+        forward_signal(pid, false, signal);
+        tokio::select! {
+            _ = &mut wait => return Err(RazdError::interrupted(signal)),
+            _ = tokio::time::sleep(grace_period()) => {}
+            // A second signal does not wait any longer
+            _ = signals.next() => {}
+        }
+        terminate_process(pid, false, true);
+    }
+    let _ = wait.await;
+    Err(RazdError::interrupted(signal))
+}
+
+/// Spawn a shell command line interactively with additional environment variables
+/// With `process_group` the shell leads a new process group (see `terminate_process_group`)
+pub fn spawn_shell_interactive(
+    script: &str,
+    working_dir: Option<&Path>,
+    envs: &[(String, String)],
+    process_group: bool,
+) -> Result<std::process::Child> {
+    let (program, args) = shell_invocation(script);
+    let mut cmd = std::process::Command::new(tools::program_path(program));
+    cmd.args(&args);
+
+    // Inherit current environment, then layer task-specific variables on top
+    cmd.env_clear().envs(std::env::vars_os());
+    cmd.envs(envs.iter().map(|(k, v)| (k, v)));
+    cmd.stdin(std::process::Stdio::inherit());
+    cmd.stdout(terminal_stdout());
+    cmd.stderr(std::process::Stdio::inherit());
+
+    if let Some(dir) = working_dir {
+        cmd.current_dir(dir);
+    }
+    if process_group {
+        isolate_process_group(&mut cmd);
+    }
+
+    log_spawn(program, &args, working_dir, envs);
+    cmd.spawn()
+        .map_err(|e| RazdError::config(format!("Failed to spawn {}: {}", program, e)))
+}
+
+/// Spawn a shell command line with piped output so it can be relayed (see `wait_with_relay`)
+/// With `inherit_stdin` the command still reads from the terminal; concurrent commands
+/// cannot share it and get an empty input instead.
+pub fn spawn_shell_piped(
+    script: &str,
+    working_dir: Option<&Path>,
+    envs: &[(String, String)],
+    process_group: bool,
+    inherit_stdin: bool,
+) -> Result<tokio::process::Child> {
+    let (program, args) = shell_invocation(script);
+    let mut std_cmd = std::process::Command::new(tools::program_path(program));
+    if process_group {
+        isolate_process_group(&mut std_cmd);
+    }
+    let mut cmd = Command::from(std_cmd);
+    cmd.args(&args);
+
+    cmd.env_clear().envs(std::env::vars_os());
+    cmd.envs(envs.iter().map(|(k, v)| (k, v)));
+    cmd.stdin(if inherit_stdin {
+        std::process::Stdio::inherit()
+    } else {
+        std::process::Stdio::null()
+    });
+    cmd.stdout(std::process::Stdio::piped());
+    cmd.stderr(std::process::Stdio::piped());
+
+    if let Some(dir) = working_dir {
+        cmd.current_dir(dir);
+    }
+
+    log_spawn(program, &args, working_dir, envs);
     cmd.spawn()
         .map_err(|e| RazdError::config(format!("Failed to spawn {}: {}", program, e)))
 }
 
+/// Run a shell command line quietly and report whether it exited successfully
+/// Used for checks such as `status:` and `preconditions:`, whose output is not shown
+pub async fn run_shell_check(
+    script: &str,
+    working_dir: Option<&Path>,
+    envs: &[(String, String)],
+) -> Result<bool> {
+    let (program, args) = shell_invocation(script);
+    let mut cmd = Command::new(tools::program_path(program));
+    cmd.args(&args);
+    cmd.env_clear().envs(std::env::vars_os());
+    cmd.envs(envs.iter().map(|(k, v)| (k, v)));
+    cmd.stdin(std::process::Stdio::null());
+    cmd.stdout(std::process::Stdio::null());
+    cmd.stderr(std::process::Stdio::null());
+
+    if let Some(dir) = working_dir {
+        cmd.current_dir(dir);
+    }
+
+    log_spawn(program, &args, working_dir, envs);
+    let status = cmd
+        .status()
+        .await
+        .map_err(|e| RazdError::config(format!("Failed to spawn {}: {}", program, e)))?;
+    Ok(status.success())
+}
+
+/// Output stream of a child process
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Receives every complete line a relayed process prints
+pub type LineSink = Arc<dyn Fn(OutputStream, &str) + Send + Sync>;
+
+/// How relayed output reaches the terminal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Relay {
+    /// Passed through as it arrives, so prompts without a trailing newline still show up
+    Passthrough,
+    /// Every line is tagged with the given label
+    Prefixed(String),
+    /// Not printed; lines only go to the sink (e.g. to be printed later as a block)
+    Quiet,
+}
+
+/// Relay the output of a piped process, then return its exit status
+/// stdout goes to stdout and stderr to stderr; every line is also handed to `sink`.
+pub async fn wait_with_relay(
+    mut child: tokio::process::Child,
+    relay: Relay,
+    sink: Option<LineSink>,
+    program: &str,
+) -> Result<std::process::ExitStatus> {
+    let stdout = child.stdout.take().map(|out| {
+        tokio::spawn(relay_stream(
+            out,
+            OutputStream::Stdout,
+            relay.clone(),
+            sink.clone(),
+        ))
+    });
+    let stderr = child.stderr.take().map(|err| {
+        tokio::spawn(relay_stream(
+            err,
+            OutputStream::Stderr,
+            relay.clone(),
+            sink.clone(),
+        ))
+    });
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| RazdError::config(format!("Failed to wait for {}: {}", program, e)))?;
+
+    // Drain whatever is still buffered before reporting completion
+    for relay in [stdout, stderr].into_iter().flatten() {
+        let _ = relay.await;
+    }
+
+    Ok(status)
+}
+
+/// Copy one output stream of a child process to ours until it closes
+async fn relay_stream(
+    mut reader: impl AsyncRead + Unpin,
+    stream: OutputStream,
+    relay: Relay,
+    sink: Option<LineSink>,
+) {
+    let mut buffer = [0u8; 8192];
+    let mut pending = Vec::new();
+
+    loop {
+        let read = match reader.read(&mut buffer).await {
+            Ok(0) | Err(_) => break,
+            Ok(read) => read,
+        };
+        let chunk = &buffer[..read];
+        if relay == Relay::Passthrough {
+            write_raw(stream, chunk);
+        }
+
+        pending.extend_from_slice(chunk);
+        while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            emit_line(stream, &line[..end], &relay, sink.as_ref());
+        }
+    }
+
+    if !pending.is_empty() {
+        emit_line(stream, &pending, &relay, sink.as_ref());
+    }
+}
+
+/// Print a complete line when output is prefixed and pass it to the sink
+fn emit_line(stream: OutputStream, line: &[u8], relay: &Relay, sink: Option<&LineSink>) {
+    let text = String::from_utf8_lossy(line);
+    let text = text.strip_suffix('\r').unwrap_or(&text);
+    if let Relay::Prefixed(prefix) = relay {
+        match stream {
+            OutputStream::Stdout => println!("{} {}", prefix, text),
+            OutputStream::Stderr => eprintln!("{} {}", prefix, text),
+        }
+    }
+    if let Some(sink) = sink {
+        sink(stream, text);
+    }
+}
+
+/// Write output through unchanged and flush it right away
+fn write_raw(stream: OutputStream, chunk: &[u8]) {
+    use std::io::Write;
+
+    match stream {
+        OutputStream::Stdout => {
+            let mut out = std::io::stdout().lock();
+            let _ = out.write_all(chunk);
+            let _ = out.flush();
+        }
+        OutputStream::Stderr => {
+            let mut err = std::io::stderr().lock();
+            let _ = err.write_all(chunk);
+            let _ = err.flush();
+        }
+    }
+}
+
+/// Future waiting for a spawned command, see `wait_until`
+pub type WaitFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<std::process::ExitStatus>> + Send + 'a>>;
+
+/// Wait for a command, stopping it once `deadline` passes: its process group gets
+/// SIGTERM, then SIGKILL when it is still running after `grace`
+/// Returns None when the command was stopped because of the deadline.
+pub async fn wait_until(
+    mut wait: WaitFuture<'_>,
+    pid: Option<u32>,
+    deadline: Option<tokio::time::Instant>,
+    grace: Duration,
+) -> Option<Result<std::process::ExitStatus>> {
+    let Some(deadline) = deadline else {
+        return Some(wait.await);
+    };
+    if let Ok(result) = tokio::time::timeout_at(deadline, &mut wait).await {
+        return Some(result);
+    }
+
+    if let Some(pid) = pid {
+        terminate_process_group(pid, false);
+        if tokio::time::timeout(grace, &mut wait).await.is_ok() {
+            return None;
+        }
+        terminate_process_group(pid, true);
+    }
+    let _ = wait.await;
+    None
+}
+
+/// Wait for an interactive spawned process and return its exit status
+pub async fn wait_for_status(
+    mut child: std::process::Child,
+    program: &str,
+) -> Result<std::process::ExitStatus> {
+    tokio::task::spawn_blocking(move || child.wait())
+        .await
+        .map_err(|e| RazdError::config(format!("Failed to wait for task: {}", e)))?
+        .map_err(|e| RazdError::config(format!("Failed to wait for {}: {}", program, e)))
+}
+
 /// Wait for a spawned process to complete
 #[allow(unused_mut)]
 pub async fn wait_for_command(mut child: tokio::process::Child, program: &str) -> Result<()> {
-    let output = child
-        .wait_with_output()
-        .await
-        .map_err(|e| RazdError::config(format!("Failed to wait for {}: {}", program, e)))?;
+    let pid = child.id();
+    let output = wait_forwarding_signals(
+        async {
+            child
+                .wait_with_output()
+                .await
+                .map_err(|e| RazdError::config(format!("Failed to wait for {}: {}", program, e)))
+        },
+        pid,
+    )
+    .await?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -66,21 +543,16 @@ pub async fn wait_for_command(mut child: tokio::process::Child, program: &str) -
     // Print stdout if there's output
     let stdout = String::from_utf8_lossy(&output.stdout);
     if !stdout.trim().is_empty() {
-        println!("{}", stdout);
+        output::note(&stdout);
     }
 
     Ok(())
 }
 
 /// Wait for an interactive spawned process to complete
-pub async fn wait_for_command_interactive(
-    mut child: std::process::Child,
-    program: &str,
-) -> Result<()> {
-    let status = tokio::task::spawn_blocking(move || child.wait())
-        .await
-        .map_err(|e| RazdError::config(format!("Failed to wait for task: {}", e)))?
-        .map_err(|e| RazdError::config(format!("Failed to wait for {}: {}", program, e)))?;
+pub async fn wait_for_command_interactive(child: std::process::Child, program: &str) -> Result<()> {
+    let pid = child.id();
+    let status = wait_forwarding_signals(wait_for_status(child, program), Some(pid)).await?;
 
     if !status.success() {
         return Err(RazdError::config(format!(
@@ -117,43 +589,8 @@ pub async fn execute_command_interactive(
     wait_for_command_interactive(child, program).await
 }
 
-/// Check if a command is available in PATH
-pub async fn check_command_available(program: &str) -> bool {
-    // On Windows, also try the .exe extension
-    let exe_name = format!("{}.exe", program);
-    let programs_to_try = if cfg!(windows) {
-        vec![program, exe_name.as_str()]
-    } else {
-        vec![program]
-    };
-
-    for prog in programs_to_try {
-        // Try with --version flag first
-        if let Ok(output) = Command::new(prog)
-            .arg("--version")
-            .env_clear()
-            .envs(std::env::vars())
-            .output()
-            .await
-        {
-            if output.status.success() {
-                return true;
-            }
-        }
-
-        // Fallback: try with -v flag (some tools use this instead)
-        if let Ok(output) = Command::new(prog)
-            .arg("-v")
-            .env_clear()
-            .envs(std::env::vars())
-            .output()
-            .await
-        {
-            if output.status.success() {
-                return true;
-            }
-        }
-    }
-
-    false
+/// Check if a command is available, on PATH or where mise installs tools
+/// Nothing is spawned; see `tools::resolve`.
+pub fn check_command_available(program: &str) -> bool {
+    tools::resolve(program).is_some()
 }
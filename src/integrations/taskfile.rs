@@ -1,8 +1,17 @@
+use crate::config::{Output, OutputMode, Workflow};
+use crate::core::duration::HumanDuration;
+use crate::core::events::{self, Event};
+use crate::core::log;
+use crate::core::signals;
+use crate::core::summary::{self, Phase};
 use crate::core::{output, RazdError, Result};
 use crate::defaults;
+use crate::executor::{logs, plan, watch, Executor, ExecutorOptions};
 use crate::integrations::{mise, process};
-use std::path::Path;
-use std::time::Duration;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 /// Execute task command, trying direct execution first, then mise exec as fallback
 async fn execute_task_command(args: &[&str], working_dir: &Path) -> Result<()> {
@@ -29,7 +38,7 @@ async fn execute_task_command_with_mode(
     interactive: bool,
 ) -> Result<()> {
     // First try direct execution
-    if process::check_command_available("task").await {
+    if process::check_command_available("task") {
         if interactive {
             // Note: task doesn't have --interactive flag, but we use interactive execution
             // to properly handle stdin/stdout for commands that task runs
@@ -74,6 +83,11 @@ pub async fn setup_project_with_path(
     working_dir: &Path,
     custom_path: Option<std::path::PathBuf>,
 ) -> Result<()> {
+    if process::dry_run() {
+        output::info("Dry run: would run `task setup` if a Taskfile is present");
+        return Ok(());
+    }
+
     // Ensure task tool is available
     mise::ensure_tool_available("task", "latest", working_dir).await?;
 
@@ -104,103 +118,369 @@ pub async fn setup_project_with_path(
     Ok(())
 }
 
-/// Execute a workflow task using custom taskfile content
-pub async fn execute_workflow_task(task_name: &str, workflow_content: &str) -> Result<()> {
-    execute_workflow_task_with_mode(task_name, workflow_content, false, None).await
+/// Check whether tasks should be delegated to the external `task` binary
+/// Enabled with the global `--external-task` flag (RAZD_EXTERNAL_TASK=1), and on
+/// Windows when there is no bash or sh to run task commands with
+pub fn use_external_task() -> bool {
+    env::var("RAZD_EXTERNAL_TASK").unwrap_or_default() == "1" || process::posix_shell().is_none()
+}
+
+/// Job limit for the built-in executor from the global `--jobs` flag (RAZD_JOBS)
+fn configured_jobs() -> Option<usize> {
+    env::var("RAZD_JOBS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+}
+
+/// Check whether up-to-date checks are bypassed with the global `--force` flag (RAZD_FORCE=1)
+fn force_run() -> bool {
+    env::var("RAZD_FORCE").unwrap_or_default() == "1"
+}
+
+/// Execute a workflow task
+pub async fn execute_workflow_task(task_name: &str, workflow: &Workflow) -> Result<()> {
+    execute_workflow_task_with_mode(task_name, workflow, false, None).await
 }
 
 /// Execute a workflow task with option for interactive mode
-pub async fn execute_workflow_task_interactive(
-    task_name: &str,
-    workflow_content: &str,
+pub async fn execute_workflow_task_interactive(task_name: &str, workflow: &Workflow) -> Result<()> {
+    execute_workflow_task_with_mode(task_name, workflow, true, None).await
+}
+
+/// Duration from a global flag stored in an environment variable (`--timeout`, `--grace-period`)
+fn configured_duration(name: &str) -> Option<Duration> {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse::<HumanDuration>().ok())
+        .map(HumanDuration::as_duration)
+}
+
+/// Log directory of the current project, unless disabled with the global `--no-log` flag (RAZD_NO_LOG=1)
+fn configured_log_dir() -> Option<PathBuf> {
+    if env::var("RAZD_NO_LOG").unwrap_or_default() == "1" {
+        return None;
+    }
+    let working_dir = env::current_dir().ok()?;
+    logs::project_logs_dir(&working_dir).ok()
+}
+
+/// Output mode from the global `--output` flag (RAZD_OUTPUT), then the Razdfile's `output:`;
+/// by default lines are prefixed when several tasks run in parallel
+/// Grouping markers from the Razdfile are kept when `--output grouped` is given.
+fn configured_output(workflow: &Workflow, task_names: &[String], parallel: bool) -> Output {
+    let configured = workflow.config.output.clone();
+    let flag = env::var("RAZD_OUTPUT")
+        .ok()
+        .and_then(|value| value.parse::<OutputMode>().ok());
+
+    match flag {
+        Some(OutputMode::Grouped) => configured
+            .filter(|output| output.markers().is_some())
+            .unwrap_or(Output::Mode(OutputMode::Grouped)),
+        Some(mode) => Output::Mode(mode),
+        None => configured.unwrap_or(Output::Mode(if parallel && task_names.len() > 1 {
+            OutputMode::Prefixed
+        } else {
+            OutputMode::Interleaved
+        })),
+    }
+}
+
+/// Options for the built-in executor from the global flags and the run mode
+fn executor_options(
+    workflow: &Workflow,
+    task_names: &[String],
+    cli_vars: &[(String, String)],
+    parallel: bool,
+    continue_on_error: bool,
+) -> ExecutorOptions {
+    ExecutorOptions {
+        jobs: configured_jobs(),
+        force: force_run(),
+        parallel,
+        continue_on_error,
+        output: configured_output(workflow, task_names, parallel),
+        vars: cli_vars.to_vec(),
+        log_dir: configured_log_dir(),
+        timeout: configured_duration("RAZD_TIMEOUT"),
+        stop_grace: configured_duration("RAZD_GRACE_PERIOD"),
+        junit: env::var_os("RAZD_JUNIT").map(PathBuf::from),
+        ..Default::default()
+    }
+}
+
+/// Run workflow tasks and rerun them whenever their sources change, until interrupted
+pub async fn watch_workflow_tasks(
+    task_names: &[String],
+    workflow: &Workflow,
+    cli_args: &[String],
+    cli_vars: &[(String, String)],
+    parallel: bool,
+    continue_on_error: bool,
 ) -> Result<()> {
-    execute_workflow_task_with_mode(task_name, workflow_content, true, None).await
+    if use_external_task() {
+        return Err(RazdError::task(
+            "--watch is not supported with --external-task",
+        ));
+    }
+
+    // Nothing changes during a dry run, so one plan covers every rerun
+    if process::dry_run() {
+        return execute_workflow_tasks(
+            task_names,
+            workflow,
+            cli_args,
+            cli_vars,
+            parallel,
+            continue_on_error,
+        )
+        .await;
+    }
+
+    let working_dir = env::current_dir()
+        .map_err(|e| RazdError::task(format!("Failed to get current directory: {}", e)))?;
+
+    output::step(&format!("Watching workflow: {}", task_names.join(", ")));
+
+    watch::run_watch(
+        workflow.clone(),
+        working_dir,
+        executor_options(workflow, task_names, cli_vars, parallel, continue_on_error),
+        task_names.to_vec(),
+        cli_args.to_vec(),
+    )
+    .await
 }
 
-/// Execute a workflow task with CLI arguments
-pub async fn execute_workflow_task_with_args(
-    task_name: &str,
-    workflow_content: &str,
+/// Execute several workflow tasks in one invocation
+/// Tasks run in order, or concurrently with `parallel`; CLI arguments go to the last task
+/// and `KEY=value` variables to every task
+pub async fn execute_workflow_tasks(
+    task_names: &[String],
+    workflow: &Workflow,
     cli_args: &[String],
+    cli_vars: &[(String, String)],
+    parallel: bool,
+    continue_on_error: bool,
 ) -> Result<()> {
-    execute_workflow_task_with_mode(task_name, workflow_content, true, Some(cli_args)).await
+    execute_workflow(
+        task_names,
+        workflow,
+        true,
+        cli_args,
+        cli_vars,
+        parallel,
+        continue_on_error,
+    )
+    .await
 }
 
-/// Execute a workflow task using custom taskfile content with interactive option and CLI args
 async fn execute_workflow_task_with_mode(
     task_name: &str,
-    workflow_content: &str,
+    workflow: &Workflow,
     interactive: bool,
     cli_args: Option<&[String]>,
 ) -> Result<()> {
-    use std::env;
-    use std::fs;
+    execute_workflow(
+        &[task_name.to_string()],
+        workflow,
+        interactive,
+        cli_args.unwrap_or_default(),
+        &[],
+        false,
+        false,
+    )
+    .await
+}
 
+/// Execute workflow tasks natively, or via the external `task` binary when opted in
+async fn execute_workflow(
+    task_names: &[String],
+    workflow: &Workflow,
+    interactive: bool,
+    cli_args: &[String],
+    cli_vars: &[(String, String)],
+    parallel: bool,
+    continue_on_error: bool,
+) -> Result<()> {
     // Get current working directory
     let working_dir = env::current_dir()
         .map_err(|e| RazdError::task(format!("Failed to get current directory: {}", e)))?;
 
-    // Ensure task tool is available
-    mise::ensure_tool_available("task", "latest", &working_dir).await?;
+    let label = task_names.join(", ");
 
-    output::step(&format!("Executing workflow: {}", task_name));
+    if process::dry_run() {
+        return print_workflow_plan(task_names, workflow, working_dir, cli_args, cli_vars);
+    }
 
-    // Create temporary taskfile in system temp directory for task to load.
-    let temp_taskfile = env::temp_dir().join(format!("razd-workflow-{}.yml", task_name));
+    output::step(&format!("Executing workflow: {}", label));
+
+    log::debug("taskfile", || {
+        format!(
+            "Running {} with {} from {}",
+            label,
+            if use_external_task() {
+                "the external `task` binary (--external-task)"
+            } else {
+                "the built-in executor"
+            },
+            workflow.source_display()
+        )
+    });
+    if use_external_task() {
+        if continue_on_error {
+            output::warning("--continue-on-error is not supported with --external-task");
+        }
+        if env::var_os("RAZD_JUNIT").is_some() {
+            output::warning("--junit is not supported with --external-task");
+        }
+        let workflow_content = workflow.to_yaml()?;
+        // go-task runs every task in one process, so the tasks share its outcome
+        for name in task_names {
+            events::emit_if_enabled(|| Event::TaskStarted { task: name.clone() });
+        }
+        let started = Instant::now();
+        let result = execute_external_workflow_task(
+            task_names,
+            &workflow_content,
+            &working_dir,
+            interactive,
+            cli_args,
+            cli_vars,
+            parallel,
+        )
+        .await;
+        for name in task_names {
+            summary::record(
+                Phase::Task,
+                name.as_str(),
+                events::status(&result),
+                started.elapsed(),
+            );
+            events::emit_if_enabled(|| Event::TaskFinished {
+                task: name.clone(),
+                status: events::status(&result),
+                duration_ms: events::millis(started.elapsed()),
+                exit_code: result.as_ref().err().map_or(0, RazdError::exit_code),
+            });
+        }
+        result?;
+    } else {
+        let options = executor_options(workflow, task_names, cli_vars, parallel, continue_on_error);
+        let executor = Executor::new(workflow.clone(), working_dir, options);
+        let result = executor.run_all_interruptible(task_names, cli_args).await;
+        executor.write_report();
+        result?;
+    }
 
-    fs::write(&temp_taskfile, workflow_content)
-        .map_err(|e| RazdError::task(format!("Failed to create temporary taskfile: {}", e)))?;
+    output::success(&format!("Successfully executed workflow: {}", label));
+
+    Ok(())
+}
+
+/// Print what a workflow run would do for `--dry-run`: tools, then every command in order
+fn print_workflow_plan(
+    task_names: &[String],
+    workflow: &Workflow,
+    working_dir: PathBuf,
+    cli_args: &[String],
+    cli_vars: &[(String, String)],
+) -> Result<()> {
+    let tools = mise::planned_tools(&working_dir);
+    if !tools.is_empty() {
+        output::info(&format!(
+            "Mise tools (installed by `mise install`): {}",
+            tools.join(", ")
+        ));
+    }
+
+    output::step(&format!(
+        "Dry run of workflow: {} (nothing will be executed)",
+        task_names.join(", ")
+    ));
+    let options = executor_options(workflow, task_names, cli_vars, false, false);
+    let steps =
+        Executor::new(workflow.clone(), working_dir.clone(), options).plan(task_names, cli_args)?;
+    plan::print_plan(&steps, &working_dir);
+
+    Ok(())
+}
+
+/// Execute a workflow task through the external `task` binary using a temporary taskfile
+async fn execute_external_workflow_task(
+    task_names: &[String],
+    workflow_content: &str,
+    working_dir: &Path,
+    interactive: bool,
+    cli_args: &[String],
+    cli_vars: &[(String, String)],
+    parallel: bool,
+) -> Result<()> {
+    // Ensure task tool is available
+    mise::ensure_tool_available("task", "latest", working_dir).await?;
+
+    // Create temporary taskfile in system temp directory for task to load.
+    let temp_taskfile = TempTaskfile::create(
+        env::temp_dir().join(format!(
+            "razd-workflow-{}.yml",
+            task_names.join("-").replace(':', "_")
+        )),
+        workflow_content,
+    )?;
 
     // Use --dir/-d flag to ensure task executes in project directory, not temp directory
     let mut args = vec![
         "--taskfile",
-        temp_taskfile.to_str().unwrap(),
+        temp_taskfile.path.to_str().unwrap(),
         "--dir",
         working_dir.to_str().unwrap(),
-        task_name,
     ];
-
-    // Add CLI arguments if provided (after task name, with -- separator)
-    let cli_arg_strings: Vec<String>;
-    if let Some(cli_args_slice) = cli_args {
-        if !cli_args_slice.is_empty() {
-            args.push("--");
-            cli_arg_strings = cli_args_slice.to_vec();
-            for arg in &cli_arg_strings {
-                args.push(arg.as_str());
-            }
-        }
+    if parallel {
+        args.push("--parallel");
+    }
+    args.extend(task_names.iter().map(String::as_str));
+
+    // go-task accepts variables as KEY=value arguments as well
+    let var_args: Vec<String> = cli_vars
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    args.extend(var_args.iter().map(String::as_str));
+
+    // Add CLI arguments if provided (after task names, with -- separator)
+    if !cli_args.is_empty() {
+        args.push("--");
+        args.extend(cli_args.iter().map(String::as_str));
     }
 
     // Check if we can execute task directly (allows early cleanup) or need mise exec (keeps file)
-    let result = if process::check_command_available("task").await {
+    let result = if process::check_command_available("task") {
+        log::debug("taskfile", || {
+            format!(
+                "Running `task` directly with {}",
+                temp_taskfile.path.display()
+            )
+        });
         // Direct execution: spawn, wait briefly for file load, cleanup, then wait for completion
         if interactive {
-            let child = process::spawn_command_interactive("task", &args, Some(&working_dir))
-                .inspect_err(|_| {
-                    let _ = fs::remove_file(&temp_taskfile);
-                })?;
+            let child = process::spawn_command_interactive("task", &args, Some(working_dir))?;
 
             // Wait briefly to ensure the process has loaded the file
             tokio::time::sleep(Duration::from_millis(defaults::DEFAULT_SPAWN_DELAY_MS)).await;
 
             // Clean up temporary file immediately after process has had time to load it
-            let _ = fs::remove_file(&temp_taskfile);
+            drop(temp_taskfile);
 
             // Wait for the task process to complete
             process::wait_for_command_interactive(child, "task").await
         } else {
-            let child = process::spawn_command("task", &args, Some(&working_dir))
-                .await
-                .inspect_err(|_| {
-                    let _ = fs::remove_file(&temp_taskfile);
-                })?;
+            let child = process::spawn_command("task", &args, Some(working_dir)).await?;
 
             // Wait briefly to ensure the process has loaded the file
             tokio::time::sleep(Duration::from_millis(defaults::DEFAULT_SPAWN_DELAY_MS)).await;
 
             // Clean up temporary file immediately after process has had time to load it
-            let _ = fs::remove_file(&temp_taskfile);
+            drop(temp_taskfile);
 
             // Wait for the task process to complete
             process::wait_for_command(child, "task").await
@@ -208,27 +488,49 @@ async fn execute_workflow_task_with_mode(
     } else {
         // Fallback via mise exec: file must exist for duration of execution
         // because mise spawns a subshell that then runs task
+        log::debug("taskfile", || {
+            "`task` was not found, falling back to `mise exec task`".to_string()
+        });
         output::step("Executing task via mise...");
         let mut mise_args = vec!["exec", "task", "--", "task"];
         mise_args.extend(&args);
 
         let result = if interactive {
-            process::execute_command_interactive("mise", &mise_args, Some(&working_dir)).await
+            process::execute_command_interactive("mise", &mise_args, Some(working_dir)).await
         } else {
-            process::execute_command("mise", &mise_args, Some(&working_dir)).await
+            process::execute_command("mise", &mise_args, Some(working_dir)).await
         };
 
         // Clean up temporary file after mise exec completes
-        let _ = fs::remove_file(&temp_taskfile);
+        drop(temp_taskfile);
 
         result
     };
 
-    result?;
+    result
+}
+
+/// Temporary taskfile for the external `task` binary
+/// Removed when dropped, including on errors and interruptions, or by the signal
+/// handler when a signal ends razd before that.
+struct TempTaskfile {
+    path: PathBuf,
+}
 
-    output::success(&format!("Successfully executed workflow: {}", task_name));
+impl TempTaskfile {
+    fn create(path: PathBuf, content: &str) -> Result<Self> {
+        fs::write(&path, content)
+            .map_err(|e| RazdError::task(format!("Failed to create temporary taskfile: {}", e)))?;
+        signals::remove_on_exit(&path);
+        Ok(Self { path })
+    }
+}
 
-    Ok(())
+impl Drop for TempTaskfile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+        signals::forget_on_exit(&self.path);
+    }
 }
 
 #[cfg(test)]
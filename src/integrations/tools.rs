@@ -0,0 +1,480 @@
+//! Tool discovery without spawning
+//!
+//! Finds executables the way a shell would, by searching PATH, then in the
+//! places mise puts tools that may not be on PATH yet: its shims, the directory
+//! its installer uses (`~/.local/bin`) and its install directories. Nothing is
+//! spawned to find a tool; versions are only probed with `--version` when they
+//! are shown (`-v` and `razd doctor`), and then with a time limit.
+//!
+//! Found tools are cached for the rest of the process; missing ones are searched
+//! again, so tools mise installs meanwhile are found. Setting `RAZD_TOOL_CACHE_TTL`
+//! (e.g. `1h`) also keeps them in `<data_dir>/tool-cache.json` for that long, so
+//! later runs skip the search as long as the cached executable still exists.
+
+use crate::config::file_tracker::get_data_dir;
+use crate::core::duration::HumanDuration;
+use crate::core::log;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Time a `--version` probe gets before it is killed
+const VERSION_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Where a tool was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    Path,
+    MiseShims,
+    /// Directory of the mise installer, `~/.local/bin`
+    LocalBin,
+    MiseInstalls,
+}
+
+impl Source {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Source::Path => "PATH",
+            Source::MiseShims => "mise shims",
+            Source::LocalBin => "~/.local/bin",
+            Source::MiseInstalls => "mise installs",
+        }
+    }
+}
+
+/// A resolved executable
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tool {
+    pub path: PathBuf,
+    pub source: Source,
+    /// First line of its `--version` output, or the version of its mise install directory
+    pub version: Option<String>,
+}
+
+/// Entry of the on-disk cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedTool {
+    #[serde(flatten)]
+    tool: Tool,
+    /// Seconds since the Unix epoch
+    resolved_at: u64,
+}
+
+/// Tools found during this process
+/// Tools that were not found are searched again, as mise may install them meanwhile.
+static RESOLVED: Mutex<BTreeMap<String, Tool>> = Mutex::new(BTreeMap::new());
+
+/// Whether the on-disk cache was read into `RESOLVED` already
+static DISK_CACHE_LOADED: Mutex<bool> = Mutex::new(false);
+
+/// Find an executable by name
+pub fn resolve(program: &str) -> Option<Tool> {
+    load_disk_cache();
+    if let Some(known) = RESOLVED.lock().ok().and_then(|r| r.get(program).cloned()) {
+        return Some(known);
+    }
+
+    let found = search(program, &search_dirs(program)).map(|(path, source)| Tool {
+        version: match source {
+            Source::MiseInstalls => install_dir_version(&path),
+            _ => None,
+        },
+        path,
+        source,
+    });
+    match found {
+        Some(ref tool) => {
+            if let Ok(mut resolved) = RESOLVED.lock() {
+                resolved.insert(program.to_string(), tool.clone());
+            }
+            store_disk_cache(program, tool);
+            log::debug("process", || {
+                format!(
+                    "Resolved {} to {} from {} ({})",
+                    program,
+                    tool.path.display(),
+                    tool.source.describe(),
+                    version(program).unwrap_or_else(|| "unknown version".to_string())
+                )
+            });
+        }
+        None => log::debug("process", || format!("{} was not found", program)),
+    }
+    // The debug line above may have probed the version already
+    RESOLVED
+        .lock()
+        .ok()
+        .and_then(|r| r.get(program).cloned())
+        .or(found)
+}
+
+/// Version of a tool, probed with `--version` the first time it is asked for
+pub fn version(program: &str) -> Option<String> {
+    let tool = RESOLVED.lock().ok()?.get(program).cloned()?;
+    if tool.version.is_some() {
+        return tool.version;
+    }
+    let version = probe_version(&tool.path);
+    let tool = Tool {
+        version: version.clone(),
+        ..tool
+    };
+    store_disk_cache(program, &tool);
+    if let Ok(mut resolved) = RESOLVED.lock() {
+        resolved.insert(program.to_string(), tool);
+    }
+    version
+}
+
+/// Drop what is known about a tool, e.g. after installing it
+pub fn forget(program: &str) {
+    if let Ok(mut resolved) = RESOLVED.lock() {
+        resolved.remove(program);
+    }
+}
+
+/// Path to spawn for a program: the resolved executable, or the name itself
+/// when it was not found or already is a path
+pub fn program_path(program: &str) -> PathBuf {
+    if program.contains(['/', '\\']) {
+        return PathBuf::from(program);
+    }
+    resolve(program)
+        .map(|tool| tool.path)
+        .unwrap_or_else(|| PathBuf::from(program))
+}
+
+/// Directories searched for a program, in order of preference
+fn search_dirs(program: &str) -> Vec<(PathBuf, Source)> {
+    let mut search_path: Vec<(PathBuf, Source)> = std::env::var_os("PATH")
+        .map(|path| {
+            std::env::split_paths(&path)
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .map(|dir| (dir, Source::Path))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mise_dir = mise_data_dir();
+    if let Some(ref mise_dir) = mise_dir {
+        search_path.push((mise_dir.join("shims"), Source::MiseShims));
+    }
+    if let Some(home) = dirs::home_dir() {
+        search_path.push((home.join(".local").join("bin"), Source::LocalBin));
+    }
+    if let Some(ref mise_dir) = mise_dir {
+        // Newest version first
+        let installs = mise_dir.join("installs").join(program);
+        let mut versions: Vec<PathBuf> = fs::read_dir(&installs)
+            .map(|entries| entries.filter_map(|e| e.ok().map(|e| e.path())).collect())
+            .unwrap_or_default();
+        versions.sort_by_cached_key(|dir| {
+            std::cmp::Reverse(version_key(
+                &dir.file_name().unwrap_or_default().to_string_lossy(),
+            ))
+        });
+        search_path.extend(
+            versions
+                .into_iter()
+                .map(|dir| (dir.join("bin"), Source::MiseInstalls)),
+        );
+    }
+    search_path
+}
+
+/// Data directory of mise: `MISE_DATA_DIR`, or `mise` in the user's data directory
+fn mise_data_dir() -> Option<PathBuf> {
+    std::env::var_os("MISE_DATA_DIR")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("XDG_DATA_HOME").map(|dir| PathBuf::from(dir).join("mise")))
+        .or_else(|| {
+            if cfg!(windows) {
+                dirs::data_local_dir().map(|dir| dir.join("mise"))
+            } else {
+                dirs::home_dir().map(|home| home.join(".local").join("share").join("mise"))
+            }
+        })
+}
+
+/// First executable named `program` in `dirs`
+fn search(program: &str, dirs: &[(PathBuf, Source)]) -> Option<(PathBuf, Source)> {
+    let names = executable_names(program);
+    dirs.iter().find_map(|(dir, source)| {
+        names
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|candidate| is_executable(candidate))
+            .map(|path| (path, *source))
+    })
+}
+
+/// File names a program may have: on Windows with each extension of PATHEXT
+fn executable_names(program: &str) -> Vec<String> {
+    if !cfg!(windows) {
+        return vec![program.to_string()];
+    }
+    let extensions = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".into());
+    std::iter::once(program.to_string())
+        .chain(
+            extensions
+                .split(';')
+                .filter(|ext| !ext.is_empty())
+                .map(|ext| format!("{}{}", program, ext.to_ascii_lowercase())),
+        )
+        .collect()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Sort key of a version such as `22.1.0`: its numbers in order
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+/// Version of an executable inside `installs/<tool>/<version>/bin`
+fn install_dir_version(path: &Path) -> Option<String> {
+    let version_dir = path.parent()?.parent()?;
+    Some(version_dir.file_name()?.to_string_lossy().into_owned())
+}
+
+/// First non-empty line `<path> --version` prints, unless it takes too long
+fn probe_version(path: &Path) -> Option<String> {
+    log::trace("process", || {
+        format!("Probing `{} --version`", path.display())
+    });
+    let mut child = Command::new(path)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() < VERSION_PROBE_TIMEOUT => {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            _ => {
+                log::debug("process", || {
+                    format!("`{} --version` did not finish, killed", path.display())
+                });
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+    let output = child.wait_with_output().ok()?;
+    [output.stdout, output.stderr].iter().find_map(|bytes| {
+        String::from_utf8_lossy(bytes)
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(String::from)
+    })
+}
+
+/// How long entries of the on-disk cache stay valid; None when it is disabled
+pub fn disk_cache_ttl() -> Option<Duration> {
+    std::env::var("RAZD_TOOL_CACHE_TTL")
+        .ok()
+        .and_then(|value| value.parse::<HumanDuration>().ok())
+        .map(HumanDuration::as_duration)
+        .filter(|ttl| !ttl.is_zero())
+}
+
+/// Location of the on-disk cache
+pub fn disk_cache_path() -> Option<PathBuf> {
+    get_data_dir().ok().map(|dir| dir.join("tool-cache.json"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn read_disk_cache(path: &Path) -> BTreeMap<String, CachedTool> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Entries of the on-disk cache that are younger than `ttl` and still exist
+fn fresh_entries(
+    cache: BTreeMap<String, CachedTool>,
+    ttl: Duration,
+    now: u64,
+) -> impl Iterator<Item = (String, Tool)> {
+    cache.into_iter().filter_map(move |(program, entry)| {
+        let age = now.saturating_sub(entry.resolved_at);
+        (age < ttl.as_secs() && is_executable(&entry.tool.path)).then_some((program, entry.tool))
+    })
+}
+
+/// Read the on-disk cache into the process cache, once
+fn load_disk_cache() {
+    let Ok(mut loaded) = DISK_CACHE_LOADED.lock() else {
+        return;
+    };
+    if *loaded {
+        return;
+    }
+    *loaded = true;
+    let (Some(ttl), Some(path)) = (disk_cache_ttl(), disk_cache_path()) else {
+        return;
+    };
+    if let Ok(mut resolved) = RESOLVED.lock() {
+        for (program, tool) in fresh_entries(read_disk_cache(&path), ttl, now_secs()) {
+            log::trace("process", || {
+                format!("{} cached as {}", program, tool.path.display())
+            });
+            resolved.entry(program).or_insert(tool);
+        }
+    }
+}
+
+/// Remember a found tool in the on-disk cache, when it is enabled
+/// Failing to write the cache only costs a search next time.
+fn store_disk_cache(program: &str, tool: &Tool) {
+    let (Some(_), Some(path)) = (disk_cache_ttl(), disk_cache_path()) else {
+        return;
+    };
+    let mut cache = read_disk_cache(&path);
+    cache.insert(
+        program.to_string(),
+        CachedTool {
+            tool: tool.clone(),
+            resolved_at: now_secs(),
+        },
+    );
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(content) = serde_json::to_string_pretty(&cache) {
+        let _ = fs::write(&path, content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    fn write_executable(path: &Path) {
+        use std::os::unix::fs::PermissionsExt;
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "#!/bin/sh\necho 'demo 1.2.3'\n").unwrap();
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_missing_tool_is_found_once_installed() {
+        let temp_dir = TempDir::new().unwrap();
+        let bin = temp_dir.path().join("bin");
+        let mut path: Vec<PathBuf> = std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).collect())
+            .unwrap_or_default();
+        path.push(bin.clone());
+        std::env::set_var("PATH", std::env::join_paths(path).unwrap());
+
+        let program = "razd-test-installed-later";
+        assert!(resolve(program).is_none());
+        write_executable(&bin.join(program));
+        assert_eq!(resolve(program).unwrap().path, bin.join(program));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_search_takes_the_first_executable_in_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let first = temp_dir.path().join("first");
+        let second = temp_dir.path().join("second");
+        fs::create_dir_all(&first).unwrap();
+        // Not executable, so skipped
+        fs::write(first.join("demo"), "").unwrap();
+        write_executable(&second.join("demo"));
+
+        let dirs = vec![
+            (first.clone(), Source::Path),
+            (second.clone(), Source::MiseShims),
+        ];
+        assert_eq!(
+            search("demo", &dirs),
+            Some((second.join("demo"), Source::MiseShims))
+        );
+        assert_eq!(search("missing", &dirs), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_probe_version_reads_the_first_line() {
+        let temp_dir = TempDir::new().unwrap();
+        let program = temp_dir.path().join("demo");
+        write_executable(&program);
+        assert_eq!(probe_version(&program), Some("demo 1.2.3".to_string()));
+    }
+
+    #[test]
+    fn test_install_dirs_sort_by_version_and_name_it() {
+        let mut versions = vec!["9.0.1", "22.1.0", "latest", "22.10.0"];
+        versions.sort_by_cached_key(|v| std::cmp::Reverse(version_key(v)));
+        assert_eq!(versions, ["22.10.0", "22.1.0", "9.0.1", "latest"]);
+
+        let path = Path::new("/data/mise/installs/node/22.1.0/bin/node");
+        assert_eq!(install_dir_version(path), Some("22.1.0".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_disk_cache_entries_expire_and_need_the_executable() {
+        let temp_dir = TempDir::new().unwrap();
+        let program = temp_dir.path().join("demo");
+        write_executable(&program);
+        let entry = |path: &Path, resolved_at| CachedTool {
+            tool: Tool {
+                path: path.to_path_buf(),
+                source: Source::Path,
+                version: None,
+            },
+            resolved_at,
+        };
+        let cache = BTreeMap::from([
+            ("fresh".to_string(), entry(&program, 1_000)),
+            ("old".to_string(), entry(&program, 100)),
+            (
+                "gone".to_string(),
+                entry(&temp_dir.path().join("gone"), 1_000),
+            ),
+        ]);
+
+        let fresh: Vec<String> = fresh_entries(cache, Duration::from_secs(60), 1_030)
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(fresh, ["fresh"]);
+    }
+}
@@ -4,6 +4,7 @@
 pub mod config;
 pub mod core;
 pub mod defaults;
+pub mod executor;
 pub mod integrations;
 
 // Re-export commonly used types
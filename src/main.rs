@@ -2,8 +2,10 @@ mod commands;
 mod config;
 mod core;
 mod defaults;
+mod executor;
 mod integrations;
 
+use crate::core::duration::HumanDuration;
 use clap::{Parser, Subcommand};
 use colored::*;
 use std::path::PathBuf;
@@ -32,10 +34,79 @@ struct Cli {
     #[arg(short = 'y', long, global = true)]
     yes: bool,
 
+    /// File whose value wins when Razdfile.yml and mise.toml (or a version file) changed the same
+    /// entry differently
+    #[arg(long, global = true, value_name = "FILE", value_parser = ["razdfile", "mise"])]
+    prefer: Option<String>,
+
     /// List all available tasks
     #[arg(long, global = true)]
     list: bool,
 
+    /// Run tasks through the external `task` binary instead of the built-in executor
+    #[arg(long, global = true)]
+    external_task: bool,
+
+    /// Maximum number of task commands to run in parallel (unlimited by default)
+    #[arg(short = 'j', long, global = true, value_name = "N")]
+    jobs: Option<usize>,
+
+    /// Run tasks even when their sources are up to date
+    #[arg(long, global = true)]
+    force: bool,
+
+    /// Print the commands, tool installs and syncs that would run without executing anything
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// How output of task commands is shown (overrides `output:` in the Razdfile)
+    #[arg(long, global = true, value_name = "MODE", value_parser = ["interleaved", "prefixed", "grouped"])]
+    output: Option<String>,
+
+    /// Stop tasks that run longer than this (e.g. 90s, 10m), unless they set `timeout:`
+    #[arg(long, global = true, value_name = "DURATION")]
+    timeout: Option<HumanDuration>,
+
+    /// Time stopped commands get to exit after SIGTERM before they are killed
+    #[arg(long, global = true, value_name = "DURATION")]
+    grace_period: Option<HumanDuration>,
+
+    /// Retry git clone, mise install and mise trust this many times when they fail
+    #[arg(long, global = true, value_name = "N")]
+    retries: Option<u32>,
+
+    /// Wait before the first retry of a built-in step, doubled for every further one
+    #[arg(long, global = true, value_name = "DURATION")]
+    retry_delay: Option<HumanDuration>,
+
+    /// Only print warnings and errors
+    #[arg(short = 'q', long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Explain what razd decides and runs on stderr (-vv for even more detail)
+    #[arg(short = 'v', long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Print one JSON event per line on stdout instead of text (for IDEs and CI)
+    #[arg(long, global = true, value_name = "FORMAT", value_parser = ["text", "json"])]
+    format: Option<String>,
+
+    /// Also write the `razd up` summary as JSON to the data directory (or --summary-file)
+    #[arg(long, global = true, value_name = "FORMAT", value_parser = ["text", "json"])]
+    summary: Option<String>,
+
+    /// Write the JSON summary of `razd up` to this file (implies --summary json)
+    #[arg(long, global = true, value_name = "FILE")]
+    summary_file: Option<PathBuf>,
+
+    /// Write a JUnit XML report with one testcase per task that ran
+    #[arg(long, global = true, value_name = "PATH")]
+    junit: Option<PathBuf>,
+
+    /// Do not write task logs
+    #[arg(long, global = true)]
+    no_log: bool,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -43,10 +114,20 @@ struct Cli {
 /// Resolve the configuration file path based on CLI flags
 /// Priority: --razdfile > --taskfile > None (default)
 fn resolve_config_path(cli: &Cli) -> Option<PathBuf> {
-    cli.razdfile
-        .as_ref()
-        .or(cli.taskfile.as_ref())
-        .map(PathBuf::from)
+    let (flag, path) = match (&cli.razdfile, &cli.taskfile) {
+        (Some(path), _) => ("--razdfile", path),
+        (None, Some(path)) => ("--taskfile", path),
+        (None, None) => {
+            core::log::debug("config", || {
+                "No --razdfile or --taskfile given, looking for Razdfile.yml".to_string()
+            });
+            return None;
+        }
+    };
+    core::log::debug("config", || {
+        format!("Using config file {} from {}", path, flag)
+    });
+    Some(PathBuf::from(path))
 }
 
 #[derive(Subcommand)]
@@ -70,6 +151,10 @@ enum Commands {
         /// Output in JSON format
         #[arg(long)]
         json: bool,
+        /// Run the status and precondition commands of every task to report whether it is up
+        /// to date or blocked (trusted projects only)
+        #[arg(long)]
+        check: bool,
     },
     /// Install development tools via mise
     Install,
@@ -81,14 +166,49 @@ enum Commands {
     Build,
     /// Execute any custom task defined in Razdfile.yml
     Run {
-        /// Task name to execute
-        task_name: Option<String>,
-        /// Arguments to pass to the task
-        #[arg(trailing_var_arg = true)]
+        /// Task names to execute, in order, and `KEY=value` variables for them
+        #[arg(value_name = "TASK|KEY=VALUE")]
+        task_names: Vec<String>,
+        /// Arguments after `--` are passed to the last task
+        #[arg(last = true)]
         args: Vec<String>,
         /// List all available tasks instead of running
         #[arg(long)]
         list: bool,
+        /// Run the given tasks concurrently
+        #[arg(short, long)]
+        parallel: bool,
+        /// Keep running the remaining tasks after a failure
+        #[arg(long)]
+        continue_on_error: bool,
+        /// Rerun the tasks whenever their sources (or project files) change
+        #[arg(short, long)]
+        watch: bool,
+    },
+    /// Show the logs of the last runs of a task
+    Logs {
+        /// Task whose logs to show
+        task: String,
+        /// Number of most recent runs to show
+        #[arg(long, value_name = "N", default_value_t = 1)]
+        last: usize,
+        /// Keep printing output as the task runs
+        #[arg(short, long)]
+        follow: bool,
+    },
+    /// Show where git, mise, task and the project's tools are found, and their versions
+    Doctor,
+    /// Sync the mise configuration between Razdfile.yml and mise.toml now
+    Sync {
+        /// Write this file from the other one, whatever changed since the last sync
+        #[arg(long, value_name = "FILE", value_parser = ["mise", "razdfile"])]
+        to: Option<String>,
+        /// Write nothing; fail when Razdfile.yml and mise.toml disagree (for CI)
+        #[arg(long)]
+        check: bool,
+        /// Write nothing; print the entries a sync would change in each file
+        #[arg(long)]
+        diff: bool,
     },
     /// Manage project trust status
     Trust {
@@ -111,42 +231,117 @@ enum Commands {
 
 #[tokio::main]
 async fn main() {
-    // Handle -v flag manually before clap parsing
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() == 2 && (args[1] == "-v" || args[1] == "--version") {
-        println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
-        return;
-    }
-
     let cli = Cli::parse();
 
+    // Commands razd waits for receive SIGINT/SIGTERM/SIGHUP before razd exits
+    crate::core::signals::install();
+
     if let Err(e) = run(cli).await {
-        eprintln!("{} {}", "Error:".red().bold(), e);
-        std::process::exit(1);
+        if crate::core::events::enabled() {
+            crate::core::events::emit(crate::core::events::error(&e));
+        } else {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+        }
+        std::process::exit(e.exit_code());
     }
 }
 
 async fn run(cli: Cli) -> core::Result<()> {
+    // Store output format first so everything below reports as events with --format json
+    std::env::set_var("RAZD_FORMAT", cli.format.as_deref().unwrap_or("text"));
+
+    // Store verbosity; RAZD_LOG can refine it per target (see core::log)
+    let verbosity = match (cli.quiet, cli.verbose) {
+        (true, _) => "warn",
+        (false, 0) => "",
+        (false, 1) => "debug",
+        (false, _) => "trace",
+    };
+    std::env::set_var("RAZD_VERBOSITY", verbosity);
+
     // Store no_sync flag for use by commands
     std::env::set_var("RAZD_NO_SYNC", if cli.no_sync { "1" } else { "0" });
 
     // Store yes flag for use by commands
     std::env::set_var("RAZD_AUTO_YES", if cli.yes { "1" } else { "0" });
 
+    // Store which file wins sync conflicts
+    if let Some(ref prefer) = cli.prefer {
+        std::env::set_var("RAZD_PREFER", prefer);
+    }
+
+    // Store external task runner opt-in for use by commands
+    std::env::set_var(
+        "RAZD_EXTERNAL_TASK",
+        if cli.external_task { "1" } else { "0" },
+    );
+
+    // Store job limit for the built-in executor
+    if let Some(jobs) = cli.jobs {
+        std::env::set_var("RAZD_JOBS", jobs.to_string());
+    }
+
+    // Store force flag to bypass up-to-date checks
+    std::env::set_var("RAZD_FORCE", if cli.force { "1" } else { "0" });
+
+    // Store dry-run flag so commands only describe what they would do
+    std::env::set_var("RAZD_DRY_RUN", if cli.dry_run { "1" } else { "0" });
+
+    // Store output mode for the built-in executor
+    if let Some(ref output) = cli.output {
+        std::env::set_var("RAZD_OUTPUT", output);
+    }
+
+    // Store time limits for the built-in executor
+    if let Some(timeout) = cli.timeout {
+        std::env::set_var("RAZD_TIMEOUT", timeout.to_string());
+    }
+    if let Some(grace_period) = cli.grace_period {
+        std::env::set_var("RAZD_GRACE_PERIOD", grace_period.to_string());
+    }
+
+    // Store retry policy of the built-in steps (clone, mise install, mise trust)
+    if let Some(retries) = cli.retries {
+        std::env::set_var("RAZD_RETRIES", retries.to_string());
+    }
+    if let Some(retry_delay) = cli.retry_delay {
+        std::env::set_var("RAZD_RETRY_DELAY", retry_delay.to_string());
+    }
+
+    // Store how the run summary of `razd up` is reported
+    if let Some(ref summary) = cli.summary {
+        std::env::set_var("RAZD_SUMMARY", summary);
+    }
+    if let Some(ref summary_file) = cli.summary_file {
+        std::env::set_var("RAZD_SUMMARY_FILE", summary_file);
+    }
+
+    // Store JUnit report path; absolute, since `razd up <url>` changes directory
+    if let Some(ref junit) = cli.junit {
+        std::env::set_var("RAZD_JUNIT", std::env::current_dir()?.join(junit));
+    }
+
+    // Store no-log flag so task output is not teed into log files
+    std::env::set_var("RAZD_NO_LOG", if cli.no_log { "1" } else { "0" });
+
     // Resolve custom config path from flags
     let custom_path = resolve_config_path(&cli);
 
     // Handle global --list flag
     if cli.list {
-        return commands::list::execute(false, false, custom_path).await;
+        return commands::list::execute(false, false, false, custom_path).await;
     }
 
     match cli.command {
         Some(Commands::Up { url, name, init }) => {
             commands::up::execute(url.as_deref(), name.as_deref(), init, custom_path).await?;
         }
-        Some(Commands::List { list_all, json }) => {
-            commands::list::execute(list_all, json, custom_path).await?;
+        Some(Commands::List {
+            list_all,
+            json,
+            check,
+        }) => {
+            commands::list::execute(list_all, json, check, custom_path).await?;
         }
         Some(Commands::Install) => {
             commands::install::execute().await?;
@@ -161,21 +356,38 @@ async fn run(cli: Cli) -> core::Result<()> {
             commands::build::execute().await?;
         }
         Some(Commands::Run {
-            task_name,
+            task_names,
             args,
             list,
+            parallel,
+            continue_on_error,
+            watch,
         }) => {
             if list {
-                commands::list::execute(false, false, custom_path).await?;
+                commands::list::execute(false, false, false, custom_path).await?;
             } else {
-                let task = task_name.ok_or_else(|| {
-                    crate::core::error::RazdError::config(
+                if task_names.is_empty() {
+                    return Err(crate::core::error::RazdError::config(
                         "Task name required unless --list is specified".to_string(),
-                    )
-                })?;
-                commands::run::execute(&task, &args, custom_path).await?;
+                    ));
+                }
+                let options = commands::run::RunOptions {
+                    parallel,
+                    continue_on_error,
+                    watch,
+                };
+                commands::run::execute(&task_names, &args, options, custom_path).await?;
             }
         }
+        Some(Commands::Logs { task, last, follow }) => {
+            commands::logs::execute(&task, last, follow).await?;
+        }
+        Some(Commands::Doctor) => {
+            commands::doctor::execute().await?;
+        }
+        Some(Commands::Sync { to, check, diff }) => {
+            commands::sync::execute(to.as_deref(), check, diff, custom_path).await?;
+        }
         Some(Commands::Trust {
             path,
             untrust,
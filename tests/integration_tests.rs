@@ -388,6 +388,105 @@ tasks:
     assert!(json["location"].as_str().unwrap().ends_with("Razdfile.yml"));
 }
 
+#[test]
+fn test_list_runs_checks_only_with_check_flag() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Razdfile.yml"),
+        r#"tasks:
+  done:
+    status:
+      - "true"
+    cmds:
+      - echo done
+  gated:
+    preconditions:
+      - sh: touch precondition-ran && false
+    cmds:
+      - echo gated
+"#,
+    )
+    .unwrap();
+
+    let razd = |args: &[&str]| {
+        Command::cargo_bin("razd")
+            .unwrap()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .env("HOME", temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(razd(&["trust"]).status.success());
+
+    // Listing a trusted project still runs nothing by default
+    let output = razd(&["list", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["tasks"][0]["up_to_date"].is_null());
+    assert!(json["tasks"][0]["status"]["satisfied"].is_null());
+    assert!(!temp_dir.path().join("precondition-ran").exists());
+
+    let output = razd(&["list", "--json", "--check"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["tasks"][0]["up_to_date"], true);
+    assert_eq!(json["tasks"][0]["status"]["satisfied"], true);
+    assert_eq!(json["tasks"][1]["blocked"], true);
+    assert!(temp_dir.path().join("precondition-ran").exists());
+
+    // Text mode agrees with JSON
+    let output = razd(&["list", "--check"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("(up to date)"), "stdout: {}", stdout);
+    assert!(stdout.contains("(blocked)"), "stdout: {}", stdout);
+    let stdout = String::from_utf8_lossy(&razd(&["list"]).stdout).to_string();
+    assert!(!stdout.contains("(up to date)"), "stdout: {}", stdout);
+}
+
+#[test]
+fn test_list_json_reports_checks_without_running_them_untrusted() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Razdfile.yml"),
+        r#"tasks:
+  build:
+    status:
+      - touch status-ran
+    preconditions:
+      - sh: touch precondition-ran
+        msg: never checked
+    cmds:
+      - echo build
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("razd").unwrap();
+    cmd.args(["list", "--json"]);
+    cmd.current_dir(temp_dir.path());
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let task = &json["tasks"][0];
+    assert_eq!(
+        task["status"]["commands"],
+        serde_json::json!(["touch status-ran"])
+    );
+    assert!(task["status"]["satisfied"].is_null());
+    assert_eq!(task["preconditions"][0]["sh"], "touch precondition-ran");
+    assert_eq!(task["preconditions"][0]["msg"], "never checked");
+    assert!(task["preconditions"][0]["satisfied"].is_null());
+    assert!(!temp_dir.path().join("status-ran").exists());
+    assert!(!temp_dir.path().join("precondition-ran").exists());
+}
+
 #[test]
 fn test_list_json_with_internal_tasks() {
     use tempfile::TempDir;
@@ -771,3 +870,652 @@ tasks:
         );
     }
 }
+
+#[cfg(unix)]
+#[test]
+fn test_run_multiple_tasks_in_order() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+
+    let razdfile_content = r"
+version: '3'
+tasks:
+  lint:
+    cmds:
+      - echo linting
+  test:
+    cmds:
+      - echo testing {{.CLI_ARGS}}
+";
+    fs::write(temp_dir.path().join("Razdfile.yml"), razdfile_content).unwrap();
+
+    let mut cmd = Command::cargo_bin("razd").unwrap();
+    cmd.args(["--yes", "--no-sync", "run", "lint", "test", "--", "-race"]);
+    cmd.current_dir(temp_dir.path());
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lint = stdout.find("linting").expect("lint output missing");
+    let test = stdout.find("testing -race").expect("test output missing");
+    assert!(lint < test, "tasks ran out of order: {}", stdout);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_exit_code_reflects_failed_task() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+
+    let razdfile_content = r"
+version: '3'
+tasks:
+  broken:
+    cmds:
+      - exit 7
+  ok:
+    cmds:
+      - echo fine
+";
+    fs::write(temp_dir.path().join("Razdfile.yml"), razdfile_content).unwrap();
+
+    let mut cmd = Command::cargo_bin("razd").unwrap();
+    cmd.args([
+        "--yes",
+        "--no-sync",
+        "run",
+        "--parallel",
+        "--continue-on-error",
+        "broken",
+        "ok",
+    ]);
+    cmd.current_dir(temp_dir.path());
+
+    cmd.assert()
+        .code(7)
+        .stdout(predicate::str::contains("fine"));
+}
+
+#[test]
+fn test_dry_run_prints_plan_without_executing() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+
+    let razdfile_content = r"
+version: '3'
+tasks:
+  setup:
+    cmds:
+      - touch setup-ran
+  default:
+    deps: [setup]
+    cmds:
+      - touch default-ran
+";
+    fs::write(temp_dir.path().join("Razdfile.yml"), razdfile_content).unwrap();
+
+    let mut cmd = Command::cargo_bin("razd").unwrap();
+    cmd.args(["--dry-run", "--no-sync", "run", "default"]);
+    cmd.current_dir(temp_dir.path());
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let setup = stdout
+        .find("$ touch setup-ran")
+        .expect("setup command missing");
+    let default = stdout
+        .find("$ touch default-ran")
+        .expect("default command missing");
+    assert!(setup < default, "plan out of order: {}", stdout);
+    assert!(!temp_dir.path().join("setup-ran").exists());
+    assert!(!temp_dir.path().join("default-ran").exists());
+}
+
+#[test]
+fn test_run_cli_vars_override_razdfile_vars() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+
+    let razdfile_content = r"
+version: '3'
+vars:
+  VERSION: '0.0.0'
+tasks:
+  release:
+    vars:
+      TAG: 'v{{.VERSION}}'
+    cmds:
+      - echo releasing {{.TAG}}
+";
+    fs::write(temp_dir.path().join("Razdfile.yml"), razdfile_content).unwrap();
+
+    let mut cmd = Command::cargo_bin("razd").unwrap();
+    cmd.args(["--yes", "--no-sync", "run", "release", "VERSION=1.2.3"]);
+    cmd.current_dir(temp_dir.path());
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("releasing v1.2.3"), "{}", stdout);
+}
+
+#[test]
+fn test_run_grouped_output_prints_each_task_as_a_block() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let razdfile_content = r#"
+output:
+  group:
+    begin: "::group::{{.TASK}}"
+    end: "::endgroup::"
+tasks:
+  slow:
+    cmds:
+      - echo slow-1; sleep 0.3; echo slow-2
+  fast:
+    cmds:
+      - echo fast-1; sleep 0.1; echo fast-2
+"#;
+    fs::write(temp_dir.path().join("Razdfile.yml"), razdfile_content).unwrap();
+
+    let mut cmd = Command::cargo_bin("razd").unwrap();
+    cmd.args([
+        "--yes",
+        "--no-sync",
+        "--no-log",
+        "run",
+        "--parallel",
+        "slow",
+        "fast",
+    ]);
+    cmd.current_dir(temp_dir.path());
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("::group::fast\nfast-1\nfast-2\n::endgroup::\n::group::slow\nslow-1\nslow-2\n::endgroup::"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_run_prefixed_output_flag_tags_lines() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Razdfile.yml"),
+        "tasks:\n  hello:\n    cmds:\n      - echo hi\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("razd").unwrap();
+    cmd.args(["--yes", "--no-sync", "--output", "prefixed", "run", "hello"]);
+    cmd.current_dir(temp_dir.path()).env("NO_COLOR", "1");
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("[hello] hi"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_timeout_flag_stops_task_with_exit_code_124() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Razdfile.yml"),
+        "tasks:\n  hang:\n    cmds:\n      - sleep 10\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("razd").unwrap();
+    cmd.args([
+        "--yes",
+        "--no-sync",
+        "--no-log",
+        "--timeout",
+        "300ms",
+        "run",
+        "hang",
+    ]);
+    cmd.current_dir(temp_dir.path());
+    cmd.assert()
+        .code(124)
+        .stderr(predicate::str::contains("Task 'hang' timed out after"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_sigterm_stops_commands_runs_cleanup_and_exits_143() {
+    use std::process::Stdio;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Razdfile.yml"),
+        "tasks:\n  serve:\n    cmds:\n      - defer: touch cleaned\n      - touch started; sleep 10\n",
+    )
+    .unwrap();
+
+    let mut child = Command::cargo_bin("razd")
+        .unwrap()
+        .args(["--yes", "--no-sync", "--no-log", "run", "serve"])
+        .current_dir(temp_dir.path())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let started = Instant::now();
+    while !temp_dir.path().join("started").exists() {
+        assert!(
+            started.elapsed() < Duration::from_secs(10),
+            "task never started"
+        );
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    Command::new("kill")
+        .args(["-TERM", &child.id().to_string()])
+        .status()
+        .unwrap();
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(143));
+    assert!(started.elapsed() < Duration::from_secs(8));
+    assert!(temp_dir.path().join("cleaned").exists());
+}
+
+#[test]
+fn test_logs_shows_last_task_run() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let home = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Razdfile.yml"),
+        "tasks:\n  greet:\n    cmds:\n      - echo hello from greet\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("razd").unwrap();
+    cmd.args(["logs", "greet"]);
+    cmd.current_dir(temp_dir.path()).env("HOME", home.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("No logs for task 'greet'"));
+
+    let mut cmd = Command::cargo_bin("razd").unwrap();
+    cmd.args(["--yes", "--no-sync", "run", "greet"]);
+    cmd.current_dir(temp_dir.path()).env("HOME", home.path());
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("razd").unwrap();
+    cmd.args(["logs", "greet"]);
+    cmd.current_dir(temp_dir.path()).env("HOME", home.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("[cmd] echo hello from greet"))
+        .stdout(predicate::str::contains("[out] hello from greet"))
+        .stdout(predicate::str::contains("[exit] 0"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_format_json_prints_one_event_per_line() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Razdfile.yml"),
+        "tasks:\n  check:\n    cmds:\n      - echo hello\n      - exit 3\n",
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("razd")
+        .unwrap()
+        .args(["--yes", "--no-sync", "--format", "json", "run", "check"])
+        .current_dir(temp_dir.path())
+        .env("HOME", temp_dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+
+    let events: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("every stdout line is a JSON event"))
+        .collect();
+    let kinds: Vec<&str> = events
+        .iter()
+        .map(|event| event["event"].as_str().unwrap())
+        .collect();
+    for kind in [
+        "trust",
+        "task_started",
+        "command",
+        "output",
+        "task_finished",
+    ] {
+        assert!(kinds.contains(&kind), "no {} event in {:?}", kind, kinds);
+    }
+
+    let output_event = events.iter().find(|e| e["event"] == "output").unwrap();
+    assert_eq!(output_event["line"], "hello");
+    let finished = events
+        .iter()
+        .find(|e| e["event"] == "task_finished")
+        .unwrap();
+    assert_eq!(finished["status"], "failed");
+    assert_eq!(finished["exit_code"], 3);
+    assert!(finished["duration_ms"].is_u64());
+    let error = events.last().unwrap();
+    assert_eq!(error["event"], "error");
+    assert_eq!(error["kind"], "task_failed");
+}
+
+#[test]
+fn test_verbosity_flags_control_stderr_tracing() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Razdfile.yml"),
+        "tasks:\n  hello:\n    cmds:\n      - echo hello\n",
+    )
+    .unwrap();
+
+    let run = |flag: &str| {
+        Command::cargo_bin("razd")
+            .unwrap()
+            .args(["--yes", "--no-sync", "--no-log", flag, "run", "hello"])
+            .current_dir(temp_dir.path())
+            .env("HOME", temp_dir.path())
+            .env_remove("RAZD_LOG")
+            .output()
+            .unwrap()
+    };
+
+    let verbose = run("-v");
+    assert!(verbose.status.success());
+    let stderr = String::from_utf8_lossy(&verbose.stderr);
+    assert!(stderr.contains("[debug config]"), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("[debug process] Spawning"),
+        "stderr: {}",
+        stderr
+    );
+    assert!(!String::from_utf8_lossy(&verbose.stdout).contains("[debug"));
+
+    let quiet = run("-q");
+    assert!(quiet.status.success());
+    let stdout = String::from_utf8_lossy(&quiet.stdout);
+    assert!(stdout.contains("hello"));
+    assert!(!stdout.contains("Running task"), "stdout: {}", stdout);
+    assert!(!String::from_utf8_lossy(&quiet.stderr).contains("[debug"));
+}
+
+#[test]
+fn test_up_summary_file_times_every_task() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Razdfile.yml"),
+        "tasks:\n  default:\n    deps: [prepare]\n    cmds:\n      - exit 4\n  prepare:\n    cmds:\n      - echo ready\n",
+    )
+    .unwrap();
+    let summary_path = temp_dir.path().join("summary.json");
+
+    let output = Command::cargo_bin("razd")
+        .unwrap()
+        .args(["--yes", "--no-sync", "--no-log", "up", "--summary-file"])
+        .arg(&summary_path)
+        .current_dir(temp_dir.path())
+        .env("HOME", temp_dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(4));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Summary:"), "stdout: {}", stdout);
+
+    let summary: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&summary_path).unwrap()).unwrap();
+    assert_eq!(summary["status"], "failed");
+    let tasks: Vec<(&str, &str)> = summary["steps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|step| step["phase"] == "task")
+        .map(|step| {
+            (
+                step["name"].as_str().unwrap(),
+                step["status"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(tasks, [("prepare", "succeeded"), ("default", "failed")]);
+}
+
+#[test]
+fn test_junit_report_has_a_testcase_per_task() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Razdfile.yml"),
+        "tasks:\n  ci:\n    deps: [lint, other-os]\n    cmds:\n      - task: test\n  lint:\n    cmds:\n      - echo linted\n  other-os:\n    platforms: [plan9]\n    cmds:\n      - echo never\n  test:\n    cmds:\n      - echo broken >&2\n      - exit 5\n",
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("razd")
+        .unwrap()
+        .args([
+            "--yes",
+            "--no-sync",
+            "run",
+            "ci",
+            "--junit",
+            "reports/junit.xml",
+        ])
+        .current_dir(temp_dir.path())
+        .env("HOME", temp_dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(5));
+
+    let xml = fs::read_to_string(temp_dir.path().join("reports/junit.xml")).unwrap();
+    assert!(
+        xml.contains("tests=\"4\" failures=\"2\" errors=\"0\" skipped=\"1\""),
+        "{}",
+        xml
+    );
+    assert!(xml.contains("<testcase name=\"lint\""));
+    assert!(xml.contains("<system-out>linted\n</system-out>"));
+    assert!(xml.contains("<skipped message=\"not available on"));
+    assert!(xml.contains("<system-err>broken\n</system-err>"));
+    assert!(xml.contains("exited with code 5"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_doctor_reports_resolved_tools() {
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let bin = temp_dir.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    for (tool, version) in [("git", "git version 9.9.9"), ("mise", "2099.1.0")] {
+        let path = bin.join(tool);
+        fs::write(&path, format!("#!/bin/sh\necho '{}'\n", version)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    let doctor = |path: &std::path::Path| {
+        Command::cargo_bin("razd")
+            .unwrap()
+            .arg("doctor")
+            .current_dir(temp_dir.path())
+            .env("HOME", temp_dir.path())
+            .env("PATH", path)
+            .env_remove("MISE_DATA_DIR")
+            .env_remove("XDG_DATA_HOME")
+            .output()
+            .unwrap()
+    };
+
+    let output = doctor(&bin);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+    assert!(stdout.contains(&format!(
+        "{} (git version 9.9.9, from PATH)",
+        bin.join("git").display()
+    )));
+    assert!(stdout.contains("2099.1.0"));
+
+    fs::remove_file(bin.join("mise")).unwrap();
+    let output = doctor(&bin);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Missing required tool: mise"));
+}
+
+#[test]
+fn test_sync_check_fails_on_drift_until_synced() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Razdfile.yml"),
+        "version: '3'\nmise:\n  tools:\n    node: '22'\ntasks:\n  default:\n    cmds:\n      - echo hi\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("mise.toml"),
+        "# pinned\n[tools]\nnode = \"20\"\n",
+    )
+    .unwrap();
+
+    let sync = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("razd").unwrap();
+        cmd.arg("--yes")
+            .arg("sync")
+            .args(args)
+            .current_dir(temp_dir.path())
+            .env("HOME", temp_dir.path());
+        cmd
+    };
+
+    sync(&["--check", "--diff"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("tools.node  20 → 22"))
+        .stderr(predicate::str::contains("out of sync"));
+    // Neither --check nor --diff writes
+    assert!(fs::read_to_string(temp_dir.path().join("mise.toml"))
+        .unwrap()
+        .contains("node = \"20\""));
+
+    sync(&["--to", "mise"]).assert().success();
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("mise.toml")).unwrap(),
+        "# pinned\n[tools]\nnode = \"22\"\n"
+    );
+
+    sync(&["--check"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("in sync"));
+}
+
+#[test]
+fn test_sync_uses_directory_of_razdfile_flag() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("app");
+    fs::create_dir(&project).unwrap();
+    fs::write(
+        project.join("Razdfile.yml"),
+        "version: '3'\nmise:\n  tools:\n    node: '22'\ntasks: {}\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("razd").unwrap();
+    cmd.args([
+        "--yes",
+        "--razdfile",
+        "app/Razdfile.yml",
+        "sync",
+        "--to",
+        "mise",
+    ])
+    .current_dir(temp_dir.path())
+    .env("HOME", temp_dir.path());
+    cmd.assert().success();
+    assert_eq!(
+        fs::read_to_string(project.join("mise.toml")).unwrap(),
+        "[tools]\nnode = \"22\"\n"
+    );
+    assert!(!temp_dir.path().join("mise.toml").exists());
+
+    fs::write(project.join("tasks.yml"), "version: '3'\ntasks: {}\n").unwrap();
+    let mut cmd = Command::cargo_bin("razd").unwrap();
+    cmd.args(["--razdfile", "app/tasks.yml", "sync"])
+        .current_dir(temp_dir.path())
+        .env("HOME", temp_dir.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("is not a Razdfile.yml"));
+}
+
+#[test]
+fn test_short_v_is_verbose_and_version_is_long_flag() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("Razdfile.yml"),
+        "tasks:\n  default:\n    cmds:\n      - echo hello-default\n",
+    )
+    .unwrap();
+
+    let razd = |args: &[&str]| {
+        Command::cargo_bin("razd")
+            .unwrap()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .env("HOME", temp_dir.path())
+            .env_remove("RAZD_LOG")
+            .output()
+            .unwrap()
+    };
+
+    // `razd -v` runs the default workflow verbosely
+    assert!(razd(&["trust"]).status.success());
+    let verbose = razd(&["-v"]);
+    assert!(verbose.status.success());
+    assert!(String::from_utf8_lossy(&verbose.stdout).contains("hello-default"));
+    assert!(String::from_utf8_lossy(&verbose.stderr).contains("[debug"));
+
+    let version = razd(&["--version"]);
+    assert!(version.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&version.stdout).trim(),
+        format!("razd {}", env!("CARGO_PKG_VERSION"))
+    );
+}
@@ -177,6 +177,7 @@ node = "18"
         no_sync: false,
         auto_approve: true,
         create_backups: true,
+        prefer: None,
     };
 
     let manager = MiseSyncManager::new(temp_dir.path().to_path_buf(), config);
@@ -222,6 +223,7 @@ node = "20"
         no_sync: false,
         auto_approve: true,
         create_backups: true,
+        prefer: None,
     };
 
     let manager = MiseSyncManager::new(temp_dir.path().to_path_buf(), config);
@@ -265,6 +267,7 @@ node = "18"
         no_sync: false,
         auto_approve: true,
         create_backups: true,
+        prefer: None,
     };
 
     let manager = MiseSyncManager::new(temp_dir.path().to_path_buf(), config);
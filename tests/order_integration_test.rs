@@ -32,6 +32,7 @@ node = "https://github.com/asdf-vm/asdf-nodejs.git"
             vars: None,
             silent: None,
             platforms: None,
+            ..Default::default()
         },
     );
     tasks.insert(
@@ -45,6 +46,7 @@ node = "https://github.com/asdf-vm/asdf-nodejs.git"
             vars: None,
             silent: None,
             platforms: None,
+            ..Default::default()
         },
     );
     tasks.insert(
@@ -58,6 +60,7 @@ node = "https://github.com/asdf-vm/asdf-nodejs.git"
             vars: None,
             silent: None,
             platforms: None,
+            ..Default::default()
         },
     );
     tasks.insert(
@@ -71,6 +74,7 @@ node = "https://github.com/asdf-vm/asdf-nodejs.git"
             vars: None,
             silent: None,
             platforms: None,
+            ..Default::default()
         },
     );
 
@@ -79,6 +83,10 @@ node = "https://github.com/asdf-vm/asdf-nodejs.git"
         mise: None,
         env: None,
         vars: None,
+        dotenv: None,
+        includes: None,
+        output: None,
+        version_files: None,
         tasks,
     };
 
@@ -104,6 +112,7 @@ node = "https://github.com/asdf-vm/asdf-nodejs.git"
         no_sync: false,
         auto_approve: true,
         create_backups: false,
+        prefer: None,
     };
 
     let manager = MiseSyncManager::new(project_root.to_path_buf(), config);
@@ -35,6 +35,7 @@ mise:
         no_sync: false,
         auto_approve: true,
         create_backups: false,
+        prefer: None,
     };
     let manager = MiseSyncManager::new(project_root.to_path_buf(), config);
     let result = manager.check_and_sync_if_needed().unwrap();
@@ -77,6 +78,7 @@ python = "https://github.com/asdf-community/asdf-python.git"
         no_sync: false,
         auto_approve: true,
         create_backups: false,
+        prefer: None,
     };
     let manager = MiseSyncManager::new(project_root.to_path_buf(), config);
     let result = manager.check_and_sync_if_needed();
@@ -142,6 +144,7 @@ mise:
         no_sync: false,
         auto_approve: true,
         create_backups: false,
+        prefer: None,
     };
     let manager = MiseSyncManager::new(project_root.to_path_buf(), config.clone());
     manager.check_and_sync_if_needed().unwrap();
@@ -184,6 +187,7 @@ node = "18"
         no_sync: false,
         auto_approve: true,
         create_backups: false,
+        prefer: None,
     };
     let manager = MiseSyncManager::new(project_root.to_path_buf(), config);
     let _ = manager.check_and_sync_if_needed();
@@ -305,6 +309,7 @@ mise:
         no_sync: false,
         auto_approve: true,
         create_backups: false,
+        prefer: None,
     };
     let manager = MiseSyncManager::new(project_root.to_path_buf(), config);
     manager.check_and_sync_if_needed().unwrap();